@@ -0,0 +1,250 @@
+//! Refuse to run queries which aren't in the `queries.lock.json` manifest.
+//!
+//! The `gelx generate` command writes a manifest of every query the generated
+//! code can run, keyed by the SHA-256 hash of the query text which is also the
+//! `QUERY_HASH` of the generated modules. Wrapping an executor with
+//! [`Allowlisted`] rejects every other query string before it reaches the
+//! database, so no ad-hoc queries run in production.
+//!
+//! ```rust,ignore
+//! use std::sync::Arc;
+//!
+//! use gelx::Allowlisted;
+//! use gelx::QueryAllowlist;
+//!
+//! let allowlist = QueryAllowlist::from_manifest(include_str!("../queries.lock.json"))?;
+//! let mut executor = Allowlisted::new(client, Arc::new(allowlist));
+//!
+//! let user = select_user::execute(&mut executor, &props).await?;
+//! ```
+//!
+//! The generated functions which take a `Client`, e.g. `query`, `query_json`,
+//! `query_stream`, `query_bulk` and the `paginate` functions, don't run with a
+//! wrapped executor. With `features = { allowlist = true }` they check their
+//! `QUERY_HASH` against the allowlist which is installed once for the whole
+//! process instead. The queries derived from a query, e.g. the `QUERY_STREAM`,
+//! are allowed together with it.
+//!
+//! ```rust,ignore
+//! gelx::allowlist::install(Arc::new(allowlist))?;
+//!
+//! let user = select_user::query(&client, &props).await?;
+//! ```
+
+use std::collections::BTreeMap;
+use std::collections::HashSet;
+use std::fmt::Write;
+use std::future::Future;
+use std::sync::Arc;
+use std::sync::OnceLock;
+
+use gel_errors::ClientError;
+use gel_errors::Error;
+use gel_errors::ErrorKind;
+use gel_protocol::QueryResult;
+use gel_protocol::query_arg::QueryArgs;
+use serde::Deserialize;
+use serde_json::Value;
+use sha2::Digest;
+use sha2::Sha256;
+
+use crate::Executor;
+use crate::Modifying;
+
+static INSTALLED: OnceLock<Arc<QueryAllowlist>> = OnceLock::new();
+
+/// Install the allowlist which is checked by the generated functions that
+/// take a `Client` when the code is generated with `features = { allowlist =
+/// true }`. The allowlist can only be installed once.
+pub fn install(allowlist: Arc<QueryAllowlist>) -> Result<(), Error> {
+	INSTALLED
+		.set(allowlist)
+		.map_err(|_| ClientError::with_message("an allowlist is already installed"))
+}
+
+/// The allowlist installed with [`install`].
+pub fn installed() -> Option<&'static QueryAllowlist> {
+	INSTALLED.get().map(AsRef::as_ref)
+}
+
+/// Returns an error when the query with the `hash`, e.g. the `QUERY_HASH` of a
+/// generated module, isn't in the installed allowlist. Every query is refused
+/// until an allowlist is installed.
+pub fn check_hash(hash: &str) -> Result<(), Error> {
+	match installed() {
+		Some(allowlist) => allowlist.check_hash(hash),
+		None => {
+			Err(ClientError::with_message(
+				"no allowlist is installed, see `gelx::allowlist::install`",
+			))
+		}
+	}
+}
+
+/// The hex encoded SHA-256 of the query text which identifies the query in the
+/// manifest. It matches the `QUERY_HASH` of the generated modules.
+pub fn query_hash(query: &str) -> String {
+	let mut hash = String::with_capacity(64);
+
+	for byte in Sha256::digest(query.as_bytes()) {
+		let _ = write!(hash, "{byte:02x}");
+	}
+
+	hash
+}
+
+/// The hashes of the queries which are allowed to run.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct QueryAllowlist {
+	hashes: HashSet<String>,
+}
+
+#[derive(Deserialize)]
+struct Manifest {
+	queries: BTreeMap<String, Value>,
+}
+
+impl QueryAllowlist {
+	/// Allow the queries of a `queries.lock.json` manifest.
+	pub fn from_manifest(json: &str) -> Result<Self, Error> {
+		let manifest: Manifest = serde_json::from_str(json).map_err(ClientError::with_source)?;
+
+		Ok(Self::from_hashes(manifest.queries.into_keys()))
+	}
+
+	/// Allow the queries with the provided hashes, e.g. the `QUERY_HASH`
+	/// constants of the generated modules.
+	pub fn from_hashes(hashes: impl IntoIterator<Item = impl Into<String>>) -> Self {
+		Self {
+			hashes: hashes.into_iter().map(Into::into).collect(),
+		}
+	}
+
+	/// The number of allowed queries.
+	pub fn len(&self) -> usize {
+		self.hashes.len()
+	}
+
+	/// Whether no queries are allowed.
+	pub fn is_empty(&self) -> bool {
+		self.hashes.is_empty()
+	}
+
+	/// Whether the `query` is allowed to run.
+	pub fn contains(&self, query: &str) -> bool {
+		self.hashes.contains(&query_hash(query))
+	}
+
+	/// Returns an error when the `query` isn't allowed to run.
+	pub fn check(&self, query: &str) -> Result<(), Error> {
+		self.check_hash(&query_hash(query))
+	}
+
+	/// Returns an error when the query with the `hash` isn't allowed to run.
+	pub fn check_hash(&self, hash: &str) -> Result<(), Error> {
+		if self.hashes.contains(hash) {
+			Ok(())
+		} else {
+			Err(ClientError::with_message(format!(
+				"the query with the hash `{hash}` isn't in the allowlist"
+			)))
+		}
+	}
+}
+
+/// An executor which only runs the queries of a [`QueryAllowlist`].
+#[derive(Debug, Clone)]
+pub struct Allowlisted<E> {
+	executor: E,
+	allowlist: Arc<QueryAllowlist>,
+}
+
+impl<E> Allowlisted<E> {
+	pub fn new(executor: E, allowlist: Arc<QueryAllowlist>) -> Self {
+		Self {
+			executor,
+			allowlist,
+		}
+	}
+
+	/// The wrapped executor.
+	pub fn executor(&self) -> &E {
+		&self.executor
+	}
+
+	/// The queries which are allowed to run.
+	pub fn allowlist(&self) -> &QueryAllowlist {
+		&self.allowlist
+	}
+}
+
+impl<E: Executor> Executor for Allowlisted<E> {
+	fn execute<A>(
+		&mut self,
+		query: &str,
+		arguments: &A,
+	) -> impl Future<Output = Result<(), Error>> + Send
+	where
+		A: QueryArgs + 'static,
+	{
+		let future = self
+			.allowlist
+			.check(query)
+			.map(|()| self.executor.execute(query, arguments));
+
+		async move { future?.await }
+	}
+
+	fn query<R, A>(
+		&mut self,
+		query: &str,
+		arguments: &A,
+	) -> impl Future<Output = Result<Vec<R>, Error>> + Send
+	where
+		A: QueryArgs + 'static,
+		R: QueryResult + Send + 'static,
+	{
+		let future = self
+			.allowlist
+			.check(query)
+			.map(|()| self.executor.query(query, arguments));
+
+		async move { future?.await }
+	}
+
+	fn query_single<R, A>(
+		&mut self,
+		query: &str,
+		arguments: &A,
+	) -> impl Future<Output = Result<Option<R>, Error>> + Send
+	where
+		A: QueryArgs + 'static,
+		R: QueryResult + Send + 'static,
+	{
+		let future = self
+			.allowlist
+			.check(query)
+			.map(|()| self.executor.query_single(query, arguments));
+
+		async move { future?.await }
+	}
+
+	fn query_required_single<R, A>(
+		&mut self,
+		query: &str,
+		arguments: &A,
+	) -> impl Future<Output = Result<R, Error>> + Send
+	where
+		A: QueryArgs + 'static,
+		R: QueryResult + Send + 'static,
+	{
+		let future = self
+			.allowlist
+			.check(query)
+			.map(|()| self.executor.query_required_single(query, arguments));
+
+		async move { future?.await }
+	}
+}
+
+impl<E: Modifying> Modifying for Allowlisted<E> {}
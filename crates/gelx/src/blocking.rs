@@ -0,0 +1,151 @@
+//! Run the generated queries from synchronous code.
+//!
+//! The generated `query_blocking` and `transaction_blocking` functions have
+//! the same signatures as the `query` and `transaction` functions without
+//! `async`. They run the queries on the runtime owned by a
+//! [`BlockingClient`].
+//!
+//! ```rust,ignore
+//! use gelx::BlockingClient;
+//!
+//! let client = BlockingClient::connect()?;
+//! let user = select_user::query_blocking(&client, &props)?;
+//!
+//! client.transaction(|conn| {
+//! 	insert_user::transaction_blocking(conn, &props)?;
+//! 	remove_user::transaction_blocking(conn, &props)
+//! })?;
+//! ```
+
+use std::future::Future;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::PoisonError;
+
+use gel_errors::ClientError;
+use gel_errors::Error;
+use gel_errors::ErrorKind;
+use gel_tokio::Client;
+use gel_tokio::Transaction;
+use tokio::runtime::Builder;
+use tokio::runtime::Handle;
+use tokio::runtime::Runtime;
+use tokio::runtime::RuntimeFlavor;
+
+/// A gel client which owns the tokio runtime used to run its queries.
+///
+/// Cloning the client shares the runtime and the connection pool.
+#[derive(Debug, Clone)]
+pub struct BlockingClient {
+	client: Client,
+	runtime: Arc<Runtime>,
+}
+
+impl BlockingClient {
+	/// Create a runtime for the provided client.
+	pub fn new(client: Client) -> Result<Self, Error> {
+		let runtime = Builder::new_multi_thread()
+			.enable_all()
+			.build()
+			.map_err(ClientError::with_source)?;
+
+		Self::with_runtime(client, Arc::new(runtime))
+	}
+
+	/// Run the client on an existing runtime. The runtime must be multi
+	/// threaded since [`BlockingClient::transaction`] blocks on the queries of
+	/// the transaction from a thread of the runtime.
+	pub fn with_runtime(client: Client, runtime: Arc<Runtime>) -> Result<Self, Error> {
+		if runtime.handle().runtime_flavor() == RuntimeFlavor::CurrentThread {
+			return Err(ClientError::with_message(
+				"the runtime of a `BlockingClient` must be multi threaded",
+			));
+		}
+
+		Ok(Self { client, runtime })
+	}
+
+	/// Create a client with the configuration of the environment, e.g. the
+	/// `gel.toml` of the project, and connect to the instance.
+	pub fn connect() -> Result<Self, Error> {
+		let runtime = Builder::new_multi_thread()
+			.enable_all()
+			.build()
+			.map_err(ClientError::with_source)?;
+		let client = runtime.block_on(gel_tokio::create_client())?;
+
+		Self::with_runtime(client, Arc::new(runtime))
+	}
+
+	/// The async client, e.g. to change its options with
+	/// [`Client::with_globals`].
+	pub fn client(&self) -> &Client {
+		&self.client
+	}
+
+	/// Use a different async client with the same runtime.
+	#[must_use]
+	pub fn with_client(&self, client: Client) -> Self {
+		Self {
+			client,
+			runtime: self.runtime.clone(),
+		}
+	}
+
+	/// Block the current thread until the future completes.
+	///
+	/// This panics when it is called from an async context.
+	pub fn block_on<F: Future>(&self, future: F) -> F::Output {
+		self.runtime.block_on(future)
+	}
+
+	/// Run the `body` in a transaction. The transaction is retried with the
+	/// retry options of the client, so the `body` may run more than once.
+	pub fn transaction<T, F>(&self, body: F) -> Result<T, Error>
+	where
+		F: FnMut(&mut BlockingTransaction<'_>) -> Result<T, Error>,
+	{
+		let body = Mutex::new(body);
+		let handle = self.runtime.handle();
+
+		self.runtime
+			.block_on(self.client.transaction(|mut transaction| {
+				let body = &body;
+
+				async move {
+					// The body blocks on the queries of the transaction, which isn't allowed
+					// on a thread which drives the runtime.
+					tokio::task::block_in_place(|| {
+						let mut body = body.lock().unwrap_or_else(PoisonError::into_inner);
+						let mut conn = BlockingTransaction {
+							transaction: &mut transaction,
+							handle,
+						};
+
+						body(&mut conn)
+					})
+				}
+			}))
+	}
+}
+
+/// A transaction started by [`BlockingClient::transaction`].
+#[derive(Debug)]
+pub struct BlockingTransaction<'a> {
+	transaction: &'a mut Transaction,
+	handle: &'a Handle,
+}
+
+impl BlockingTransaction<'_> {
+	/// The handle of the runtime and the async transaction. The generated
+	/// `transaction_blocking` functions block on the `transaction` functions
+	/// with these.
+	///
+	/// ```rust,ignore
+	/// let (handle, conn) = conn.split();
+	/// handle.block_on(insert_user::transaction(conn, &props))?;
+	/// ```
+	pub fn split(&mut self) -> (&Handle, &mut Transaction) {
+		(self.handle, self.transaction)
+	}
+}
@@ -0,0 +1,82 @@
+//! Insert many objects in a single round trip.
+//!
+//! The generated `query_bulk` functions serialize a batch of inputs to a JSON
+//! array which is passed as the `$items` argument of the bulk query. The query
+//! runs the original query once for every item with
+//! `for item in json_array_unpack(<json>$items) union (...)`.
+//!
+//! ```rust,ignore
+//! let users = insert_user::query_bulk(&client, &inputs).await?;
+//! ```
+
+use gel_errors::ClientEncodingError;
+use gel_errors::Error;
+use gel_errors::ErrorKind;
+use gel_protocol::model::Json;
+use gel_protocol::query_arg::Encoder;
+use gel_protocol::query_arg::QueryArgs;
+use serde::Serialize;
+use serde_json::Map;
+use serde_json::Value;
+
+/// The name of the query parameter with the JSON array of items.
+pub const ITEMS_PARAM: &str = "items";
+
+/// The default number of items inserted by each query.
+pub const DEFAULT_CHUNK_SIZE: usize = 1000;
+
+/// The items of a bulk query. Each item is a JSON object with the arguments of
+/// the original query.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BulkItems {
+	items: Vec<Value>,
+}
+
+impl BulkItems {
+	/// Create the items with space for `capacity` items.
+	pub fn with_capacity(capacity: usize) -> Self {
+		Self {
+			items: Vec::with_capacity(capacity),
+		}
+	}
+
+	/// Add an item with the named arguments of the original query.
+	pub fn push<'a>(&mut self, fields: impl IntoIterator<Item = (&'a str, Value)>) {
+		let item = fields
+			.into_iter()
+			.map(|(name, value)| (name.to_string(), value))
+			.collect::<Map<_, _>>();
+
+		self.items.push(Value::Object(item));
+	}
+
+	/// The number of items.
+	pub fn len(&self) -> usize {
+		self.items.len()
+	}
+
+	/// Whether there are no items.
+	pub fn is_empty(&self) -> bool {
+		self.items.is_empty()
+	}
+
+	/// The JSON array which is passed as the `$items` argument.
+	pub fn to_json(&self) -> Json {
+		Json::new_unchecked(Value::Array(self.items.clone()).to_string())
+	}
+}
+
+impl QueryArgs for BulkItems {
+	fn encode(&self, encoder: &mut Encoder) -> Result<(), Error> {
+		let map = gel_protocol::named_args! {
+			ITEMS_PARAM => self.to_json(),
+		};
+
+		map.encode(encoder)
+	}
+}
+
+/// Serialize an argument of an item to JSON.
+pub fn to_value<T: Serialize + ?Sized>(value: &T) -> Result<Value, Error> {
+	serde_json::to_value(value).map_err(ClientEncodingError::with_source)
+}
@@ -0,0 +1,106 @@
+//! Classify the constraint violations reported by the database.
+//!
+//! The `gelx` CLI generates a `<Type>ConstraintError` enum for every object
+//! type with exclusive constraints. The `from_error` method of the enum uses
+//! [`ExclusiveViolation`] to turn the error of a mutation into the variant of
+//! the violated constraint.
+//!
+//! ```rust,ignore
+//! use crate::db::ProjectConstraintError;
+//!
+//! match insert_project::query(&client, &props).await {
+//! 	Ok(project) => Ok(project),
+//! 	Err(error) => {
+//! 		match ProjectConstraintError::from_error(&error) {
+//! 			Some(ProjectConstraintError::CreatorSlugTaken) => Err(ApiError::Conflict),
+//! 			None => Err(error.into()),
+//! 		}
+//! 	}
+//! }
+//! ```
+
+use gel_errors::ConstraintViolationError;
+use gel_errors::Error;
+
+const EXCLUSIVE_MESSAGE_SUFFIX: &str = " violates exclusivity constraint";
+
+/// A violation of an exclusive constraint reported by the database.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExclusiveViolation<'a> {
+	subject: &'a str,
+	object_type: Option<&'a str>,
+}
+
+impl<'a> ExclusiveViolation<'a> {
+	/// Read the violation from the error. Returns `None` when the error isn't
+	/// a violation of an exclusive constraint.
+	pub fn from_error(error: &'a Error) -> Option<Self> {
+		if !error.is::<ConstraintViolationError>() {
+			return None;
+		}
+
+		let subject = error
+			.initial_message()?
+			.strip_suffix(EXCLUSIVE_MESSAGE_SUFFIX)?;
+		let object_type = error.details().and_then(|details| {
+			let (_, rest) = details.split_once("object type '")?;
+			rest.split_once('\'').map(|(name, _)| name)
+		});
+
+		Some(Self {
+			subject,
+			object_type,
+		})
+	}
+
+	/// The subject of the constraint. This is the name of the pointer for
+	/// constraints on a single pointer and the name of the object type for
+	/// constraints on multiple pointers.
+	pub fn subject(&self) -> &'a str {
+		self.subject
+	}
+
+	/// The fully qualified name of the object type when it is included in the
+	/// details of the error, e.g. `default::Project`.
+	pub fn object_type(&self) -> Option<&'a str> {
+		self.object_type
+	}
+
+	/// Whether this is a violation of the exclusive constraint on the
+	/// `pointers` of the `object_type`.
+	///
+	/// The database reports constraints on multiple pointers with the name of
+	/// the object type, so this matches every constraint on multiple pointers
+	/// of the object type. Use [`ExclusiveViolation::find`] to tell the
+	/// constraints apart.
+	pub fn matches(&self, object_type: &str, pointers: &[&str]) -> bool {
+		if self.object_type.is_some_and(|name| name != object_type) {
+			return false;
+		}
+
+		if let [pointer] = pointers {
+			return self.subject == *pointer;
+		}
+
+		let local_name = object_type.rsplit("::").next().unwrap_or(object_type);
+		self.subject == object_type || self.subject == local_name
+	}
+
+	/// Find the violated constraint among the exclusive `constraints` of the
+	/// `object_type`, each listed with the names of its pointers. Returns the
+	/// index of the constraint.
+	///
+	/// Returns `None` when more than one constraint matches, e.g. when the
+	/// object type has several constraints on multiple pointers, since the
+	/// database doesn't report which of them was violated.
+	pub fn find(&self, object_type: &str, constraints: &[&[&str]]) -> Option<usize> {
+		let mut indexes = constraints
+			.iter()
+			.enumerate()
+			.filter(|(_, pointers)| self.matches(object_type, pointers))
+			.map(|(index, _)| index);
+		let index = indexes.next()?;
+
+		indexes.next().is_none().then_some(index)
+	}
+}
@@ -0,0 +1,330 @@
+use std::future::Future;
+
+use gel_errors::Error;
+use gel_protocol::QueryResult;
+use gel_protocol::query_arg::QueryArgs;
+use gel_tokio::Client;
+use gel_tokio::Transaction;
+
+/// A connection which can run queries. The generated `execute` functions accept
+/// any executor, which allows the same function to be used with a [`Client`],
+/// a [`Transaction`] or a test double like the `MockExecutor` from the
+/// `testing` module.
+///
+/// The executor is implemented for [`Client`], `&Client`, [`Transaction`] and
+/// mutable references to any other executor, e.g. `&mut Transaction`.
+///
+/// The returned futures are `Send`, so the queries can run on a spawned task.
+pub trait Executor {
+	/// Execute a query and ignore the result.
+	fn execute<A>(
+		&mut self,
+		query: &str,
+		arguments: &A,
+	) -> impl Future<Output = Result<(), Error>> + Send
+	where
+		A: QueryArgs + 'static;
+
+	/// Run a query and return all the results.
+	fn query<R, A>(
+		&mut self,
+		query: &str,
+		arguments: &A,
+	) -> impl Future<Output = Result<Vec<R>, Error>> + Send
+	where
+		A: QueryArgs + 'static,
+		R: QueryResult + Send + 'static;
+
+	/// Run a query and return at most one result.
+	fn query_single<R, A>(
+		&mut self,
+		query: &str,
+		arguments: &A,
+	) -> impl Future<Output = Result<Option<R>, Error>> + Send
+	where
+		A: QueryArgs + 'static,
+		R: QueryResult + Send + 'static;
+
+	/// Run a query which must return exactly one result.
+	fn query_required_single<R, A>(
+		&mut self,
+		query: &str,
+		arguments: &A,
+	) -> impl Future<Output = Result<R, Error>> + Send
+	where
+		A: QueryArgs + 'static,
+		R: QueryResult + Send + 'static;
+}
+
+/// A marker for executors which can run queries that modify the database.
+///
+/// The generated `execute` functions of queries which insert, update or
+/// delete data, change the schema or configure the database require this
+/// trait. It is implemented by every executor except [`ReadOnly`].
+pub trait Modifying: Executor {}
+
+impl Modifying for Client {}
+impl Modifying for &Client {}
+impl Modifying for Transaction {}
+impl<E: Modifying + ?Sized> Modifying for &mut E {}
+
+/// An executor which can only run read only queries, e.g. a client connected
+/// to a replica.
+///
+/// Passing it to the `execute` function of a modifying query fails to
+/// compile.
+///
+/// ```rust,ignore
+/// let mut replica = gelx::ReadOnly(client);
+///
+/// select_users::execute(&mut replica).await?;
+/// // This doesn't compile.
+/// insert_user::execute(&mut replica, &props).await?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct ReadOnly<E>(pub E);
+
+impl<E: Executor> Executor for ReadOnly<E> {
+	fn execute<A>(
+		&mut self,
+		query: &str,
+		arguments: &A,
+	) -> impl Future<Output = Result<(), Error>> + Send
+	where
+		A: QueryArgs + 'static,
+	{
+		self.0.execute(query, arguments)
+	}
+
+	fn query<R, A>(
+		&mut self,
+		query: &str,
+		arguments: &A,
+	) -> impl Future<Output = Result<Vec<R>, Error>> + Send
+	where
+		A: QueryArgs + 'static,
+		R: QueryResult + Send + 'static,
+	{
+		self.0.query(query, arguments)
+	}
+
+	fn query_single<R, A>(
+		&mut self,
+		query: &str,
+		arguments: &A,
+	) -> impl Future<Output = Result<Option<R>, Error>> + Send
+	where
+		A: QueryArgs + 'static,
+		R: QueryResult + Send + 'static,
+	{
+		self.0.query_single(query, arguments)
+	}
+
+	fn query_required_single<R, A>(
+		&mut self,
+		query: &str,
+		arguments: &A,
+	) -> impl Future<Output = Result<R, Error>> + Send
+	where
+		A: QueryArgs + 'static,
+		R: QueryResult + Send + 'static,
+	{
+		self.0.query_required_single(query, arguments)
+	}
+}
+
+impl Executor for Client {
+	fn execute<A>(
+		&mut self,
+		query: &str,
+		arguments: &A,
+	) -> impl Future<Output = Result<(), Error>> + Send
+	where
+		A: QueryArgs + 'static,
+	{
+		Client::execute(self, query, arguments)
+	}
+
+	fn query<R, A>(
+		&mut self,
+		query: &str,
+		arguments: &A,
+	) -> impl Future<Output = Result<Vec<R>, Error>> + Send
+	where
+		A: QueryArgs + 'static,
+		R: QueryResult + Send + 'static,
+	{
+		Client::query(self, query, arguments)
+	}
+
+	fn query_single<R, A>(
+		&mut self,
+		query: &str,
+		arguments: &A,
+	) -> impl Future<Output = Result<Option<R>, Error>> + Send
+	where
+		A: QueryArgs + 'static,
+		R: QueryResult + Send + 'static,
+	{
+		Client::query_single(self, query, arguments)
+	}
+
+	fn query_required_single<R, A>(
+		&mut self,
+		query: &str,
+		arguments: &A,
+	) -> impl Future<Output = Result<R, Error>> + Send
+	where
+		A: QueryArgs + 'static,
+		R: QueryResult + Send + 'static,
+	{
+		Client::query_required_single(self, query, arguments)
+	}
+}
+
+impl Executor for &Client {
+	fn execute<A>(
+		&mut self,
+		query: &str,
+		arguments: &A,
+	) -> impl Future<Output = Result<(), Error>> + Send
+	where
+		A: QueryArgs + 'static,
+	{
+		Client::execute(self, query, arguments)
+	}
+
+	fn query<R, A>(
+		&mut self,
+		query: &str,
+		arguments: &A,
+	) -> impl Future<Output = Result<Vec<R>, Error>> + Send
+	where
+		A: QueryArgs + 'static,
+		R: QueryResult + Send + 'static,
+	{
+		Client::query(self, query, arguments)
+	}
+
+	fn query_single<R, A>(
+		&mut self,
+		query: &str,
+		arguments: &A,
+	) -> impl Future<Output = Result<Option<R>, Error>> + Send
+	where
+		A: QueryArgs + 'static,
+		R: QueryResult + Send + 'static,
+	{
+		Client::query_single(self, query, arguments)
+	}
+
+	fn query_required_single<R, A>(
+		&mut self,
+		query: &str,
+		arguments: &A,
+	) -> impl Future<Output = Result<R, Error>> + Send
+	where
+		A: QueryArgs + 'static,
+		R: QueryResult + Send + 'static,
+	{
+		Client::query_required_single(self, query, arguments)
+	}
+}
+
+impl Executor for Transaction {
+	fn execute<A>(
+		&mut self,
+		query: &str,
+		arguments: &A,
+	) -> impl Future<Output = Result<(), Error>> + Send
+	where
+		A: QueryArgs + 'static,
+	{
+		Transaction::execute(self, query, arguments)
+	}
+
+	fn query<R, A>(
+		&mut self,
+		query: &str,
+		arguments: &A,
+	) -> impl Future<Output = Result<Vec<R>, Error>> + Send
+	where
+		A: QueryArgs + 'static,
+		R: QueryResult + Send + 'static,
+	{
+		Transaction::query(self, query, arguments)
+	}
+
+	fn query_single<R, A>(
+		&mut self,
+		query: &str,
+		arguments: &A,
+	) -> impl Future<Output = Result<Option<R>, Error>> + Send
+	where
+		A: QueryArgs + 'static,
+		R: QueryResult + Send + 'static,
+	{
+		Transaction::query_single(self, query, arguments)
+	}
+
+	fn query_required_single<R, A>(
+		&mut self,
+		query: &str,
+		arguments: &A,
+	) -> impl Future<Output = Result<R, Error>> + Send
+	where
+		A: QueryArgs + 'static,
+		R: QueryResult + Send + 'static,
+	{
+		Transaction::query_required_single(self, query, arguments)
+	}
+}
+
+impl<E: Executor + ?Sized> Executor for &mut E {
+	fn execute<A>(
+		&mut self,
+		query: &str,
+		arguments: &A,
+	) -> impl Future<Output = Result<(), Error>> + Send
+	where
+		A: QueryArgs + 'static,
+	{
+		(**self).execute(query, arguments)
+	}
+
+	fn query<R, A>(
+		&mut self,
+		query: &str,
+		arguments: &A,
+	) -> impl Future<Output = Result<Vec<R>, Error>> + Send
+	where
+		A: QueryArgs + 'static,
+		R: QueryResult + Send + 'static,
+	{
+		(**self).query(query, arguments)
+	}
+
+	fn query_single<R, A>(
+		&mut self,
+		query: &str,
+		arguments: &A,
+	) -> impl Future<Output = Result<Option<R>, Error>> + Send
+	where
+		A: QueryArgs + 'static,
+		R: QueryResult + Send + 'static,
+	{
+		(**self).query_single(query, arguments)
+	}
+
+	fn query_required_single<R, A>(
+		&mut self,
+		query: &str,
+		arguments: &A,
+	) -> impl Future<Output = Result<R, Error>> + Send
+	where
+		A: QueryArgs + 'static,
+		R: QueryResult + Send + 'static,
+	{
+		(**self).query_required_single(query, arguments)
+	}
+}
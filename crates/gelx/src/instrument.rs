@@ -0,0 +1,56 @@
+//! Instrument the generated queries with [`tracing`] spans.
+//!
+//! When the `tracing` feature is enabled in the gelx configuration every
+//! generated `execute` function creates a span with the name of the query
+//! module and its result cardinality. The functions in this module run the
+//! query inside of the span and record the time it took, the number of rows
+//! returned for sets and the kind of the error when it fails.
+//!
+//! ```toml
+//! [package.metadata.gelx]
+//! features = { tracing = "otel" }
+//! ```
+
+use std::future::Future;
+use std::time::Instant;
+
+use gel_errors::Error;
+use tracing::Instrument;
+use tracing::Span;
+
+/// Run the query inside of the `span` and record the elapsed time and the
+/// error kind.
+pub async fn query<T, F>(span: Span, future: F) -> Result<T, Error>
+where
+	F: Future<Output = Result<T, Error>>,
+{
+	let start = Instant::now();
+	let result = future.instrument(span.clone()).await;
+	record(&span, start, result.as_ref().err());
+
+	result
+}
+
+/// Run a query which returns a set inside of the `span` and also record the
+/// number of rows.
+pub async fn query_many<T, F>(span: Span, future: F) -> Result<Vec<T>, Error>
+where
+	F: Future<Output = Result<Vec<T>, Error>>,
+{
+	let result = query(span.clone(), future).await;
+
+	if let Ok(rows) = &result {
+		span.record("rows", rows.len());
+	}
+
+	result
+}
+
+fn record(span: &Span, start: Instant, error: Option<&Error>) {
+	let elapsed_ms = u64::try_from(start.elapsed().as_millis()).unwrap_or(u64::MAX);
+	span.record("elapsed_ms", elapsed_ms);
+
+	if let Some(error) = error {
+		span.record("error", error.kind_name());
+	}
+}
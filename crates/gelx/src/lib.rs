@@ -7,13 +7,60 @@
 
 use cfg_if::cfg_if;
 
+pub mod constraint;
+pub mod validate;
+
 cfg_if! {
 	if #[cfg(feature = "query")] {
 		mod client;
+		mod executor;
+		mod options;
+		pub mod stream;
 		pub use client::*;
+		pub use executor::*;
+		pub use options::*;
+	}
+}
+
+cfg_if! {
+	if #[cfg(feature = "blocking")] {
+		pub mod blocking;
+		pub use blocking::BlockingClient;
+		pub use blocking::BlockingTransaction;
+	}
+}
+
+#[cfg(all(feature = "query", feature = "serde"))]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "query", feature = "serde"))))]
+pub mod bulk;
+
+#[cfg(feature = "testing")]
+#[cfg_attr(docsrs, doc(cfg(feature = "testing")))]
+pub mod testing;
+
+cfg_if! {
+	if #[cfg(feature = "allowlist")] {
+		pub mod allowlist;
+		pub use allowlist::Allowlisted;
+		pub use allowlist::QueryAllowlist;
+	}
+}
+
+cfg_if! {
+	if #[cfg(feature = "verify")] {
+		pub mod verify;
+		pub use verify::verify_all;
 	}
 }
 
+#[cfg(feature = "tower")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tower")))]
+pub mod tower;
+
+#[cfg(feature = "tracing")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tracing")))]
+pub mod instrument;
+
 cfg_if! {
 	if #[cfg(feature = "with_geo")] {
 		mod geometry;
@@ -97,6 +144,9 @@ pub mod exports {
 	use cfg_if::cfg_if;
 	#[cfg(feature = "query")]
 	#[cfg_attr(docsrs, doc(cfg(feature = "query")))]
+	pub use futures;
+	#[cfg(feature = "query")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "query")))]
 	pub use gel_derive;
 	pub use gel_errors;
 	pub use gel_protocol;
@@ -104,6 +154,9 @@ pub mod exports {
 	#[cfg_attr(docsrs, doc(cfg(feature = "query")))]
 	pub use gel_tokio;
 	pub use gelx_macros;
+	#[cfg(feature = "verify")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "verify")))]
+	pub use inventory;
 	#[cfg(any(feature = "with_bigdecimal", feature = "with_bigint"))]
 	#[cfg_attr(
 		docsrs,
@@ -122,6 +175,9 @@ pub mod exports {
 	#[cfg(feature = "strum")]
 	#[cfg_attr(docsrs, doc(cfg(feature = "strum")))]
 	pub use strum;
+	#[cfg(feature = "tracing")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "tracing")))]
+	pub use tracing;
 	#[cfg(feature = "builder")]
 	#[cfg_attr(docsrs, doc(cfg(feature = "builder")))]
 	pub use typed_builder;
@@ -170,5 +226,39 @@ pub mod exports {
 		}
 	}
 
+	pub use gel_protocol::common::Capabilities;
+
+	#[cfg(feature = "query")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "query")))]
+	pub use super::Executor;
+	#[cfg(feature = "query")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "query")))]
+	pub use super::Modifying;
+	#[cfg(feature = "query")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "query")))]
+	pub use super::QueryOptions;
+	#[cfg(feature = "allowlist")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "allowlist")))]
+	pub use super::allowlist;
+	#[cfg(feature = "blocking")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "blocking")))]
+	pub use super::blocking::BlockingClient;
+	#[cfg(feature = "blocking")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "blocking")))]
+	pub use super::blocking::BlockingTransaction;
+	#[cfg(all(feature = "query", feature = "serde"))]
+	#[cfg_attr(docsrs, doc(cfg(all(feature = "query", feature = "serde"))))]
+	pub use super::bulk;
 	pub use super::check_scalar;
+	pub use super::constraint;
+	#[cfg(feature = "tracing")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "tracing")))]
+	pub use super::instrument;
+	#[cfg(feature = "query")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "query")))]
+	pub use super::stream;
+	pub use super::validate;
+	#[cfg(feature = "verify")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "verify")))]
+	pub use super::verify;
 }
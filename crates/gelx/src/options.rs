@@ -0,0 +1,113 @@
+use std::time::Duration;
+
+use gel_tokio::Client;
+use gel_tokio::RetryOptions;
+use gel_tokio::TransactionOptions;
+
+/// The options for a single call of a generated `query_with` function.
+///
+/// The options which aren't set use the defaults of the client. The defaults
+/// of a query can also be set in the gelx configuration with the
+/// `query_options` rules.
+///
+/// ```rust,ignore
+/// use std::time::Duration;
+///
+/// use gelx::QueryOptions;
+///
+/// let options = QueryOptions::new()
+/// 	.timeout(Duration::from_secs(30))
+/// 	.no_retry();
+///
+/// let user = insert_user::query_with(&client, &props, options).await?;
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct QueryOptions {
+	timeout: Option<Duration>,
+	retry: Option<RetryOptions>,
+	transaction: Option<TransactionOptions>,
+}
+
+impl QueryOptions {
+	/// Create the options with the defaults of the client.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Cancel the query on the server when it runs for longer than the
+	/// `timeout`. This sets the `query_execution_timeout` of the session.
+	#[must_use]
+	pub fn timeout(mut self, timeout: Duration) -> Self {
+		self.timeout = Some(timeout);
+		self
+	}
+
+	/// Retry the query with the provided options.
+	#[must_use]
+	pub fn retry(mut self, retry: RetryOptions) -> Self {
+		self.retry = Some(retry);
+		self
+	}
+
+	/// Attempt to run the query at most `attempts` times with the default
+	/// exponential backoff between the attempts.
+	#[must_use]
+	pub fn retry_attempts(self, attempts: u32) -> Self {
+		self.retry(RetryOptions::default().new(attempts, |attempt| {
+			Duration::from_millis(2u64.saturating_pow(attempt).saturating_mul(100))
+		}))
+	}
+
+	/// Never retry the query, e.g. for mutations which aren't idempotent.
+	#[must_use]
+	pub fn no_retry(self) -> Self {
+		self.retry_attempts(1)
+	}
+
+	/// Run the query in a transaction with the provided options.
+	#[must_use]
+	pub fn transaction(mut self, transaction: TransactionOptions) -> Self {
+		self.transaction = Some(transaction);
+		self
+	}
+
+	/// Use the `defaults` for the options which aren't set.
+	#[must_use]
+	pub fn or(self, defaults: Self) -> Self {
+		Self {
+			timeout: self.timeout.or(defaults.timeout),
+			retry: self.retry.or(defaults.retry),
+			transaction: self.transaction.or(defaults.transaction),
+		}
+	}
+
+	/// Whether the query should run in a transaction.
+	pub fn is_transaction(&self) -> bool {
+		self.transaction.is_some()
+	}
+
+	/// Create a client with the options applied.
+	pub fn apply_to(&self, client: &Client) -> Client {
+		let mut client = client.clone();
+
+		if let Some(timeout) = self.timeout {
+			let micros = i64::try_from(timeout.as_micros()).unwrap_or(i64::MAX);
+			client = client.with_config_fn(|modifier| {
+				modifier.set(
+					"query_execution_timeout",
+					gel_protocol::model::Duration::from_micros(micros),
+				);
+			});
+		}
+
+		if let Some(retry) = &self.retry {
+			client = client.with_retry_options(retry.clone());
+		}
+
+		if let Some(transaction) = &self.transaction {
+			client = client.with_transaction_options(transaction.clone());
+		}
+
+		client
+	}
+}
@@ -0,0 +1,152 @@
+//! Stream large result sets without buffering them in memory.
+//!
+//! `gel-tokio` doesn't provide a streaming API, so the generated
+//! `query_stream` functions fetch the results in pages which are ordered by
+//! `id`. Each page starts after the `id` of the last row of the previous page.
+//!
+//! Queries which already take a limit with an offset or a cursor get the
+//! `paginate` functions instead, which return a [`Page`] of the results and
+//! walk every page with [`pages`].
+
+use std::collections::VecDeque;
+use std::future::Future;
+use std::sync::Arc;
+
+use futures::Stream;
+use futures::stream;
+use gel_errors::Error;
+use uuid::Uuid;
+
+/// The default number of rows fetched for each page.
+pub const DEFAULT_PAGE_SIZE: i64 = 1000;
+
+/// Create a stream which fetches the rows in pages of `page_size` with keyset
+/// pagination.
+///
+/// The `fetch` function receives the `id` of the last row of the previous page
+/// (`None` for the first page) and the maximum number of rows to return. The
+/// `cursor` function returns the `id` of a row. The stream ends after the first
+/// page with fewer rows than the `page_size`, or after the first error.
+pub fn keyset<'a, T, C, F, Fut>(
+	page_size: i64,
+	cursor: C,
+	fetch: F,
+) -> impl Stream<Item = Result<T, Error>> + 'a
+where
+	T: 'a,
+	C: Fn(&T) -> Uuid + 'a,
+	F: Fn(Option<Uuid>, i64) -> Fut + 'a,
+	Fut: Future<Output = Result<Vec<T>, Error>> + 'a,
+{
+	let page_size = page_size.max(1);
+	let cursor = Arc::new(cursor);
+	let fetch = Arc::new(fetch);
+
+	pages(move |next_cursor| {
+		let cursor = cursor.clone();
+		let fetch = fetch.clone();
+
+		async move {
+			let rows = fetch(next_cursor, page_size).await?;
+			Ok(Page::from_keyset(rows, page_size, &*cursor))
+		}
+	})
+}
+
+/// A page of the results of a paginated query.
+///
+/// The `next_cursor` is passed to the generated `paginate_from` function to
+/// fetch the following page. It is `None` for the last page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Page<T, C> {
+	/// The results of the page.
+	pub items: Vec<T>,
+	/// The cursor of the following page, e.g. the `id` of the last result or
+	/// the offset of the next result.
+	pub next_cursor: Option<C>,
+}
+
+impl<T, C> Page<T, C> {
+	/// Create a page with the provided results and cursor.
+	pub fn new(items: Vec<T>, next_cursor: Option<C>) -> Self {
+		Self { items, next_cursor }
+	}
+
+	/// Whether there is a following page.
+	pub fn has_next(&self) -> bool {
+		self.next_cursor.is_some()
+	}
+}
+
+impl<T> Page<T, i64> {
+	/// Create a page of the results which start at the `offset`. There is a
+	/// following page when the page is full.
+	pub fn from_offset(items: Vec<T>, offset: i64, page_size: i64) -> Self {
+		let len = i64::try_from(items.len()).unwrap_or(i64::MAX);
+		let next_cursor = (len >= page_size.max(1)).then(|| offset.saturating_add(len));
+
+		Self { items, next_cursor }
+	}
+}
+
+impl<T> Page<T, Uuid> {
+	/// Create a page of the results which are ordered by `id`. The `id` of the
+	/// last result is the cursor of the following page when the page is full.
+	pub fn from_keyset(items: Vec<T>, page_size: i64, cursor: impl Fn(&T) -> Uuid) -> Self {
+		let len = i64::try_from(items.len()).unwrap_or(i64::MAX);
+		let next_cursor = if len >= page_size.max(1) {
+			items.last().map(cursor)
+		} else {
+			None
+		};
+
+		Self { items, next_cursor }
+	}
+}
+
+/// Create a stream of the results of every page.
+///
+/// The `fetch` function receives the `next_cursor` of the previous page
+/// (`None` for the first page). The stream ends after the first page without
+/// a `next_cursor`, or after the first error.
+pub fn pages<'a, T, C, F, Fut>(fetch: F) -> impl Stream<Item = Result<T, Error>> + 'a
+where
+	T: 'a,
+	C: 'a,
+	F: Fn(Option<C>) -> Fut + 'a,
+	Fut: Future<Output = Result<Page<T, C>, Error>> + 'a,
+{
+	let state = PagesState {
+		cursor: None,
+		items: VecDeque::new(),
+		is_done: false,
+	};
+
+	stream::unfold((state, fetch), move |(mut state, fetch)| {
+		async move {
+			if state.items.is_empty() && !state.is_done {
+				match fetch(state.cursor.take()).await {
+					Ok(page) => {
+						state.is_done = page.next_cursor.is_none();
+						state.cursor = page.next_cursor;
+						state.items = page.items.into();
+					}
+					Err(error) => {
+						state.is_done = true;
+						return Some((Err(error), (state, fetch)));
+					}
+				}
+			}
+
+			let item = state.items.pop_front()?;
+			Some((Ok(item), (state, fetch)))
+		}
+	})
+}
+
+struct PagesState<T, C> {
+	cursor: Option<C>,
+	items: VecDeque<T>,
+	is_done: bool,
+}
@@ -0,0 +1,249 @@
+//! Utilities for testing code which calls the generated queries without a
+//! running gel instance.
+//!
+//! ```rust,ignore
+//! use gelx::testing::MockExecutor;
+//!
+//! let mut executor = MockExecutor::new();
+//! executor
+//! 	.expect(select_user::QUERY)
+//! 	.with_input(|props: &select_user::Input| props.slug == "test")
+//! 	.returns(Some(select_user::Output { slug: "test".into() }));
+//!
+//! let props = select_user::Input { slug: "test".into() };
+//! let user = select_user::execute(&mut executor, &props).await?;
+//!
+//! executor.verify();
+//! ```
+
+use std::any::Any;
+use std::any::type_name;
+use std::future::Future;
+use std::future::ready;
+
+use gel_errors::Error;
+use gel_protocol::QueryResult;
+use gel_protocol::query_arg::QueryArgs;
+
+use crate::Executor;
+use crate::Modifying;
+
+type InputMatcher = Box<dyn Fn(&dyn Any) -> bool + Send>;
+
+/// An [`Executor`] which returns pre-programmed responses for the queries
+/// instead of connecting to the database.
+///
+/// Every query which is run must match an expectation, otherwise the executor
+/// panics. Expectations are matched in the order they were added and each
+/// expectation is used for a single call.
+#[derive(Debug, Default)]
+pub struct MockExecutor {
+	expectations: Vec<MockExpectation>,
+	calls: Vec<MockCall>,
+}
+
+impl MockExecutor {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Expect the query to be run. The `query` is usually the `QUERY` constant
+	/// of the generated query module.
+	pub fn expect(&mut self, query: impl Into<String>) -> &mut MockExpectation {
+		self.expectations.push(MockExpectation {
+			query: query.into(),
+			matcher: None,
+			response: None,
+		});
+
+		self.expectations
+			.last_mut()
+			.unwrap_or_else(|| unreachable!())
+	}
+
+	/// The calls which have been made to the executor in order.
+	pub fn calls(&self) -> &[MockCall] {
+		&self.calls
+	}
+
+	/// The number of calls which have been made for the `query`.
+	pub fn calls_to(&self, query: &str) -> usize {
+		self.calls.iter().filter(|call| call.query == query).count()
+	}
+
+	/// Panics when some of the expectations haven't been used.
+	pub fn verify(&self) {
+		let queries = self
+			.expectations
+			.iter()
+			.map(|expectation| expectation.query.as_str())
+			.collect::<Vec<_>>();
+
+		assert!(
+			queries.is_empty(),
+			"expected queries which were never run: {queries:#?}"
+		);
+	}
+
+	fn respond<T: 'static, A: 'static>(&mut self, query: &str, arguments: &A) -> Result<T, Error> {
+		self.calls.push(MockCall {
+			query: query.to_string(),
+			input_type: type_name::<A>(),
+		});
+
+		let Some(index) = self
+			.expectations
+			.iter()
+			.position(|expectation| expectation.matches(query, arguments))
+		else {
+			panic!(
+				"no expectation matches the query with input `{}`:\n{query}",
+				type_name::<A>()
+			);
+		};
+
+		let expectation = self.expectations.remove(index);
+
+		match expectation.response {
+			Some(MockResponse::Value(value)) => {
+				let Ok(value) = value.downcast::<T>() else {
+					panic!(
+						"the response for the query should be `{}`:\n{query}",
+						type_name::<T>()
+					);
+				};
+
+				Ok(*value)
+			}
+			Some(MockResponse::Error(error)) => Err(error),
+			None => panic!("no response was provided for the query:\n{query}"),
+		}
+	}
+}
+
+/// A call made to the [`MockExecutor`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MockCall {
+	/// The query which was run.
+	pub query: String,
+	/// The name of the input type of the query.
+	pub input_type: &'static str,
+}
+
+/// An expected query created with [`MockExecutor::expect`].
+pub struct MockExpectation {
+	query: String,
+	matcher: Option<InputMatcher>,
+	response: Option<MockResponse>,
+}
+
+impl MockExpectation {
+	/// Only match the calls where the input satisfies the `predicate`.
+	pub fn with_input<A: 'static>(
+		&mut self,
+		predicate: impl Fn(&A) -> bool + Send + 'static,
+	) -> &mut Self {
+		self.matcher = Some(Box::new(move |input| {
+			input.downcast_ref::<A>().is_some_and(&predicate)
+		}));
+
+		self
+	}
+
+	/// The value returned by the query. This must be the same type as the
+	/// value returned by the generated function, e.g. `Vec<Output>` for queries
+	/// which return a set and `Option<Output>` for optional results.
+	pub fn returns<T: Send + 'static>(&mut self, value: T) -> &mut Self {
+		self.response = Some(MockResponse::Value(Box::new(value)));
+		self
+	}
+
+	/// The error returned by the query.
+	pub fn returns_error(&mut self, error: Error) -> &mut Self {
+		self.response = Some(MockResponse::Error(error));
+		self
+	}
+
+	fn matches(&self, query: &str, arguments: &dyn Any) -> bool {
+		self.query.trim() == query.trim()
+			&& self
+				.matcher
+				.as_ref()
+				.is_none_or(|matcher| matcher(arguments))
+	}
+}
+
+impl std::fmt::Debug for MockExpectation {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("MockExpectation")
+			.field("query", &self.query)
+			.field("has_matcher", &self.matcher.is_some())
+			.field("response", &self.response)
+			.finish()
+	}
+}
+
+enum MockResponse {
+	Value(Box<dyn Any + Send>),
+	Error(Error),
+}
+
+impl std::fmt::Debug for MockResponse {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Value(_) => f.write_str("Value(..)"),
+			Self::Error(error) => f.debug_tuple("Error").field(error).finish(),
+		}
+	}
+}
+
+impl Executor for MockExecutor {
+	fn execute<A>(
+		&mut self,
+		query: &str,
+		arguments: &A,
+	) -> impl Future<Output = Result<(), Error>> + Send
+	where
+		A: QueryArgs + 'static,
+	{
+		ready(self.respond(query, arguments))
+	}
+
+	fn query<R, A>(
+		&mut self,
+		query: &str,
+		arguments: &A,
+	) -> impl Future<Output = Result<Vec<R>, Error>> + Send
+	where
+		A: QueryArgs + 'static,
+		R: QueryResult + Send + 'static,
+	{
+		ready(self.respond(query, arguments))
+	}
+
+	fn query_single<R, A>(
+		&mut self,
+		query: &str,
+		arguments: &A,
+	) -> impl Future<Output = Result<Option<R>, Error>> + Send
+	where
+		A: QueryArgs + 'static,
+		R: QueryResult + Send + 'static,
+	{
+		ready(self.respond(query, arguments))
+	}
+
+	fn query_required_single<R, A>(
+		&mut self,
+		query: &str,
+		arguments: &A,
+	) -> impl Future<Output = Result<R, Error>> + Send
+	where
+		A: QueryArgs + 'static,
+		R: QueryResult + Send + 'static,
+	{
+		ready(self.respond(query, arguments))
+	}
+}
+
+impl Modifying for MockExecutor {}
@@ -0,0 +1,125 @@
+//! A [`tower`](https://docs.rs/tower) layer which adds a gel client with the
+//! globals of the current request to every http request.
+//!
+//! The globals are read from the request extensions, usually inserted by an
+//! authentication middleware which runs before this layer. The client is
+//! added to the request extensions so that handlers can run the generated
+//! queries with it.
+//!
+//! ```rust,ignore
+//! use gelx::tower::GlobalsLayer;
+//!
+//! use crate::db::GlobalsPatch;
+//!
+//! let client = gelx::create_client().await?;
+//! let app = Router::new()
+//! 	.route("/", get(handler))
+//! 	.layer(GlobalsLayer::<GlobalsPatch>::new(client));
+//!
+//! async fn handler(Extension(client): Extension<gelx::exports::gel_tokio::Client>) {
+//! 	let user = global_current_user::query(&client).await;
+//! }
+//! ```
+
+use std::marker::PhantomData;
+use std::task::Context;
+use std::task::Poll;
+
+use gel_tokio::Client;
+use gel_tokio::GlobalsDelta;
+use http::Request;
+use tower_layer::Layer;
+use tower_service::Service;
+
+/// A [`Layer`] which adds a [`Client`] with the globals `G` of the request to
+/// the request extensions.
+///
+/// `G` is usually the generated `Globals` or `GlobalsPatch` struct. When the
+/// request has no `G` extension the shared client is added unchanged.
+pub struct GlobalsLayer<G> {
+	client: Client,
+	_globals: PhantomData<fn() -> G>,
+}
+
+impl<G> GlobalsLayer<G> {
+	/// Create the layer from the client which is shared by every request.
+	pub fn new(client: Client) -> Self {
+		Self {
+			client,
+			_globals: PhantomData,
+		}
+	}
+}
+
+impl<G> Clone for GlobalsLayer<G> {
+	fn clone(&self) -> Self {
+		Self::new(self.client.clone())
+	}
+}
+
+impl<G> std::fmt::Debug for GlobalsLayer<G> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("GlobalsLayer").finish_non_exhaustive()
+	}
+}
+
+impl<S, G> Layer<S> for GlobalsLayer<G> {
+	type Service = GlobalsService<S, G>;
+
+	fn layer(&self, inner: S) -> Self::Service {
+		GlobalsService {
+			inner,
+			client: self.client.clone(),
+			_globals: PhantomData,
+		}
+	}
+}
+
+/// The [`Service`] created by the [`GlobalsLayer`].
+pub struct GlobalsService<S, G> {
+	inner: S,
+	client: Client,
+	_globals: PhantomData<fn() -> G>,
+}
+
+impl<S: Clone, G> Clone for GlobalsService<S, G> {
+	fn clone(&self) -> Self {
+		Self {
+			inner: self.inner.clone(),
+			client: self.client.clone(),
+			_globals: PhantomData,
+		}
+	}
+}
+
+impl<S: std::fmt::Debug, G> std::fmt::Debug for GlobalsService<S, G> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("GlobalsService")
+			.field("inner", &self.inner)
+			.finish_non_exhaustive()
+	}
+}
+
+impl<S, G, B> Service<Request<B>> for GlobalsService<S, G>
+where
+	S: Service<Request<B>>,
+	G: GlobalsDelta + Clone + Send + Sync + 'static,
+{
+	type Error = S::Error;
+	type Future = S::Future;
+	type Response = S::Response;
+
+	fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+		self.inner.poll_ready(cx)
+	}
+
+	fn call(&mut self, mut request: Request<B>) -> Self::Future {
+		let client = match request.extensions().get::<G>() {
+			Some(globals) => self.client.with_globals(globals.clone()),
+			None => self.client.clone(),
+		};
+
+		request.extensions_mut().insert(client);
+		self.inner.call(request)
+	}
+}
@@ -0,0 +1,251 @@
+//! Client side validation of the constraints defined in the database schema.
+//!
+//! The `gelx` CLI generates implementations of [`Validate`] for custom scalar
+//! types and query inputs which are constrained in the schema. This allows
+//! invalid values to be rejected before making a round trip to the database.
+
+#[cfg(feature = "regex")]
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::fmt::Display;
+#[cfg(feature = "regex")]
+use std::sync::LazyLock;
+#[cfg(feature = "regex")]
+use std::sync::PoisonError;
+#[cfg(feature = "regex")]
+use std::sync::RwLock;
+
+#[cfg(feature = "regex")]
+use fancy_regex::Regex;
+use gel_errors::ErrorKind;
+use gel_errors::InvalidArgumentError;
+
+/// A value which doesn't satisfy a constraint from the database schema.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+	/// The schema item which is constrained, e.g. `default::Position` or
+	/// `default::Actor.slug`.
+	pub subject: &'static str,
+	/// The fully qualified name of the constraint, e.g. `std::max_value`.
+	pub constraint: &'static str,
+	/// A description of the violation.
+	pub message: String,
+}
+
+impl ValidationError {
+	pub fn new(
+		subject: &'static str,
+		constraint: &'static str,
+		message: impl Into<String>,
+	) -> Self {
+		Self {
+			subject,
+			constraint,
+			message: message.into(),
+		}
+	}
+}
+
+impl Display for ValidationError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}: {}", self.subject, self.message)
+	}
+}
+
+impl std::error::Error for ValidationError {}
+
+impl From<ValidationError> for gel_errors::Error {
+	fn from(value: ValidationError) -> Self {
+		InvalidArgumentError::with_source(value)
+	}
+}
+
+/// Validate a value against the constraints of the database schema.
+pub trait Validate {
+	fn validate(&self) -> Result<(), ValidationError>;
+}
+
+impl<T: Validate> Validate for Option<T> {
+	fn validate(&self) -> Result<(), ValidationError> {
+		self.as_ref().map_or(Ok(()), Validate::validate)
+	}
+}
+
+impl<T: Validate> Validate for Vec<T> {
+	fn validate(&self) -> Result<(), ValidationError> {
+		self.iter().try_for_each(Validate::validate)
+	}
+}
+
+impl<T: Validate + ?Sized> Validate for &T {
+	fn validate(&self) -> Result<(), ValidationError> {
+		(*self).validate()
+	}
+}
+
+/// Check the `std::min_value` constraint.
+pub fn min_value<T: PartialOrd + Display>(
+	subject: &'static str,
+	value: &T,
+	min: &T,
+) -> Result<(), ValidationError> {
+	if value >= min {
+		return Ok(());
+	}
+
+	Err(ValidationError::new(
+		subject,
+		"std::min_value",
+		format!("minimum allowed value is {min}, received {value}"),
+	))
+}
+
+/// Check the `std::min_ex_value` constraint.
+pub fn min_ex_value<T: PartialOrd + Display>(
+	subject: &'static str,
+	value: &T,
+	min: &T,
+) -> Result<(), ValidationError> {
+	if value > min {
+		return Ok(());
+	}
+
+	Err(ValidationError::new(
+		subject,
+		"std::min_ex_value",
+		format!("value must be greater than {min}, received {value}"),
+	))
+}
+
+/// Check the `std::max_value` constraint.
+pub fn max_value<T: PartialOrd + Display>(
+	subject: &'static str,
+	value: &T,
+	max: &T,
+) -> Result<(), ValidationError> {
+	if value <= max {
+		return Ok(());
+	}
+
+	Err(ValidationError::new(
+		subject,
+		"std::max_value",
+		format!("maximum allowed value is {max}, received {value}"),
+	))
+}
+
+/// Check the `std::max_ex_value` constraint.
+pub fn max_ex_value<T: PartialOrd + Display>(
+	subject: &'static str,
+	value: &T,
+	max: &T,
+) -> Result<(), ValidationError> {
+	if value < max {
+		return Ok(());
+	}
+
+	Err(ValidationError::new(
+		subject,
+		"std::max_ex_value",
+		format!("value must be less than {max}, received {value}"),
+	))
+}
+
+/// Check the `std::min_len_value` constraint. The length is measured in
+/// characters to match the `len` function of the database.
+pub fn min_len_value(
+	subject: &'static str,
+	value: &str,
+	min: usize,
+) -> Result<(), ValidationError> {
+	let len = value.chars().count();
+
+	if len >= min {
+		return Ok(());
+	}
+
+	Err(ValidationError::new(
+		subject,
+		"std::min_len_value",
+		format!("must be at least {min} characters long, received {len}"),
+	))
+}
+
+/// Check the `std::max_len_value` constraint. The length is measured in
+/// characters to match the `len` function of the database.
+pub fn max_len_value(
+	subject: &'static str,
+	value: &str,
+	max: usize,
+) -> Result<(), ValidationError> {
+	let len = value.chars().count();
+
+	if len <= max {
+		return Ok(());
+	}
+
+	Err(ValidationError::new(
+		subject,
+		"std::max_len_value",
+		format!("must be no longer than {max} characters, received {len}"),
+	))
+}
+
+/// Check the `std::regexp` constraint. Compiled patterns are cached for the
+/// lifetime of the program.
+#[cfg(feature = "regex")]
+pub fn regexp(
+	subject: &'static str,
+	value: &str,
+	pattern: &'static str,
+) -> Result<(), ValidationError> {
+	// Patterns which can't be compiled on the client are cached as `None` and
+	// left for the database to check.
+	static PATTERNS: LazyLock<RwLock<HashMap<&'static str, Option<Regex>>>> =
+		LazyLock::new(RwLock::default);
+
+	let cached = PATTERNS
+		.read()
+		.unwrap_or_else(PoisonError::into_inner)
+		.get(pattern)
+		.cloned();
+	let regex = cached.unwrap_or_else(|| {
+		let regex = Regex::new(pattern).ok();
+		PATTERNS
+			.write()
+			.unwrap_or_else(PoisonError::into_inner)
+			.insert(pattern, regex.clone());
+
+		regex
+	});
+
+	// The lock is released before matching so validations run in parallel.
+	let is_match = regex.is_none_or(|regex| regex.is_match(value).unwrap_or(true));
+
+	if is_match {
+		return Ok(());
+	}
+
+	Err(ValidationError::new(
+		subject,
+		"std::regexp",
+		format!("invalid value: must match the pattern {pattern:?}"),
+	))
+}
+
+/// Check the `std::one_of` constraint.
+pub fn one_of<T, U>(subject: &'static str, value: &T, options: &[U]) -> Result<(), ValidationError>
+where
+	T: PartialEq<U> + Debug,
+	U: Debug,
+{
+	if options.iter().any(|option| value == option) {
+		return Ok(());
+	}
+
+	Err(ValidationError::new(
+		subject,
+		"std::one_of",
+		format!("{value:?} must be one of {options:?}"),
+	))
+}
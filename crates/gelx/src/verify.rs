@@ -0,0 +1,200 @@
+//! Detect schema drift of the generated queries at runtime.
+//!
+//! With `features = { verify = true }` every generated module has a
+//! `FINGERPRINT` with the type ids of the input and output descriptors and the
+//! cardinality of the result. The fingerprints are registered globally when
+//! the module is compiled into the binary. [`verify_all`] parses every
+//! registered query against the current schema and reports the queries whose
+//! descriptors changed since the code was generated, e.g. to fail a readiness
+//! check instead of failing at the first request.
+//!
+//! ```rust,ignore
+//! let client = gel_tokio::create_client().await?;
+//! let mismatches = gelx::verify_all(&client).await?;
+//!
+//! for mismatch in &mismatches {
+//! 	tracing::error!("{mismatch}");
+//! }
+//! ```
+
+use std::fmt;
+
+use gel_errors::ClientError;
+use gel_errors::Error;
+use gel_errors::ErrorKind;
+use gel_protocol::common::Cardinality;
+use gel_protocol::query_arg::Encoder;
+use gel_protocol::query_arg::QueryArgs;
+use gel_protocol::server_message::CommandDataDescription1;
+use gel_protocol::value::Value;
+use gel_tokio::Client;
+use gel_tokio::raw::Description;
+use uuid::Uuid;
+
+/// The descriptor of a query when its module was generated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueryFingerprint {
+	/// The name of the query module.
+	pub name: &'static str,
+	/// The query string.
+	pub query: &'static str,
+	/// The type id of the input descriptor.
+	pub input_id: Uuid,
+	/// The type id of the output descriptor.
+	pub output_id: Uuid,
+	/// The cardinality of the result.
+	pub cardinality: Cardinality,
+}
+
+inventory::collect!(QueryFingerprint);
+
+impl QueryFingerprint {
+	/// The fingerprints of all generated modules in the binary, sorted by
+	/// name.
+	pub fn registered() -> Vec<&'static QueryFingerprint> {
+		let mut fingerprints = inventory::iter::<QueryFingerprint>
+			.into_iter()
+			.collect::<Vec<_>>();
+		fingerprints.sort_by_key(|fingerprint| fingerprint.name);
+
+		fingerprints
+	}
+
+	/// Compare the fingerprint with the descriptor of the query in the current
+	/// schema.
+	pub fn compare(&self, descriptor: &CommandDataDescription1) -> Vec<QueryMismatch> {
+		let mut mismatches = Vec::new();
+
+		if descriptor.input.id != self.input_id {
+			mismatches.push(self.mismatch(MismatchKind::Input {
+				expected: self.input_id,
+				actual: descriptor.input.id,
+			}));
+		}
+
+		if descriptor.output.id != self.output_id {
+			mismatches.push(self.mismatch(MismatchKind::Output {
+				expected: self.output_id,
+				actual: descriptor.output.id,
+			}));
+		}
+
+		if descriptor.result_cardinality != self.cardinality {
+			mismatches.push(self.mismatch(MismatchKind::Cardinality {
+				expected: self.cardinality,
+				actual: descriptor.result_cardinality,
+			}));
+		}
+
+		mismatches
+	}
+
+	fn mismatch(&self, kind: MismatchKind) -> QueryMismatch {
+		QueryMismatch {
+			name: self.name,
+			kind,
+		}
+	}
+}
+
+/// A query which no longer matches the schema.
+#[derive(Debug)]
+pub struct QueryMismatch {
+	/// The name of the query module.
+	pub name: &'static str,
+	/// What changed.
+	pub kind: MismatchKind,
+}
+
+/// The part of the descriptor which changed since the code was generated.
+#[derive(Debug)]
+pub enum MismatchKind {
+	/// The arguments of the query changed.
+	Input { expected: Uuid, actual: Uuid },
+	/// The shape of the result changed.
+	Output { expected: Uuid, actual: Uuid },
+	/// The cardinality of the result changed.
+	Cardinality {
+		expected: Cardinality,
+		actual: Cardinality,
+	},
+	/// The query can't be compiled against the schema.
+	Invalid(Error),
+}
+
+impl fmt::Display for QueryMismatch {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let name = self.name;
+
+		match &self.kind {
+			MismatchKind::Input { expected, actual } => {
+				write!(
+					f,
+					"`{name}`: the input type changed from {expected} to {actual}"
+				)
+			}
+			MismatchKind::Output { expected, actual } => {
+				write!(
+					f,
+					"`{name}`: the output type changed from {expected} to {actual}"
+				)
+			}
+			MismatchKind::Cardinality { expected, actual } => {
+				write!(
+					f,
+					"`{name}`: the cardinality changed from {expected:?} to {actual:?}"
+				)
+			}
+			MismatchKind::Invalid(error) => write!(f, "`{name}`: {error}"),
+		}
+	}
+}
+
+/// Parse every registered query and return the queries which no longer match
+/// the schema.
+///
+/// The queries are parsed with the state of the `client`, e.g. its globals and
+/// default module, without being executed. Errors of the connection are
+/// returned as an error.
+pub async fn verify_all(client: &Client) -> Result<Vec<QueryMismatch>, Error> {
+	verify(client, QueryFingerprint::registered()).await
+}
+
+/// Parse the queries of the `fingerprints` and return the queries which no
+/// longer match the schema.
+pub async fn verify<'a>(
+	client: &Client,
+	fingerprints: impl IntoIterator<Item = &'a QueryFingerprint>,
+) -> Result<Vec<QueryMismatch>, Error> {
+	let mut mismatches = Vec::new();
+
+	for fingerprint in fingerprints {
+		// The arguments are never encoded, so the query is parsed and the error
+		// carries its descriptor without the query being executed.
+		let Err(error) = client
+			.query::<Value, _>(fingerprint.query, &DescribeOnly)
+			.await
+		else {
+			continue;
+		};
+
+		if let Some(descriptor) = error.get::<Description>() {
+			mismatches.extend(fingerprint.compare(descriptor));
+		} else if error.is::<ClientError>() {
+			return Err(error);
+		} else {
+			mismatches.push(fingerprint.mismatch(MismatchKind::Invalid(error)));
+		}
+	}
+
+	Ok(mismatches)
+}
+
+/// Arguments which fail to encode so that a query is only parsed.
+struct DescribeOnly;
+
+impl QueryArgs for DescribeOnly {
+	fn encode(&self, _encoder: &mut Encoder) -> Result<(), Error> {
+		Err(ClientError::with_message("the query is only described"))
+	}
+}
@@ -0,0 +1,113 @@
+#![cfg(all(feature = "allowlist", feature = "testing"))]
+
+use std::sync::Arc;
+
+use assert2::check;
+use assert2::let_assert;
+use gel_errors::ClientError;
+use gel_tokio::Builder;
+use gel_tokio::Client;
+use gelx::Allowlisted;
+use gelx::QueryAllowlist;
+use gelx::allowlist::query_hash;
+use gelx::testing::MockExecutor;
+
+/// The code generated for `select User { slug } filter .slug = <str>$slug`
+/// with `features = { allowlist = true }` by the `codegen_features` test.
+#[allow(unused, unused_qualifications, clippy::all)]
+mod generated {
+	include!("fixtures/allowlist.rs");
+}
+
+use generated::select_user;
+
+fn manifest() -> String {
+	format!(
+		r#"{{
+  "version": 1,
+  "queries": {{
+    "{}": {{
+      "module": "select_user",
+      "constant": "QUERY",
+      "capabilities": 0
+    }}
+  }}
+}}"#,
+		select_user::QUERY_HASH
+	)
+}
+
+fn input(slug: &str) -> select_user::Input {
+	select_user::Input {
+		slug: slug.to_string(),
+	}
+}
+
+fn output(slug: &str) -> select_user::Output {
+	select_user::Output {
+		slug: slug.to_string(),
+	}
+}
+
+#[test]
+fn the_hash_matches_the_generated_constant() {
+	check!(query_hash(select_user::QUERY) == select_user::QUERY_HASH);
+}
+
+#[test]
+fn reads_the_hashes_of_the_manifest() {
+	let allowlist = QueryAllowlist::from_manifest(&manifest()).unwrap();
+
+	check!(allowlist.len() == 1);
+	check!(allowlist.contains(select_user::QUERY));
+	check!(!allowlist.contains("select User { slug };"));
+	check!(QueryAllowlist::from_manifest("{}").is_err());
+}
+
+#[tokio::test]
+async fn runs_the_queries_in_the_allowlist() {
+	let allowlist = QueryAllowlist::from_hashes([select_user::QUERY_HASH]);
+	let mut mock = MockExecutor::new();
+	mock.expect(select_user::QUERY)
+		.returns(Some(output("first")));
+
+	let mut executor = Allowlisted::new(&mut mock, Arc::new(allowlist));
+	let user = select_user::execute(&mut executor, &input("first"))
+		.await
+		.unwrap();
+
+	check!(user.map(|user| user.slug) == Some("first".to_string()));
+	mock.verify();
+}
+
+#[tokio::test]
+async fn refuses_queries_which_are_not_in_the_allowlist() {
+	let mut mock = MockExecutor::new();
+	let mut executor = Allowlisted::new(&mut mock, Arc::new(QueryAllowlist::default()));
+	let result = select_user::execute(&mut executor, &input("first")).await;
+
+	let_assert!(Err(error) = result);
+	check!(error.is::<ClientError>());
+	check!(mock.calls().is_empty());
+}
+
+#[tokio::test]
+async fn client_functions_check_the_installed_allowlist() {
+	let config = Builder::new()
+		.dsn("gel://localhost:5656")
+		.without_system()
+		.build()
+		.unwrap();
+	let client = Client::new(&config);
+	let result = select_user::query(&client, &input("first")).await;
+
+	let_assert!(Err(error) = result);
+	check!(error.to_string().contains("no allowlist is installed"));
+
+	let allowlist = QueryAllowlist::from_hashes([select_user::QUERY_HASH]);
+	gelx::allowlist::install(Arc::new(allowlist)).unwrap();
+
+	check!(gelx::allowlist::check_hash(select_user::QUERY_HASH).is_ok());
+	check!(gelx::allowlist::check_hash(&query_hash("select 1")).is_err());
+	check!(gelx::allowlist::install(Arc::new(QueryAllowlist::default())).is_err());
+}
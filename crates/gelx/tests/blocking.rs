@@ -0,0 +1,80 @@
+#![cfg(feature = "blocking")]
+
+use std::sync::Arc;
+
+use assert2::check;
+use gel_tokio::Builder;
+use gel_tokio::Client;
+use gelx::BlockingClient;
+use gelx::BlockingTransaction;
+
+/// The code generated for `select User { slug } filter .slug = <str>$slug`
+/// with `features = { blocking = true }` by the `codegen_features` test.
+#[allow(unused, unused_qualifications, clippy::all)]
+mod generated {
+	include!("fixtures/blocking.rs");
+}
+
+use generated::select_user;
+
+fn lazy_client() -> Client {
+	let config = Builder::new()
+		.dsn("gel://localhost:5656")
+		.without_system()
+		.build()
+		.unwrap();
+
+	Client::new(&config)
+}
+
+#[test]
+fn runs_futures_on_the_owned_runtime() {
+	let client = BlockingClient::new(lazy_client()).unwrap();
+	let value = client.block_on(async {
+		tokio::task::yield_now().await;
+		"done"
+	});
+
+	check!(value == "done");
+}
+
+#[test]
+fn clients_share_the_runtime() {
+	let client = BlockingClient::new(lazy_client()).unwrap();
+	let other = client.with_client(lazy_client().with_default_module(Some("other")));
+
+	check!(other.block_on(async { 1 }) == 1);
+	check!(client.block_on(async { 2 }) == 2);
+}
+
+#[test]
+fn rejects_a_current_thread_runtime() {
+	let runtime = tokio::runtime::Builder::new_current_thread()
+		.enable_all()
+		.build()
+		.unwrap();
+	let result = BlockingClient::with_runtime(lazy_client(), Arc::new(runtime));
+
+	assert2::let_assert!(Err(error) = result);
+	check!(error.to_string().contains("must be multi threaded"));
+}
+
+#[test]
+fn accepts_a_multi_thread_runtime() {
+	let runtime = tokio::runtime::Builder::new_multi_thread()
+		.enable_all()
+		.build()
+		.unwrap();
+	let client = BlockingClient::with_runtime(lazy_client(), Arc::new(runtime)).unwrap();
+
+	check!(client.block_on(async { 3 }) == 3);
+}
+
+#[test]
+fn generates_the_blocking_functions() {
+	type Output = Result<Option<select_user::Output>, gel_errors::Error>;
+
+	let _: fn(&BlockingClient, &select_user::Input) -> Output = select_user::query_blocking;
+	let _: fn(&mut BlockingTransaction<'_>, &select_user::Input) -> Output =
+		select_user::transaction_blocking;
+}
@@ -0,0 +1,70 @@
+#![cfg(all(feature = "serde", feature = "testing"))]
+
+use assert2::check;
+use gelx::bulk::BulkItems;
+use gelx::testing::MockExecutor;
+
+/// The code generated for `insert User { name := <str>$name, slug :=
+/// <str>$slug } { name }` with `bulk = { queries = ["insert_user"], chunk_size
+/// = 2 }` by the `codegen_features` test.
+#[allow(unused, unused_qualifications, clippy::all)]
+mod generated {
+	include!("fixtures/bulk.rs");
+}
+
+use generated::insert_user;
+
+fn input(name: &str) -> insert_user::Input {
+	insert_user::Input {
+		name: name.to_string(),
+		slug: name.to_lowercase(),
+	}
+}
+
+fn output(name: &str) -> insert_user::Output {
+	insert_user::Output {
+		name: name.to_string(),
+	}
+}
+
+#[tokio::test]
+async fn inserts_the_inputs_in_chunks() {
+	let mut executor = MockExecutor::new();
+	executor
+		.expect(insert_user::QUERY_BULK)
+		.with_input(|items: &BulkItems| items.len() == 2)
+		.returns(vec![output("first"), output("second")]);
+	executor
+		.expect(insert_user::QUERY_BULK)
+		.with_input(|items: &BulkItems| {
+			items.len() == 1 && &*items.to_json() == r#"[{"name":"Third","slug":"third"}]"#
+		})
+		.returns(vec![output("Third")]);
+
+	let inputs = [input("First"), input("Second"), input("Third")];
+	let users = insert_user::execute_bulk(&mut executor, &inputs)
+		.await
+		.unwrap();
+
+	check!(users.len() == 3);
+	check!(users[2].name == "Third");
+	check!(executor.calls_to(insert_user::QUERY_BULK) == 2);
+	executor.verify();
+}
+
+#[tokio::test]
+async fn skips_the_query_without_inputs() {
+	let mut executor = MockExecutor::new();
+	let users = insert_user::execute_bulk(&mut executor, &[]).await.unwrap();
+
+	check!(users.is_empty());
+	check!(executor.calls().is_empty());
+}
+
+#[test]
+fn items_are_encoded_as_a_json_array() {
+	let mut items = BulkItems::with_capacity(1);
+	items.push([("name", gelx::bulk::to_value("first").unwrap())]);
+
+	check!(&*items.to_json() == r#"[{"name":"first"}]"#);
+}
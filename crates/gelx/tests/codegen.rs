@@ -1,10 +1,22 @@
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::thread;
 
+use bytes::BufMut;
+use bytes::Bytes;
+use bytes::BytesMut;
+use gel_protocol::codec::STD_DURATION;
+use gel_protocol::codec::STD_STR;
+use gel_protocol::common::Capabilities;
+use gel_protocol::common::Cardinality;
+use gel_protocol::common::RawTypedesc;
+use gel_protocol::features::ProtocolVersion;
+use gel_protocol::server_message::CommandDataDescription1;
 use gelx_core::*;
 use proc_macro2::Span;
 use rstest::fixture;
 use rstest::rstest;
+use uuid::Uuid;
 
 #[allow(clippy::perf)]
 fn get_features() -> String {
@@ -205,3 +217,476 @@ async fn can_generate_aliased_enums() -> GelxCoreResult<()> {
 
 	Ok(())
 }
+
+/// A query with descriptors which are encoded by hand, so the code of the
+/// crate features can be generated without a database.
+struct Fixture {
+	name: &'static str,
+	query: &'static str,
+	cardinality: Cardinality,
+	capabilities: Capabilities,
+	input: &'static [(&'static str, Uuid)],
+	output: &'static [(&'static str, Uuid)],
+}
+
+impl Fixture {
+	fn descriptor(&self) -> CommandDataDescription1 {
+		CommandDataDescription1 {
+			annotations: HashMap::default(),
+			capabilities: self.capabilities,
+			result_cardinality: self.cardinality,
+			input: encode_shape(Uuid::from_u128(0x1000), self.input),
+			output: encode_shape(Uuid::from_u128(0x2000), self.output),
+		}
+	}
+}
+
+/// Encode an object shape of required scalar elements with version 1.0 of
+/// the protocol.
+fn encode_shape(id: Uuid, elements: &[(&str, Uuid)]) -> RawTypedesc {
+	let proto = ProtocolVersion::new(1, 0);
+
+	if elements.is_empty() {
+		return RawTypedesc {
+			proto,
+			id: Uuid::from_u128(0),
+			data: Bytes::new(),
+		};
+	}
+
+	let mut data = BytesMut::new();
+
+	for (_, scalar) in elements {
+		data.put_u8(0x02);
+		data.put_slice(scalar.as_bytes());
+	}
+
+	data.put_u8(0x01);
+	data.put_slice(id.as_bytes());
+	data.put_u16(u16::try_from(elements.len()).unwrap());
+
+	for (position, (name, _)) in elements.iter().enumerate() {
+		data.put_u32(0);
+		data.put_u8(b'A');
+		data.put_u32(u32::try_from(name.len()).unwrap());
+		data.put_slice(name.as_bytes());
+		data.put_u16(u16::try_from(position).unwrap());
+	}
+
+	RawTypedesc {
+		proto,
+		id,
+		data: data.freeze(),
+	}
+}
+
+/// The features of the generated code without the derives which require the
+/// optional features of `gelx`.
+fn query_features() -> GelxFeatures {
+	GelxFeatures::builder()
+		.serde(GelxFeatureOptions::disabled())
+		.builder(GelxFeatureOptions::disabled())
+		.strum(GelxFeatureOptions::disabled())
+		.build()
+}
+
+const SELECT_USER: Fixture = Fixture {
+	name: "select_user",
+	query: "select User { slug } filter .slug = <str>$slug;",
+	cardinality: Cardinality::AtMostOne,
+	capabilities: Capabilities::empty(),
+	input: &[("slug", STD_STR)],
+	output: &[("slug", STD_STR)],
+};
+
+const SELECT_USERS: Fixture = Fixture {
+	name: "select_users",
+	cardinality: Cardinality::Many,
+	..SELECT_USER
+};
+
+const INSERT_USER: Fixture = Fixture {
+	name: "insert_user",
+	query: "insert User { name := <str>$name, slug := <str>$slug } { name };",
+	cardinality: Cardinality::One,
+	capabilities: Capabilities::MODIFICATIONS,
+	input: &[("name", STD_STR), ("slug", STD_STR)],
+	output: &[("name", STD_STR)],
+};
+
+/// Generate the query modules which the runtime tests of the crate features
+/// include from `tests/fixtures`.
+#[rstest]
+#[case::testing("testing", SELECT_USER, GelxMetadata::builder().features(query_features()).build())]
+#[case::instrument(
+	"instrument",
+	SELECT_USERS,
+	GelxMetadata::builder()
+		.features(GelxFeatures {
+			tracing: GelxFeatureOptions::Enabled(true),
+			..query_features()
+		})
+		.tracing(GelxTracingOptions::builder().record_fields(vec!["slug".into()]).build())
+		.build()
+)]
+#[case::blocking(
+	"blocking",
+	SELECT_USER,
+	GelxMetadata::builder()
+		.features(GelxFeatures {
+			blocking: GelxFeatureOptions::Enabled(true),
+			..query_features()
+		})
+		.build()
+)]
+#[case::verify(
+	"verify",
+	SELECT_USER,
+	GelxMetadata::builder()
+		.features(GelxFeatures {
+			verify: GelxFeatureOptions::Enabled(true),
+			..query_features()
+		})
+		.build()
+)]
+#[case::allowlist(
+	"allowlist",
+	SELECT_USER,
+	GelxMetadata::builder()
+		.features(GelxFeatures {
+			allowlist: GelxFeatureOptions::Enabled(true),
+			..query_features()
+		})
+		.build()
+)]
+#[case::bulk(
+	"bulk",
+	INSERT_USER,
+	GelxMetadata::builder()
+		.features(GelxFeatures {
+			serde: GelxFeatureOptions::Enabled(true),
+			..query_features()
+		})
+		.bulk(GelxBulkOptions::builder().queries(vec!["insert_user".into()]).chunk_size(2usize).build())
+		.build()
+)]
+fn codegen_features(
+	#[case] feature: &str,
+	#[case] fixture: Fixture,
+	#[case] metadata: GelxMetadata,
+) -> GelxCoreResult<()> {
+	// The derived queries return the same shape as the query.
+	let derived = DerivedDescriptors {
+		bulk: metadata
+			.is_bulk_query(fixture.name)
+			.then(|| fixture.descriptor()),
+		..DerivedDescriptors::default()
+	};
+	let code = generate_query_token_stream_with_context(
+		&fixture.descriptor(),
+		fixture.name,
+		fixture.query,
+		&metadata,
+		false,
+		QueryContext::builder().derived(&derived).build(),
+	)?;
+	let content = prettify(&code.to_string())?;
+	let path = PathBuf::from(CRATE_DIR).join(format!("tests/fixtures/{feature}.rs"));
+	let current = std::fs::read_to_string(&path).unwrap_or_default();
+
+	if current != content {
+		let is_ci = std::env::var("CI")
+			.ok()
+			.is_some_and(|v| ["1", "true"].contains(&v.as_str()));
+
+		assert2::assert!(!is_ci, "attempted updating the feature fixtures in CI");
+		std::fs::create_dir_all(path.parent().unwrap())?;
+		std::fs::write(&path, content)?;
+	}
+
+	Ok(())
+}
+
+#[test]
+fn bulk_fails_for_parameters_without_a_json_cast() {
+	let fixture = Fixture {
+		query: "insert User { name := <str>$name, timeout := <duration>$timeout } { name };",
+		input: &[("name", STD_STR), ("timeout", STD_DURATION)],
+		..INSERT_USER
+	};
+	let metadata = GelxMetadata::builder()
+		.bulk(
+			GelxBulkOptions::builder()
+				.queries(vec!["insert_user".into()])
+				.build(),
+		)
+		.build();
+	let derived = DerivedDescriptors {
+		bulk: Some(fixture.descriptor()),
+		..DerivedDescriptors::default()
+	};
+	let result = generate_query_token_stream_with_context(
+		&fixture.descriptor(),
+		fixture.name,
+		fixture.query,
+		&metadata,
+		false,
+		QueryContext::builder().derived(&derived).build(),
+	);
+
+	assert2::let_assert!(Err(error) = result);
+	assert2::check!(
+		error
+			.to_string()
+			.contains("the parameter `timeout` can't be cast from JSON")
+	);
+}
+
+/// Encode the input of `insert_user` with a named `default::Role` enum with
+/// version 2.0 of the protocol.
+fn encode_role_input() -> RawTypedesc {
+	let id = Uuid::from_u128(0x6000);
+	let mut data = BytesMut::new();
+
+	let mut scalar = BytesMut::new();
+	scalar.put_u8(0x03);
+	scalar.put_slice(STD_STR.as_bytes());
+	put_string(&mut scalar, "std::str");
+	scalar.put_u8(1);
+	scalar.put_u16(0);
+	put_descriptor(&mut data, &scalar);
+
+	let mut enumeration = BytesMut::new();
+	enumeration.put_u8(0x07);
+	enumeration.put_slice(Uuid::from_u128(0x6001).as_bytes());
+	put_string(&mut enumeration, "default::Role");
+	enumeration.put_u8(1);
+	enumeration.put_u16(0);
+	enumeration.put_u16(2);
+	put_string(&mut enumeration, "Editor");
+	put_string(&mut enumeration, "Admin");
+	put_descriptor(&mut data, &enumeration);
+
+	let mut shape = BytesMut::new();
+	shape.put_u8(0x08);
+	shape.put_slice(id.as_bytes());
+	shape.put_u16(2);
+
+	for (position, name) in ["name", "role"].into_iter().enumerate() {
+		shape.put_u32(0);
+		shape.put_u8(b'A');
+		put_string(&mut shape, name);
+		shape.put_u16(u16::try_from(position).unwrap());
+	}
+
+	put_descriptor(&mut data, &shape);
+
+	RawTypedesc {
+		proto: ProtocolVersion::new(2, 0),
+		id,
+		data: data.freeze(),
+	}
+}
+
+#[rstest]
+#[case::labels(GelxSerdeOptions::default(), true)]
+#[case::enum_rename_all(
+	GelxSerdeOptions::builder().enum_rename_all(SerdeRenameRule::SnakeCase).build(),
+	false
+)]
+fn bulk_enums_require_the_gel_labels(#[case] serde: GelxSerdeOptions, #[case] is_ok: bool) {
+	let fixture = Fixture {
+		query: "insert User { name := <str>$name, role := <Role>$role } { name };",
+		..INSERT_USER
+	};
+	let descriptor = CommandDataDescription1 {
+		input: encode_role_input(),
+		..fixture.descriptor()
+	};
+	let metadata = GelxMetadata::builder()
+		.serde(serde)
+		.bulk(
+			GelxBulkOptions::builder()
+				.queries(vec!["insert_user".into()])
+				.build(),
+		)
+		.build();
+	let derived = DerivedDescriptors {
+		bulk: Some(descriptor.clone()),
+		..DerivedDescriptors::default()
+	};
+	let result = generate_query_token_stream_with_context(
+		&descriptor,
+		fixture.name,
+		fixture.query,
+		&metadata,
+		false,
+		QueryContext::builder().derived(&derived).build(),
+	);
+
+	if is_ok {
+		assert2::check!(result.is_ok());
+	} else {
+		assert2::let_assert!(Err(error) = result);
+		assert2::check!(
+			error
+				.to_string()
+				.contains("the parameter `role` can't be cast from JSON")
+		);
+	}
+}
+
+#[rstest]
+#[case::order_by("select User { id, slug } order by .slug;", "order by")]
+#[case::limit("select User { id, slug } limit 10;", "limit")]
+#[case::offset("select User { id, slug } offset 10;", "offset")]
+fn stream_fails_for_top_level_ordering(#[case] query: &'static str, #[case] clause: &str) {
+	let fixture = Fixture {
+		query,
+		input: &[],
+		..SELECT_USERS
+	};
+	let metadata = GelxMetadata::builder()
+		.stream_queries(vec!["select_users".into()])
+		.build();
+	let result = generate_query_token_stream_with_context(
+		&fixture.descriptor(),
+		fixture.name,
+		fixture.query,
+		&metadata,
+		false,
+		QueryContext::default(),
+	);
+
+	assert2::let_assert!(Err(error) = result);
+	assert2::check!(
+		error
+			.to_string()
+			.contains(&format!("the top-level `{clause}` clause conflicts"))
+	);
+}
+
+/// Append a descriptor with the length prefix of version 2.0 of the protocol.
+fn put_descriptor(data: &mut BytesMut, descriptor: &[u8]) {
+	data.put_u32(u32::try_from(descriptor.len()).unwrap());
+	data.put_slice(descriptor);
+}
+
+fn put_string(data: &mut BytesMut, value: &str) {
+	data.put_u32(u32::try_from(value.len()).unwrap());
+	data.put_slice(value.as_bytes());
+}
+
+/// Encode the output of `select <object> { <field>, author: { name } }` with
+/// version 2.0 of the protocol, which describes the object types of the
+/// shapes.
+fn encode_authored_shape(id: u128, object: &str, field: &str) -> RawTypedesc {
+	let user_id = Uuid::from_u128(0x3000);
+	let user_shape_id = Uuid::from_u128(0x3001);
+	let object_id = Uuid::from_u128(id);
+	let shape_id = Uuid::from_u128(id + 1);
+	let mut data = BytesMut::new();
+
+	// 0: std::str
+	let mut scalar = BytesMut::new();
+	scalar.put_u8(0x03);
+	scalar.put_slice(STD_STR.as_bytes());
+	put_string(&mut scalar, "std::str");
+	scalar.put_u8(1);
+	scalar.put_u16(0);
+	put_descriptor(&mut data, &scalar);
+
+	// 1: default::User, 2: the shape of the author and 3: the object type.
+	for (id, name) in [(user_id, "default::User"), (object_id, object)] {
+		let mut object_type = BytesMut::new();
+		object_type.put_u8(0x0A);
+		object_type.put_slice(id.as_bytes());
+		put_string(&mut object_type, name);
+		object_type.put_u8(1);
+		put_descriptor(&mut data, &object_type);
+
+		if id != user_id {
+			continue;
+		}
+
+		let mut shape = BytesMut::new();
+		shape.put_u8(0x01);
+		shape.put_slice(user_shape_id.as_bytes());
+		shape.put_u8(0);
+		shape.put_u16(1);
+		shape.put_u16(1);
+		shape.put_u32(0);
+		shape.put_u8(b'A');
+		put_string(&mut shape, "name");
+		shape.put_u16(0);
+		shape.put_u16(1);
+		put_descriptor(&mut data, &shape);
+	}
+
+	// 4: the root shape.
+	let mut shape = BytesMut::new();
+	shape.put_u8(0x01);
+	shape.put_slice(shape_id.as_bytes());
+	shape.put_u8(0);
+	shape.put_u16(3);
+	shape.put_u16(2);
+
+	for (flags, name, type_pos) in [(0, field, 0), (0b100, "author", 2)] {
+		shape.put_u32(flags);
+		shape.put_u8(b'A');
+		put_string(&mut shape, name);
+		shape.put_u16(type_pos);
+		shape.put_u16(3);
+	}
+
+	put_descriptor(&mut data, &shape);
+
+	RawTypedesc {
+		proto: ProtocolVersion::new(2, 0),
+		id: shape_id,
+		data: data.freeze(),
+	}
+}
+
+#[test]
+fn identical_nested_shapes_are_generated_once() -> GelxCoreResult<()> {
+	let metadata = GelxMetadata::builder().features(query_features()).build();
+	let shapes = Shapes::new();
+	let mut generated = vec![];
+
+	for (name, id, object, field) in [
+		("select_posts", 0x4000, "default::Post", "title"),
+		("select_comments", 0x5000, "default::Comment", "body"),
+	] {
+		let descriptor = CommandDataDescription1 {
+			annotations: HashMap::default(),
+			capabilities: Capabilities::empty(),
+			result_cardinality: Cardinality::Many,
+			input: encode_shape(Uuid::from_u128(0x1000), &[]),
+			output: encode_authored_shape(id, object, field),
+		};
+		let query = format!("select {object} {{ {field}, author: {{ name }} }};");
+		let code = generate_query_token_stream_with_context(
+			&descriptor,
+			name,
+			&query,
+			&metadata,
+			false,
+			QueryContext::builder().shapes(&shapes).build(),
+		)?;
+
+		generated.push(code.to_string());
+	}
+
+	for code in &generated {
+		assert2::check!(code.contains("pub author : super :: shapes :: User ,"));
+		assert2::check!(!code.contains("pub struct User"));
+	}
+
+	let module = shapes.to_token_stream(&metadata).to_string();
+
+	assert2::check!(module.matches("pub mod shapes").count() == 1);
+	assert2::check!(module.matches("pub struct User").count() == 1);
+
+	Ok(())
+}
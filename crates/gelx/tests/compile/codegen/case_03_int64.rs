@@ -1,20 +1,46 @@
 pub mod example {
     use ::gelx::exports as __g;
+    /// Execute the query with any executor, e.g. a client or a transaction.
+    pub async fn execute(
+        mut executor: impl __g::Executor,
+    ) -> ::core::result::Result<Output, __g::gel_errors::Error> {
+        executor.query_required_single(QUERY, &()).await
+    }
     /// Execute the desired query.
     pub async fn query(
         client: &__g::gel_tokio::Client,
     ) -> ::core::result::Result<Output, __g::gel_errors::Error> {
-        client.query_required_single(QUERY, &()).await
+        execute(&client.with_tag(Some(QUERY_TAG))?).await
+    }
+    /// Execute the query with the provided options, e.g. a timeout, a
+    /// retry policy or the options of the transaction which runs the query.
+    pub async fn query_with(
+        client: &__g::gel_tokio::Client,
+        options: __g::QueryOptions,
+    ) -> ::core::result::Result<Output, __g::gel_errors::Error> {
+        let client = options.apply_to(&client.with_tag(Some(QUERY_TAG))?);
+        if options.is_transaction() {
+            return client
+                .transaction(|mut conn| async move { execute(&mut *conn).await })
+                .await;
+        }
+        execute(&client).await
     }
     /// Compose the query as part of a larger transaction.
     pub async fn transaction(
         conn: &mut __g::gel_tokio::Transaction,
     ) -> ::core::result::Result<Output, __g::gel_errors::Error> {
-        conn.query_required_single(QUERY, &()).await
+        execute(conn).await
     }
     pub type Input = ();
     pub type Output = i64;
+    /// The tag which attributes the statistics of the query in `sys::QueryStats`.
+    pub const QUERY_TAG: &str = "example";
+    /// The capabilities of the query reported by the database.
+    pub const CAPABILITIES: __g::Capabilities = __g::Capabilities::from_bits_retain(0);
     /// The original query string provided to the macro. Can be reused in your codebase.
     pub const QUERY: &str = "select 3";
+    /// The hash of the query which identifies it in the `queries.lock.json` manifest.
+    pub const QUERY_HASH: &str = "3efaf2f2e7527fc540b26c1517859b5446dff36e946f40b56f25f6941b170cc2";
 }
 fn main() {}
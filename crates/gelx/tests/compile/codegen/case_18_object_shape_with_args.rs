@@ -1,18 +1,40 @@
 pub mod example {
     use ::gelx::exports as __g;
+    /// Execute the query with any executor, e.g. a client or a transaction.
+    pub async fn execute(
+        mut executor: impl __g::Executor,
+        props: &Input,
+    ) -> ::core::result::Result<Vec<Output>, __g::gel_errors::Error> {
+        executor.query(QUERY, props).await
+    }
     /// Execute the desired query.
     pub async fn query(
         client: &__g::gel_tokio::Client,
         props: &Input,
     ) -> ::core::result::Result<Vec<Output>, __g::gel_errors::Error> {
-        client.query(QUERY, props).await
+        execute(&client.with_tag(Some(QUERY_TAG))?, props).await
+    }
+    /// Execute the query with the provided options, e.g. a timeout, a
+    /// retry policy or the options of the transaction which runs the query.
+    pub async fn query_with(
+        client: &__g::gel_tokio::Client,
+        props: &Input,
+        options: __g::QueryOptions,
+    ) -> ::core::result::Result<Vec<Output>, __g::gel_errors::Error> {
+        let client = options.apply_to(&client.with_tag(Some(QUERY_TAG))?);
+        if options.is_transaction() {
+            return client
+                .transaction(|mut conn| async move { execute(&mut *conn, props).await })
+                .await;
+        }
+        execute(&client, props).await
     }
     /// Compose the query as part of a larger transaction.
     pub async fn transaction(
         conn: &mut __g::gel_tokio::Transaction,
         props: &Input,
     ) -> ::core::result::Result<Vec<Output>, __g::gel_errors::Error> {
-        conn.query(QUERY, props).await
+        execute(conn, props).await
     }
     #[derive(
         ::std::fmt::Debug,
@@ -76,7 +98,13 @@ pub mod example {
         pub name: String,
         pub wallets: Vec<OutputWalletsSet>,
     }
+    /// The tag which attributes the statistics of the query in `sys::QueryStats`.
+    pub const QUERY_TAG: &str = "example";
+    /// The capabilities of the query reported by the database.
+    pub const CAPABILITIES: __g::Capabilities = __g::Capabilities::from_bits_retain(0);
     /// The original query string provided to the macro. Can be reused in your codebase.
     pub const QUERY: &str = "select Team {**} filter .name like <str>$starts_with ++ '%' and .description like '%' ++ <str>$ends_with;";
+    /// The hash of the query which identifies it in the `queries.lock.json` manifest.
+    pub const QUERY_HASH: &str = "2f0e813554fac6b05f0332d0d3439af6a0036cab6292cf9cb571b4df4e662fd4";
 }
 fn main() {}
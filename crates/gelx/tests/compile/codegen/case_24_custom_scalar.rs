@@ -1,18 +1,40 @@
 pub mod example {
     use ::gelx::exports as __g;
+    /// Execute the query with any executor, e.g. a client or a transaction.
+    pub async fn execute(
+        mut executor: impl __g::Modifying,
+        props: &Input,
+    ) -> ::core::result::Result<Output, __g::gel_errors::Error> {
+        executor.query_required_single(QUERY, props).await
+    }
     /// Execute the desired query.
     pub async fn query(
         client: &__g::gel_tokio::Client,
         props: &Input,
     ) -> ::core::result::Result<Output, __g::gel_errors::Error> {
-        client.query_required_single(QUERY, props).await
+        execute(&client.with_tag(Some(QUERY_TAG))?, props).await
+    }
+    /// Execute the query with the provided options, e.g. a timeout, a
+    /// retry policy or the options of the transaction which runs the query.
+    pub async fn query_with(
+        client: &__g::gel_tokio::Client,
+        props: &Input,
+        options: __g::QueryOptions,
+    ) -> ::core::result::Result<Output, __g::gel_errors::Error> {
+        let client = options.apply_to(&client.with_tag(Some(QUERY_TAG))?);
+        if options.is_transaction() {
+            return client
+                .transaction(|mut conn| async move { execute(&mut *conn, props).await })
+                .await;
+        }
+        execute(&client, props).await
     }
     /// Compose the query as part of a larger transaction.
     pub async fn transaction(
         conn: &mut __g::gel_tokio::Transaction,
         props: &Input,
     ) -> ::core::result::Result<Output, __g::gel_errors::Error> {
-        conn.query_required_single(QUERY, props).await
+        execute(conn, props).await
     }
     #[derive(
         ::std::fmt::Debug,
@@ -51,7 +73,13 @@ pub mod example {
         pub id: __g::uuid::Uuid,
         pub position: i32,
     }
+    /// The tag which attributes the statistics of the query in `sys::QueryStats`.
+    pub const QUERY_TAG: &str = "example";
+    /// The capabilities of the query reported by the database.
+    pub const CAPABILITIES: __g::Capabilities = __g::Capabilities::from_bits_retain(1);
     /// The original query string provided to the macro. Can be reused in your codebase.
     pub const QUERY: &str = "select (insert Simple { position := <default::Position>$position }) {**};";
+    /// The hash of the query which identifies it in the `queries.lock.json` manifest.
+    pub const QUERY_HASH: &str = "6d93f9c1f28cf72a6c91e52574123fa02bb04448fe6196bf6789b64b747ff64a";
 }
 fn main() {}
@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+
+use assert2::check;
+use gel_errors::ConstraintViolationError;
+use gel_errors::ErrorKind;
+use gel_errors::NoDataError;
+use gelx::constraint::ExclusiveViolation;
+use gelx::exports::bytes::Bytes;
+use rstest::rstest;
+
+const FIELD_DETAILS: u16 = 0x_00_02;
+
+fn violation(message: &str, details: Option<&str>) -> gel_errors::Error {
+	let headers = details
+		.map(|details| HashMap::from([(FIELD_DETAILS, Bytes::from(details.to_string()))]))
+		.unwrap_or_default();
+
+	ConstraintViolationError::with_message(message.to_string()).with_headers(headers)
+}
+
+#[rstest]
+#[case::pointer("slug violates exclusivity constraint", None, &["slug"], true)]
+#[case::other_pointer("name violates exclusivity constraint", None, &["slug"], false)]
+#[case::object_type("Project violates exclusivity constraint", None, &["creator", "slug"], true)]
+#[case::qualified_object_type("default::Project violates exclusivity constraint", None, &["creator", "slug"], true)]
+#[case::details(
+	"slug violates exclusivity constraint",
+	Some("value of property 'slug' of object type 'default::Project' violates exclusivity constraint"),
+	&["slug"],
+	true
+)]
+#[case::other_object_type(
+	"slug violates exclusivity constraint",
+	Some("value of property 'slug' of object type 'default::Team' violates exclusivity constraint"),
+	&["slug"],
+	false
+)]
+fn matches_exclusive_violations(
+	#[case] message: &str,
+	#[case] details: Option<&str>,
+	#[case] pointers: &[&str],
+	#[case] expected: bool,
+) {
+	let error = violation(message, details);
+	let violation = ExclusiveViolation::from_error(&error).unwrap();
+
+	check!(violation.matches("default::Project", pointers) == expected);
+}
+
+#[rstest]
+#[case::pointer("slug violates exclusivity constraint", &[&["slug"][..], &["creator", "slug"]], Some(0))]
+#[case::object_type("Project violates exclusivity constraint", &[&["slug"][..], &["creator", "slug"]], Some(1))]
+#[case::ambiguous(
+	"Project violates exclusivity constraint",
+	&[&["slug"][..], &["creator", "slug"], &["creator", "name"]],
+	None
+)]
+#[case::missing("name violates exclusivity constraint", &[&["slug"][..]], None)]
+fn finds_the_violated_constraint(
+	#[case] message: &str,
+	#[case] constraints: &[&[&str]],
+	#[case] expected: Option<usize>,
+) {
+	let error = violation(message, None);
+	let violation = ExclusiveViolation::from_error(&error).unwrap();
+
+	check!(violation.find("default::Project", constraints) == expected);
+}
+
+#[test]
+fn reads_the_object_type_from_the_details() {
+	let error = violation(
+		"slug violates exclusivity constraint",
+		Some(
+			"value of property 'slug' of object type 'default::Project' violates exclusivity \
+			 constraint",
+		),
+	);
+	let violation = ExclusiveViolation::from_error(&error).unwrap();
+
+	check!(violation.subject() == "slug");
+	check!(violation.object_type() == Some("default::Project"));
+}
+
+#[test]
+fn ignores_other_errors() {
+	let error = NoDataError::with_message("slug violates exclusivity constraint");
+	check!(ExclusiveViolation::from_error(&error).is_none());
+
+	let error = violation("value violates constraint `std::max_value`", None);
+	check!(ExclusiveViolation::from_error(&error).is_none());
+}
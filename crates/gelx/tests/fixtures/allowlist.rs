@@ -0,0 +1,70 @@
+pub mod select_user {
+    use ::gelx::exports as __g;
+    /// Execute the query with any executor, e.g. a client or a transaction.
+    pub async fn execute(
+        mut executor: impl __g::Executor,
+        props: &Input,
+    ) -> ::core::result::Result<Option<Output>, __g::gel_errors::Error> {
+        executor.query_single(QUERY, props).await
+    }
+    /// Execute the desired query.
+    pub async fn query(
+        client: &__g::gel_tokio::Client,
+        props: &Input,
+    ) -> ::core::result::Result<Option<Output>, __g::gel_errors::Error> {
+        __g::allowlist::check_hash(QUERY_HASH)?;
+        execute(&client.with_tag(Some(QUERY_TAG))?, props).await
+    }
+    /// Execute the query with the provided options, e.g. a timeout, a
+    /// retry policy or the options of the transaction which runs the query.
+    pub async fn query_with(
+        client: &__g::gel_tokio::Client,
+        props: &Input,
+        options: __g::QueryOptions,
+    ) -> ::core::result::Result<Option<Output>, __g::gel_errors::Error> {
+        __g::allowlist::check_hash(QUERY_HASH)?;
+        let client = options.apply_to(&client.with_tag(Some(QUERY_TAG))?);
+        if options.is_transaction() {
+            return client
+                .transaction(|mut conn| async move { execute(&mut *conn, props).await })
+                .await;
+        }
+        execute(&client, props).await
+    }
+    /// Compose the query as part of a larger transaction.
+    pub async fn transaction(
+        conn: &mut __g::gel_tokio::Transaction,
+        props: &Input,
+    ) -> ::core::result::Result<Option<Output>, __g::gel_errors::Error> {
+        execute(conn, props).await
+    }
+    #[derive(::std::fmt::Debug, ::core::clone::Clone, __g::gel_derive::Queryable)]
+    #[gel(crate_path = __g::gel_protocol)]
+    pub struct Input {
+        pub slug: String,
+    }
+    impl __g::gel_protocol::query_arg::QueryArgs for Input {
+        fn encode(
+            &self,
+            encoder: &mut __g::gel_protocol::query_arg::Encoder,
+        ) -> core::result::Result<(), __g::gel_errors::Error> {
+            let map = __g::gel_protocol::named_args! {
+                "slug" => self.slug.clone(),
+            };
+            map.encode(encoder)
+        }
+    }
+    #[derive(::std::fmt::Debug, ::core::clone::Clone, __g::gel_derive::Queryable)]
+    #[gel(crate_path = __g::gel_protocol)]
+    pub struct Output {
+        pub slug: String,
+    }
+    /// The tag which attributes the statistics of the query in `sys::QueryStats`.
+    pub const QUERY_TAG: &str = "select_user";
+    /// The capabilities of the query reported by the database.
+    pub const CAPABILITIES: __g::Capabilities = __g::Capabilities::from_bits_retain(0);
+    /// The original query string provided to the macro. Can be reused in your codebase.
+    pub const QUERY: &str = "select User { slug } filter .slug = <str>$slug;";
+    /// The hash of the query which identifies it in the `queries.lock.json` manifest.
+    pub const QUERY_HASH: &str = "62b91d58fc5f3da8d6f5d439f51527a2472a704b7f219eef0872848003eabc2f";
+}
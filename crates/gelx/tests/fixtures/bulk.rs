@@ -0,0 +1,117 @@
+pub mod insert_user {
+    use ::gelx::exports as __g;
+    /// Execute the query with any executor, e.g. a client or a transaction.
+    pub async fn execute(
+        mut executor: impl __g::Modifying,
+        props: &Input,
+    ) -> ::core::result::Result<Output, __g::gel_errors::Error> {
+        executor.query_required_single(QUERY, props).await
+    }
+    /// Execute the desired query.
+    pub async fn query(
+        client: &__g::gel_tokio::Client,
+        props: &Input,
+    ) -> ::core::result::Result<Output, __g::gel_errors::Error> {
+        execute(&client.with_tag(Some(QUERY_TAG))?, props).await
+    }
+    /// Execute the query with the provided options, e.g. a timeout, a
+    /// retry policy or the options of the transaction which runs the query.
+    pub async fn query_with(
+        client: &__g::gel_tokio::Client,
+        props: &Input,
+        options: __g::QueryOptions,
+    ) -> ::core::result::Result<Output, __g::gel_errors::Error> {
+        let client = options.apply_to(&client.with_tag(Some(QUERY_TAG))?);
+        if options.is_transaction() {
+            return client
+                .transaction(|mut conn| async move { execute(&mut *conn, props).await })
+                .await;
+        }
+        execute(&client, props).await
+    }
+    /// Compose the query as part of a larger transaction.
+    pub async fn transaction(
+        conn: &mut __g::gel_tokio::Transaction,
+        props: &Input,
+    ) -> ::core::result::Result<Output, __g::gel_errors::Error> {
+        execute(conn, props).await
+    }
+    /// Execute the query once for every input. The inputs are sent in chunks of
+    /// `BULK_CHUNK_SIZE` with a single round trip for each chunk.
+    pub async fn execute_bulk(
+        mut executor: impl __g::Modifying,
+        props: &[Input],
+    ) -> ::core::result::Result<Vec<Output>, __g::gel_errors::Error> {
+        let mut outputs = Vec::with_capacity(props.len());
+        for chunk in props.chunks(BULK_CHUNK_SIZE) {
+            let mut items = __g::bulk::BulkItems::with_capacity(chunk.len());
+            for props in chunk {
+                items
+                    .push([
+                        ("name", __g::bulk::to_value(&props.name)?),
+                        ("slug", __g::bulk::to_value(&props.slug)?),
+                    ]);
+            }
+            outputs.extend(executor.query::<Output, _>(QUERY_BULK, &items).await?);
+        }
+        Ok(outputs)
+    }
+    /// Execute the query once for every input. All the chunks run in a single
+    /// transaction so either every input or none of them is applied.
+    pub async fn query_bulk(
+        client: &__g::gel_tokio::Client,
+        props: &[Input],
+    ) -> ::core::result::Result<Vec<Output>, __g::gel_errors::Error> {
+        client
+            .with_tag(Some(QUERY_TAG))?
+            .transaction(|mut conn| async move { execute_bulk(&mut *conn, props).await })
+            .await
+    }
+    /// The maximum number of inputs sent with each bulk query.
+    pub const BULK_CHUNK_SIZE: usize = 2usize;
+    /// The query which runs the original query for every item of the `$items`
+    /// JSON array.
+    pub const QUERY_BULK: &str = "for item in json_array_unpack(<json>$items) union (\ninsert User { name := <str>item['name'], slug := <str>item['slug'] } { name }\n);";
+    #[derive(
+        ::std::fmt::Debug,
+        ::core::clone::Clone,
+        __g::serde::Serialize,
+        __g::serde::Deserialize,
+        __g::gel_derive::Queryable
+    )]
+    #[gel(crate_path = __g::gel_protocol)]
+    pub struct Input {
+        pub name: String,
+        pub slug: String,
+    }
+    impl __g::gel_protocol::query_arg::QueryArgs for Input {
+        fn encode(
+            &self,
+            encoder: &mut __g::gel_protocol::query_arg::Encoder,
+        ) -> core::result::Result<(), __g::gel_errors::Error> {
+            let map = __g::gel_protocol::named_args! {
+                "name" => self.name.clone(), "slug" => self.slug.clone(),
+            };
+            map.encode(encoder)
+        }
+    }
+    #[derive(
+        ::std::fmt::Debug,
+        ::core::clone::Clone,
+        __g::serde::Serialize,
+        __g::serde::Deserialize,
+        __g::gel_derive::Queryable
+    )]
+    #[gel(crate_path = __g::gel_protocol)]
+    pub struct Output {
+        pub name: String,
+    }
+    /// The tag which attributes the statistics of the query in `sys::QueryStats`.
+    pub const QUERY_TAG: &str = "insert_user";
+    /// The capabilities of the query reported by the database.
+    pub const CAPABILITIES: __g::Capabilities = __g::Capabilities::from_bits_retain(1);
+    /// The original query string provided to the macro. Can be reused in your codebase.
+    pub const QUERY: &str = "insert User { name := <str>$name, slug := <str>$slug } { name };";
+    /// The hash of the query which identifies it in the `queries.lock.json` manifest.
+    pub const QUERY_HASH: &str = "744288573e876001d95b6a43d28f6d9476ba90405d2b76d995a7f5fab99420f2";
+}
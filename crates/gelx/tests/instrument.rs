@@ -0,0 +1,126 @@
+#![cfg(all(feature = "tracing", feature = "testing"))]
+
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use assert2::check;
+use gel_errors::ErrorKind;
+use gel_errors::NoDataError;
+use gelx::testing::MockExecutor;
+use tracing::Event;
+use tracing::Id;
+use tracing::Metadata;
+use tracing::Subscriber;
+use tracing::field::Field;
+use tracing::field::Visit;
+use tracing::span::Attributes;
+use tracing::span::Record;
+
+/// The code generated for `select User { slug } filter .slug = <str>$slug`
+/// with `features = { tracing = true }` and `record_fields = ["slug"]` by the
+/// `codegen_features` test.
+#[allow(unused, unused_qualifications, clippy::all)]
+mod generated {
+	include!("fixtures/instrument.rs");
+}
+
+use generated::select_users;
+
+/// Collects the fields which are recorded on every span.
+#[derive(Clone, Default)]
+struct FieldsSubscriber {
+	fields: Arc<Mutex<Vec<(String, String)>>>,
+}
+
+impl FieldsSubscriber {
+	fn value(&self, name: &str) -> Option<String> {
+		self.fields
+			.lock()
+			.unwrap()
+			.iter()
+			.find(|(field, _)| field == name)
+			.map(|(_, value)| value.clone())
+	}
+}
+
+impl Visit for FieldsSubscriber {
+	fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+		self.fields
+			.lock()
+			.unwrap()
+			.push((field.name().to_string(), format!("{value:?}")));
+	}
+}
+
+impl Subscriber for FieldsSubscriber {
+	fn enabled(&self, _: &Metadata<'_>) -> bool {
+		true
+	}
+
+	fn new_span(&self, attributes: &Attributes<'_>) -> Id {
+		attributes.record(&mut self.clone());
+		Id::from_u64(1)
+	}
+
+	fn record(&self, _: &Id, values: &Record<'_>) {
+		values.record(&mut self.clone());
+	}
+
+	fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+	fn event(&self, _: &Event<'_>) {}
+
+	fn enter(&self, _: &Id) {}
+
+	fn exit(&self, _: &Id) {}
+}
+
+fn input(slug: &str) -> select_users::Input {
+	select_users::Input {
+		slug: slug.to_string(),
+	}
+}
+
+#[tokio::test]
+async fn records_the_query_span() {
+	let subscriber = FieldsSubscriber::default();
+	let _guard = tracing::subscriber::set_default(subscriber.clone());
+	let mut executor = MockExecutor::new();
+	executor.expect(select_users::QUERY).returns(vec![
+		select_users::Output {
+			slug: "first".into(),
+		},
+		select_users::Output {
+			slug: "second".into(),
+		},
+	]);
+
+	let users = select_users::execute(&mut executor, &input("test"))
+		.await
+		.unwrap();
+
+	check!(users.len() == 2);
+	check!(users[1].slug == "second");
+	check!(subscriber.value("query").as_deref() == Some("\"select_users\""));
+	check!(subscriber.value("cardinality").as_deref() == Some("\"many\""));
+	check!(subscriber.value("slug").as_deref() == Some("\"test\""));
+	check!(subscriber.value("rows").as_deref() == Some("2"));
+	check!(subscriber.value("elapsed_ms").is_some());
+	check!(subscriber.value("error").is_none());
+}
+
+#[tokio::test]
+async fn records_the_error_kind() {
+	let subscriber = FieldsSubscriber::default();
+	let _guard = tracing::subscriber::set_default(subscriber.clone());
+	let mut executor = MockExecutor::new();
+	executor
+		.expect(select_users::QUERY)
+		.returns_error(NoDataError::with_message("no data"));
+
+	let result = select_users::execute(&mut executor, &input("test")).await;
+
+	check!(result.is_err());
+	check!(subscriber.value("error").as_deref() == Some("\"NoDataError\""));
+	check!(subscriber.value("rows").is_none());
+}
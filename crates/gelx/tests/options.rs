@@ -0,0 +1,53 @@
+#![cfg(feature = "query")]
+
+use std::time::Duration;
+
+use assert2::check;
+use gel_tokio::Builder;
+use gel_tokio::Client;
+use gel_tokio::TransactionOptions;
+use gelx::QueryOptions;
+
+fn lazy_client() -> Client {
+	let config = Builder::new()
+		.dsn("gel://localhost:5656")
+		.without_system()
+		.build()
+		.unwrap();
+
+	Client::new(&config)
+}
+
+#[test]
+fn call_options_take_precedence_over_defaults() {
+	let defaults = QueryOptions::new()
+		.timeout(Duration::from_secs(30))
+		.transaction(TransactionOptions::default().read_only(true));
+	let options = QueryOptions::new()
+		.timeout(Duration::from_secs(1))
+		.no_retry()
+		.or(defaults);
+	let debug = format!("{options:?}");
+
+	check!(options.is_transaction());
+	check!(debug.contains("timeout: Some(1s)"));
+	check!(debug.contains("read_only: true"));
+}
+
+#[test]
+fn default_options_run_outside_of_a_transaction() {
+	check!(!QueryOptions::new().is_transaction());
+	check!(!QueryOptions::new().or(QueryOptions::new()).is_transaction());
+}
+
+#[tokio::test]
+async fn applies_the_options_to_the_client() {
+	let options = QueryOptions::new()
+		.timeout(Duration::from_millis(500))
+		.retry_attempts(3)
+		.transaction(TransactionOptions::default());
+
+	// The client connects lazily so the options can be applied without an
+	// instance.
+	let _client = options.apply_to(&lazy_client());
+}
@@ -0,0 +1,89 @@
+#![cfg(feature = "query")]
+
+use std::cell::RefCell;
+
+use assert2::check;
+use futures::StreamExt;
+use gel_errors::ErrorKind;
+use gel_errors::NoDataError;
+use gelx::stream::Page;
+use gelx::stream::keyset;
+use gelx::stream::pages;
+use uuid::Uuid;
+
+fn rows(count: u128) -> Vec<Uuid> {
+	(1..=count).map(Uuid::from_u128).collect()
+}
+
+#[tokio::test]
+async fn streams_every_page() {
+	let all_rows = rows(7);
+	let calls = RefCell::new(vec![]);
+	let stream = keyset(
+		3,
+		|row: &Uuid| *row,
+		|cursor, limit| {
+			calls.borrow_mut().push(cursor);
+			let page = all_rows
+				.iter()
+				.filter(|row| cursor.is_none_or(|cursor| **row > cursor))
+				.take(usize::try_from(limit).unwrap())
+				.copied()
+				.collect::<Vec<_>>();
+
+			async move { Ok(page) }
+		},
+	);
+	let streamed = stream.map(Result::unwrap).collect::<Vec<_>>().await;
+
+	check!(streamed == all_rows);
+	check!(*calls.borrow() == [None, Some(Uuid::from_u128(3)), Some(Uuid::from_u128(6))]);
+}
+
+#[tokio::test]
+async fn ends_after_an_error() {
+	let stream = keyset(
+		2,
+		|row: &Uuid| *row,
+		|_, _| async { Err::<Vec<Uuid>, _>(NoDataError::with_message("failed")) },
+	);
+	let streamed = stream.collect::<Vec<_>>().await;
+
+	check!(streamed.len() == 1);
+	check!(streamed[0].is_err());
+}
+
+#[test]
+fn offset_pages_end_when_they_are_not_full() {
+	let page = Page::from_offset(vec![1, 2, 3], 10, 3);
+	let last_page = Page::from_offset(vec![4], 13, 3);
+
+	check!(page.next_cursor == Some(13));
+	check!(last_page.next_cursor.is_none());
+	check!(!last_page.has_next());
+}
+
+#[test]
+fn keyset_pages_use_the_last_id_as_the_cursor() {
+	let page = Page::from_keyset(rows(2), 2, |row: &Uuid| *row);
+	let last_page = Page::from_keyset(rows(1), 2, |row: &Uuid| *row);
+
+	check!(page.next_cursor == Some(Uuid::from_u128(2)));
+	check!(last_page.next_cursor.is_none());
+}
+
+#[tokio::test]
+async fn pages_stream_the_items_of_every_page() {
+	let calls = RefCell::new(vec![]);
+	let stream = pages(|cursor: Option<i64>| {
+		calls.borrow_mut().push(cursor);
+		let offset = cursor.unwrap_or_default();
+		let items = (offset..7).take(3).collect::<Vec<_>>();
+
+		async move { Ok(Page::from_offset(items, offset, 3)) }
+	});
+	let streamed = stream.map(Result::unwrap).collect::<Vec<_>>().await;
+
+	check!(streamed == (0..7).collect::<Vec<_>>());
+	check!(*calls.borrow() == [None, Some(3), Some(6)]);
+}
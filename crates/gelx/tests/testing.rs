@@ -0,0 +1,117 @@
+#![cfg(feature = "testing")]
+
+use assert2::check;
+use gel_errors::ErrorKind;
+use gel_errors::NoDataError;
+use gelx::ReadOnly;
+use gelx::testing::MockExecutor;
+
+/// The code generated for `select User { slug } filter .slug = <str>$slug` by
+/// the `codegen_features` test.
+#[allow(unused, unused_qualifications, clippy::all)]
+mod generated {
+	include!("fixtures/testing.rs");
+}
+
+use generated::select_user;
+
+fn input(slug: &str) -> select_user::Input {
+	select_user::Input {
+		slug: slug.to_string(),
+	}
+}
+
+#[tokio::test]
+async fn returns_the_expected_output() {
+	let mut executor = MockExecutor::new();
+	executor
+		.expect(select_user::QUERY)
+		.with_input(|props: &select_user::Input| props.slug == "second")
+		.returns(Some(select_user::Output {
+			slug: "second".into(),
+		}));
+	executor
+		.expect(select_user::QUERY)
+		.returns(None::<select_user::Output>);
+
+	let first = select_user::execute(&mut executor, &input("first"))
+		.await
+		.unwrap();
+	let second = select_user::execute(&mut executor, &input("second"))
+		.await
+		.unwrap();
+
+	check!(first.is_none());
+	check!(second.unwrap().slug == "second");
+	check!(executor.calls_to(select_user::QUERY) == 2);
+	check!(
+		executor.calls()[0]
+			.input_type
+			.ends_with("select_user::Input")
+	);
+	executor.verify();
+}
+
+#[tokio::test]
+async fn returns_the_expected_error() {
+	let mut executor = MockExecutor::new();
+	executor
+		.expect(select_user::QUERY)
+		.returns_error(NoDataError::with_message("no data"));
+
+	let result = select_user::execute(&mut executor, &input("slug")).await;
+
+	check!(result.unwrap_err().is::<NoDataError>());
+}
+
+#[tokio::test]
+async fn runs_read_only_queries() {
+	let mut executor = ReadOnly(MockExecutor::new());
+	executor
+		.0
+		.expect(select_user::QUERY)
+		.returns(None::<select_user::Output>);
+
+	let result = select_user::execute(&mut executor, &input("slug")).await;
+
+	check!(select_user::CAPABILITIES.is_empty());
+	check!(result.unwrap().is_none());
+	check!(executor.0.calls_to(select_user::QUERY) == 1);
+}
+
+#[tokio::test]
+async fn can_spawn_the_queries() {
+	let mut executor = MockExecutor::new();
+	executor
+		.expect(select_user::QUERY)
+		.returns(None::<select_user::Output>);
+
+	// The futures of the executors are `Send` so they can run on another task.
+	let result = tokio::spawn(async move {
+		select_user::execute(&mut executor, &input("slug"))
+			.await
+			.map(|output| output.is_none())
+	})
+	.await
+	.unwrap();
+
+	check!(result.unwrap());
+}
+
+#[tokio::test]
+#[should_panic(expected = "no expectation matches the query")]
+async fn panics_for_unexpected_queries() {
+	let mut executor = MockExecutor::new();
+	let _ = select_user::execute(&mut executor, &input("slug")).await;
+}
+
+#[test]
+#[should_panic(expected = "expected queries which were never run")]
+fn verify_panics_for_unused_expectations() {
+	let mut executor = MockExecutor::new();
+	executor
+		.expect(select_user::QUERY)
+		.returns(None::<select_user::Output>);
+
+	executor.verify();
+}
@@ -0,0 +1,82 @@
+#![cfg(feature = "tower")]
+
+use std::convert::Infallible;
+use std::future::Ready;
+use std::future::ready;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::task::Context;
+use std::task::Poll;
+
+use assert2::check;
+use gel_tokio::Builder;
+use gel_tokio::Client;
+use gel_tokio::GlobalsDelta;
+use gel_tokio::state::GlobalsModifier;
+use gelx::tower::GlobalsLayer;
+use http::Request;
+use tower_layer::Layer;
+use tower_service::Service;
+
+#[derive(Debug, Clone, Default)]
+struct Globals {
+	current_user_id: Option<uuid::Uuid>,
+	/// Records the user ids which were applied to a client.
+	applied: Arc<Mutex<Vec<Option<uuid::Uuid>>>>,
+}
+
+impl GlobalsDelta for Globals {
+	fn apply(self, modifier: &mut GlobalsModifier<'_>) {
+		self.applied.lock().unwrap().push(self.current_user_id);
+		modifier.set("default::current_user_id", self.current_user_id);
+	}
+}
+
+/// Responds with whether the request has a client extension.
+#[derive(Debug, Clone)]
+struct HasClient;
+
+impl Service<Request<()>> for HasClient {
+	type Error = Infallible;
+	type Future = Ready<Result<bool, Infallible>>;
+	type Response = bool;
+
+	fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+		Poll::Ready(Ok(()))
+	}
+
+	fn call(&mut self, request: Request<()>) -> Self::Future {
+		ready(Ok(request.extensions().get::<Client>().is_some()))
+	}
+}
+
+fn client() -> Client {
+	let config = Builder::new()
+		.dsn("gel://localhost:5656")
+		.without_system()
+		.build()
+		.unwrap();
+
+	Client::new(&config)
+}
+
+#[tokio::test]
+async fn adds_the_client_with_globals() {
+	let mut service = GlobalsLayer::<Globals>::new(client()).layer(HasClient);
+	let globals = Globals {
+		current_user_id: Some(uuid::Uuid::nil()),
+		..Globals::default()
+	};
+	let mut request = Request::new(());
+	request.extensions_mut().insert(globals.clone());
+
+	check!(service.call(request).await == Ok(true));
+	check!(*globals.applied.lock().unwrap() == vec![Some(uuid::Uuid::nil())]);
+}
+
+#[tokio::test]
+async fn adds_the_client_without_globals() {
+	let mut service = GlobalsLayer::<Globals>::new(client()).layer(HasClient);
+
+	check!(service.call(Request::new(())).await == Ok(true));
+}
@@ -0,0 +1,51 @@
+use assert2::check;
+use gelx::validate::*;
+#[cfg(feature = "regex")]
+use rstest::rstest;
+
+#[cfg(feature = "regex")]
+const SLUG_PATTERN: &str = r"^(?=.{3,39}$)(?![_.-])(?!.*[_.-]{2})[a-zA-Z0-9._-]+(?<![_.-])$";
+
+#[cfg(feature = "regex")]
+#[rstest]
+#[case::valid("awesome-slug", true)]
+#[case::too_short("ab", false)]
+#[case::leading_separator("-slug", false)]
+#[case::repeated_separator("my--slug", false)]
+#[case::trailing_separator("slug_", false)]
+fn regexp_supports_lookaround(#[case] value: &str, #[case] valid: bool) {
+	check!(regexp("default::Actor.slug", value, SLUG_PATTERN).is_ok() == valid);
+}
+
+#[test]
+fn value_constraints() {
+	check!(min_value("default::Position", &0, &0).is_ok());
+	check!(max_value("default::Position", &1_000_000, &1_000_000).is_ok());
+	check!(min_ex_value("default::Position", &0, &0).is_err());
+	check!(max_ex_value("default::Position", &1.5, &1.0).is_err());
+
+	let error = min_value("default::Position", &-1, &0).unwrap_err();
+	check!(error.subject == "default::Position");
+	check!(error.constraint == "std::min_value");
+}
+
+#[test]
+fn length_constraints() {
+	check!(max_len_value("default::TestUser.name", "ñññ", 3).is_ok());
+	check!(max_len_value("default::TestUser.name", "abcd", 3).is_err());
+	check!(min_len_value("default::TestUser.name", "ab", 3).is_err());
+}
+
+#[test]
+fn one_of_constraint() {
+	check!(one_of("default::Color", &String::from("red"), &["red", "blue"]).is_ok());
+	check!(one_of("default::Level", &4, &[1, 2, 3]).is_err());
+}
+
+#[test]
+fn validation_error_converts_to_gel_error() {
+	let error = max_value("default::Position", &2, &1).unwrap_err();
+	let gel_error: gel_errors::Error = error.into();
+
+	check!(gel_error.is::<gel_errors::InvalidArgumentError>());
+}
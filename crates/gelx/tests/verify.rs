@@ -0,0 +1,105 @@
+#![cfg(feature = "verify")]
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::time::Duration;
+
+use assert2::check;
+use assert2::let_assert;
+use bytes::Bytes;
+use gel_errors::ClientError;
+use gel_protocol::common::Capabilities;
+use gel_protocol::common::Cardinality;
+use gel_protocol::common::RawTypedesc;
+use gel_protocol::features::ProtocolVersion;
+use gel_protocol::server_message::CommandDataDescription1;
+use gelx::verify::MismatchKind;
+use gelx::verify::QueryFingerprint;
+use uuid::Uuid;
+
+/// The code generated for `select User { slug } filter .slug = <str>$slug`
+/// with `features = { verify = true }` by the `codegen_features` test.
+#[allow(unused, unused_qualifications, clippy::all)]
+mod generated {
+	include!("fixtures/verify.rs");
+}
+
+use generated::select_user;
+
+fn descriptor(
+	input_id: Uuid,
+	output_id: Uuid,
+	cardinality: Cardinality,
+) -> CommandDataDescription1 {
+	let typedesc = |id| {
+		RawTypedesc {
+			proto: ProtocolVersion::current(),
+			id,
+			data: Bytes::new(),
+		}
+	};
+
+	CommandDataDescription1 {
+		annotations: HashMap::default(),
+		capabilities: Capabilities::empty(),
+		result_cardinality: cardinality,
+		input: typedesc(input_id),
+		output: typedesc(output_id),
+	}
+}
+
+#[test]
+fn registers_the_generated_fingerprints() {
+	let fingerprints = QueryFingerprint::registered();
+
+	check!(fingerprints == [&select_user::FINGERPRINT]);
+}
+
+#[tokio::test]
+async fn returns_the_connection_error() {
+	let_assert!(
+		Ok(config) = gel_tokio::Builder::new()
+			.host(IpAddr::from([127, 0, 0, 1]))
+			.port(1)
+			.wait_until_available(Duration::from_millis(100))
+			.without_system()
+			.build()
+	);
+	let client = gel_tokio::Client::new(&config);
+
+	let_assert!(Err(error) = gelx::verify_all(&client).await);
+	check!(error.is::<ClientError>());
+}
+
+#[test]
+fn matching_descriptors_have_no_mismatches() {
+	let fingerprint = select_user::FINGERPRINT;
+	let descriptor = descriptor(
+		fingerprint.input_id,
+		fingerprint.output_id,
+		Cardinality::AtMostOne,
+	);
+
+	check!(fingerprint.compare(&descriptor).is_empty());
+}
+
+#[test]
+fn reports_every_changed_part_of_the_descriptor() {
+	let fingerprint = select_user::FINGERPRINT;
+	let descriptor = descriptor(fingerprint.input_id, Uuid::nil(), Cardinality::Many);
+	let mismatches = fingerprint.compare(&descriptor);
+
+	check!(mismatches.len() == 2);
+	let_assert!(MismatchKind::Output { actual, .. } = &mismatches[0].kind);
+	check!(actual.is_nil());
+	let_assert!(
+		MismatchKind::Cardinality {
+			expected: Cardinality::AtMostOne,
+			actual: Cardinality::Many,
+		} = &mismatches[1].kind
+	);
+	check!(
+		mismatches[1].to_string()
+			== "`select_user`: the cardinality changed from AtMostOne to Many"
+	);
+}
@@ -8,6 +8,9 @@ use std::path::PathBuf;
 pub use gelx_core::GelxCoreError;
 pub use gelx_core::GelxCoreResult;
 pub use gelx_core::GelxMetadata;
+use gelx_core::Schema;
+use gelx_core::prettify;
+use gelx_core::query_paths;
 pub use tokio;
 use tokio::fs;
 use tokio::runtime::Runtime;
@@ -54,19 +57,76 @@ use tokio::runtime::Runtime;
 /// }
 /// ```
 pub async fn gelx_build() -> GelxCoreResult<GelxMetadata> {
-	let manifest_dir =
-		PathBuf::from(env::var("CARGO_MANIFEST_DIR").expect("CARGO_MANIFEST_DIR not set"));
-	let cargo_toml_path = manifest_dir.join("Cargo.toml");
+	let cargo_toml_path = manifest_dir().join("Cargo.toml");
 	println!("cargo::rerun-if-changed={}", cargo_toml_path.display());
 	let cargo_toml_contents = fs::read_to_string(&cargo_toml_path).await?;
-	// TODO: support output to OUT_DIR with shared enums rather than generating the
-	// enum every time.
 	let metadata = GelxMetadata::try_from(&cargo_toml_contents).unwrap_or_default();
 	set_metadata_env(&metadata)?;
 
 	Ok(metadata)
 }
 
+/// The name of the file written to the `OUT_DIR` by [`gelx_build_out_dir`].
+pub const OUT_FILE_NAME: &str = "gelx.rs";
+
+/// Generates the full module tree, the schema types and every query in the
+/// `queries_path`, into the `OUT_DIR` as a single file which can be included
+/// in your crate. The schema types are shared by all the queries rather than
+/// being generated for every macro expansion.
+///
+/// The build script is rerun whenever a query file or the schema directory
+/// changes. A connection to the database is required.
+///
+/// ```no_run
+/// // build.rs
+/// use gelx_build::GelxCoreResult;
+/// use gelx_build::gelx_build_out_dir;
+/// use gelx_build::tokio;
+///
+/// #[tokio::main]
+/// async fn main() -> GelxCoreResult<()> {
+/// 	gelx_build_out_dir().await?;
+/// 	Ok(())
+/// }
+/// ```
+///
+/// The generated code can then be included in your crate.
+///
+/// ```ignore
+/// // src/lib.rs
+/// #[allow(unused, unused_qualifications, clippy::all)]
+/// pub mod db {
+/// 	include!(concat!(env!("OUT_DIR"), "/gelx.rs"));
+/// }
+/// ```
+pub async fn gelx_build_out_dir() -> GelxCoreResult<GelxMetadata> {
+	let metadata = gelx_build().await?;
+	let manifest_dir = manifest_dir();
+	let out_dir = PathBuf::from(env::var("OUT_DIR").expect("OUT_DIR not set"));
+	let queries_path = manifest_dir.join(&metadata.queries_path);
+	println!("cargo::rerun-if-changed={}", queries_path.display());
+
+	for path in query_paths(&metadata, &manifest_dir)? {
+		println!("cargo::rerun-if-changed={}", path.display());
+	}
+
+	if let Some(schema_dir) = metadata.schema_dir(&manifest_dir) {
+		println!("cargo::rerun-if-changed={}", schema_dir.display());
+	}
+
+	let schema = Schema::try_new(&metadata).await?;
+	let mut tokens = schema.generate_inline_module(&metadata)?;
+	tokens.extend(
+		schema
+			.generate_query_outputs(&metadata, &manifest_dir)
+			.await?,
+	);
+	let content = prettify(&tokens.to_string())?;
+	fs::write(out_dir.join(OUT_FILE_NAME), content).await?;
+
+	Ok(metadata)
+}
+
 /// Sets the `GELX_METADATA_BASE64` environment variable to the base64 encoded
 /// [`GelxMetadata`] struct.
 pub fn set_metadata_env(metadata: &GelxMetadata) -> GelxCoreResult<()> {
@@ -92,3 +152,25 @@ pub fn gelx_build_sync() -> GelxCoreResult<GelxMetadata> {
 	let rt = Runtime::new()?;
 	rt.block_on(async { gelx_build().await })
 }
+
+/// Generates the code into the `OUT_DIR` in a sync environment. See
+/// [`gelx_build_out_dir`] for more information.
+///
+/// ```no_run
+/// // build.rs
+/// use gelx_build::gelx_build_out_dir_sync;
+/// use gelx_core::GelxCoreResult;
+///
+/// fn main() -> GelxCoreResult<()> {
+/// 	gelx_build_out_dir_sync()?;
+/// 	Ok(())
+/// }
+/// ```
+pub fn gelx_build_out_dir_sync() -> GelxCoreResult<GelxMetadata> {
+	let rt = Runtime::new()?;
+	rt.block_on(async { gelx_build_out_dir().await })
+}
+
+fn manifest_dir() -> PathBuf {
+	PathBuf::from(env::var("CARGO_MANIFEST_DIR").expect("CARGO_MANIFEST_DIR not set"))
+}
@@ -8,11 +8,10 @@ use gelx_core::GelxCoreError;
 use gelx_core::GelxCoreResult;
 use gelx_core::GelxMetadata;
 use gelx_core::ModuleOutputs;
-use gelx_core::generate_module_outputs;
-use gelx_core::generate_query_token_stream;
-use gelx_core::get_descriptor;
-use heck::ToSnakeCase;
-use proc_macro2::TokenStream;
+use gelx_core::QueryManifest;
+use gelx_core::QueryStatsSummary;
+use gelx_core::Schema;
+use gelx_core::query_paths;
 use similar::ChangeTag;
 use similar::TextDiff;
 use tokio::fs;
@@ -66,6 +65,7 @@ impl Cli {
 		match self.command {
 			Commands::Generate { json } => Self::generate(&metadata, &root_path, json).await,
 			Commands::Check => Self::check(&metadata, &root_path).await,
+			Commands::Stats { json } => Self::stats(&metadata, &root_path, json).await,
 		}
 	}
 
@@ -74,44 +74,31 @@ impl Cli {
 		metadata: &GelxMetadata,
 		root_path: impl AsRef<Path>,
 	) -> GelxCoreResult<ModuleOutputs> {
-		let root_path = root_path.as_ref();
-		let mut query_tokens = TokenStream::new();
-		let queries_path = root_path.join(&metadata.queries_path);
-
-		if queries_path.is_dir() {
-			// not async to make sorting easier
-			let mut entries = queries_path.read_dir()?.collect::<Result<Vec<_>, _>>()?;
-			entries.sort_by_key(std::fs::DirEntry::path);
+		let (outputs, _) = Self::outputs_with_manifest(metadata, root_path).await?;
 
-			for entry in entries {
-				let path = entry.path();
+		Ok(outputs)
+	}
 
-				if !path.is_file() || path.extension().is_none_or(|ext| ext != "edgeql") {
-					continue;
-				}
+	/// Generates the module outputs and the `queries.lock.json` manifest from
+	/// the queries in the current directory.
+	pub async fn outputs_with_manifest(
+		metadata: &GelxMetadata,
+		root_path: impl AsRef<Path>,
+	) -> GelxCoreResult<(ModuleOutputs, QueryManifest)> {
+		let root_path = root_path.as_ref();
 
-				let query_content = fs::read_to_string(&path).await?;
-				let file_stem = path.file_stem().unwrap_or_default().to_string_lossy();
-				let module_name = file_stem.to_snake_case();
-
-				eprintln!("Processing query: {}", path.display());
-				let descriptor = get_descriptor(&query_content, metadata).await?;
-				let token_stream = generate_query_token_stream(
-					&descriptor,
-					&module_name,
-					&query_content,
-					metadata,
-					false,
-				)?;
-
-				query_tokens.extend(token_stream);
-			}
+		for path in query_paths(metadata, root_path)? {
+			eprintln!("Processing query: {}", path.display());
 		}
 
-		let mut outputs = generate_module_outputs(metadata).await?;
+		let schema = Schema::try_new(metadata).await?;
+		let (query_tokens, manifest) = schema
+			.generate_query_outputs_with_manifest(metadata, root_path)
+			.await?;
+		let mut outputs = schema.generate_module_outputs(metadata)?;
 		outputs.append_to_root(&query_tokens);
 
-		Ok(outputs)
+		Ok((outputs, manifest))
 	}
 
 	/// Generates Rust code from the crate in the current directory.
@@ -122,7 +109,7 @@ impl Cli {
 	) -> GelxCoreResult<()> {
 		eprintln!("Generating code...");
 		let root_path = root_path.as_ref();
-		let outputs = Self::outputs(metadata, root_path).await?;
+		let (outputs, manifest) = Self::outputs_with_manifest(metadata, root_path).await?;
 		let output_path = root_path.join(&metadata.output_path);
 
 		if json {
@@ -133,6 +120,7 @@ impl Cli {
 			fs::create_dir_all(&output_path).await?;
 			eprintln!("Writing generated code to {}", output_path.display());
 			outputs.write_to_fs(&output_path).await?;
+			fs::write(root_path.join(&metadata.manifest_path), manifest.to_json()?).await?;
 
 			eprintln!(
 				"Successfully wrote generated code to {}",
@@ -159,11 +147,24 @@ impl Cli {
 			return Err(GelxCoreError::Custom(message));
 		}
 
-		let generated_map = Self::outputs(metadata, root_path).await?.to_map()?;
+		let (outputs, manifest) = Self::outputs_with_manifest(metadata, root_path).await?;
+		let generated_map = outputs.to_map()?;
 		let existing_map = ModuleOutputs::try_new(&output_path, &output_path)
 			.await?
 			.to_map()?;
 		let mut comparison = Vec::new();
+		let manifest_json = manifest.to_json()?;
+
+		match fs::read_to_string(root_path.join(&metadata.manifest_path)).await {
+			Ok(existing_json) if existing_json == manifest_json => {}
+			Ok(existing_json) => {
+				comparison.push(Comparison::Change(
+					metadata.manifest_path.clone(),
+					diff_lines(&existing_json, &manifest_json),
+				));
+			}
+			Err(_) => comparison.push(Comparison::Add(metadata.manifest_path.clone())),
+		}
 
 		for (path, content) in &generated_map {
 			let Some(existing_content) = existing_map.get(path) else {
@@ -172,19 +173,10 @@ impl Cli {
 			};
 
 			if existing_content != content {
-				let diff = TextDiff::from_lines(existing_content, content);
-				let mut changes = Vec::new();
-
-				for change in diff.iter_all_changes() {
-					let sign = match change.tag() {
-						ChangeTag::Delete => "-",
-						ChangeTag::Insert => "+",
-						ChangeTag::Equal => " ",
-					};
-					changes.push(format!("{sign}{change}"));
-				}
-
-				comparison.push(Comparison::Change(path.clone(), changes));
+				comparison.push(Comparison::Change(
+					path.clone(),
+					diff_lines(existing_content, content),
+				));
 			}
 		}
 
@@ -219,6 +211,46 @@ impl Cli {
 
 		Ok(())
 	}
+
+	/// Reports the statistics of the generated queries from `sys::QueryStats`.
+	pub async fn stats(
+		metadata: &GelxMetadata,
+		root_path: impl AsRef<Path>,
+		json: bool,
+	) -> GelxCoreResult<()> {
+		let summaries = QueryStatsSummary::try_new(metadata, root_path).await?;
+
+		if json {
+			println!("{}", serde_json::to_string_pretty(&summaries)?);
+			return Ok(());
+		}
+
+		if summaries.is_empty() {
+			eprintln!("No statistics were recorded for the generated queries.");
+			return Ok(());
+		}
+
+		let width = summaries
+			.iter()
+			.map(|summary| summary.tag.len())
+			.max()
+			.unwrap_or_default()
+			.max("query".len());
+
+		println!(
+			"{:<width$}  {:>10}  {:>12}  {:>12}  {:>12}",
+			"query", "calls", "mean (ms)", "stddev (ms)", "max (ms)"
+		);
+
+		for summary in &summaries {
+			println!(
+				"{:<width$}  {:>10}  {:>12.3}  {:>12.3}  {:>12.3}",
+				summary.tag, summary.calls, summary.mean_ms, summary.stddev_ms, summary.max_ms
+			);
+		}
+
+		Ok(())
+	}
 }
 
 #[derive(Parser, Debug)]
@@ -232,6 +264,29 @@ pub enum Commands {
 	},
 	/// Checks if the generated Rust code is up-to-date
 	Check,
+	/// Reports the latency of the generated queries from `sys::QueryStats`
+	Stats {
+		/// Print the statistics as JSON to stdout.
+		#[clap(long)]
+		json: bool,
+	},
+}
+
+/// The lines of the diff between the existing and the generated content.
+fn diff_lines(existing: &str, generated: &str) -> Vec<String> {
+	let diff = TextDiff::from_lines(existing, generated);
+	let mut changes = Vec::new();
+
+	for change in diff.iter_all_changes() {
+		let sign = match change.tag() {
+			ChangeTag::Delete => "-",
+			ChangeTag::Insert => "+",
+			ChangeTag::Equal => " ",
+		};
+		changes.push(format!("{sign}{change}"));
+	}
+
+	changes
 }
 
 pub enum Comparison {
@@ -0,0 +1,83 @@
+use gel_tokio::Queryable;
+use heck::ToSnakeCase;
+
+use super::AnnotationsSet;
+use super::SchemaCardinality;
+use super::ToModuleName;
+
+/// Execute the desired query.
+pub async fn query_config_types(
+	client: &gel_tokio::Client,
+) -> ::core::result::Result<Vec<ConfigTypeOutput>, gel_errors::Error> {
+	client.query(CONFIG_QUERY, &()).await
+}
+
+#[derive(Debug, Clone, Queryable)]
+pub struct ConfigTarget {
+	pub id: uuid::Uuid,
+	pub name: String,
+}
+
+#[derive(Debug, Clone, Queryable)]
+pub struct ConfigProperty {
+	pub name: String,
+	pub cardinality: Option<SchemaCardinality>,
+	pub target: Option<ConfigTarget>,
+	pub annotations: Vec<AnnotationsSet>,
+}
+
+/// The `cfg::Config` type or one of the extension config types, e.g.
+/// `ext::pgvector::Config`.
+#[derive(Debug, Clone, Queryable)]
+pub struct ConfigTypeOutput {
+	pub name: String,
+	pub properties: Vec<ConfigProperty>,
+}
+
+impl ConfigTypeOutput {
+	/// Extension config types extend `cfg::ExtensionConfig` and are defined in
+	/// the `ext` module.
+	pub fn is_extension(&self) -> bool {
+		self.name != "cfg::Config"
+	}
+
+	/// The key used to set the property for the session, e.g.
+	/// `apply_access_policies` or `ext::pgvector::Config::probes`.
+	pub fn config_key(&self, property: &ConfigProperty) -> String {
+		if self.is_extension() {
+			format!("{}::{}", self.name, property.name)
+		} else {
+			property.name.clone()
+		}
+	}
+
+	/// The name of the generated field for the property. Extension properties
+	/// are prefixed with the extension name, e.g. `pgvector_probes` for
+	/// `ext::pgvector::Config::probes`.
+	pub fn field_name(&self, property: &ConfigProperty) -> String {
+		let module_name = self.name.to_module_name();
+
+		if !self.is_extension() {
+			return property.name.to_snake_case();
+		}
+
+		let prefix = module_name
+			.modules
+			.iter()
+			.filter(|module| module.as_str() != "ext")
+			.map(|module| module.to_snake_case())
+			.collect::<Vec<_>>()
+			.join("_");
+
+		format!("{prefix}_{}", property.name.to_snake_case())
+	}
+}
+
+/// The settings which can be configured for a session. Settings which can only
+/// be configured for the whole instance are annotated with `cfg::system`.
+pub const CONFIG_QUERY: &str = "with module schema select ObjectType {name, properties: {name, \
+                                cardinality, target: {id, name}, annotations: {name, value := \
+                                @value}} filter .name != 'id' and not exists (select .annotations \
+                                filter .name in {'cfg::system', 'cfg::internal'} and @value = \
+                                'true')} filter .name = 'cfg::Config' or (not .abstract and \
+                                'cfg::ExtensionConfig' in .ancestors.name) order by .name";
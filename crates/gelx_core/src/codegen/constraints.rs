@@ -0,0 +1,416 @@
+use gel_protocol::codec::STD_FLOAT32;
+use gel_protocol::codec::STD_FLOAT64;
+use gel_protocol::codec::STD_INT16;
+use gel_protocol::codec::STD_INT32;
+use gel_protocol::codec::STD_INT64;
+use gel_protocol::codec::STD_STR;
+use gel_tokio::Queryable;
+use heck::ToPascalCase;
+use proc_macro2::Literal;
+use proc_macro2::TokenStream;
+use quote::format_ident;
+use quote::quote;
+use syn::Ident;
+use uuid::Uuid;
+
+use super::Exclusives;
+use super::ObjectType;
+use crate::FeatureName;
+use crate::GelxMetadata;
+
+#[derive(Clone, Debug, Queryable)]
+pub struct ConstraintParamsSet {
+	pub name: String,
+	pub value: Option<String>,
+}
+
+#[derive(Clone, Debug, Queryable)]
+pub struct ConstraintsSet {
+	pub name: String,
+	pub params: Vec<ConstraintParamsSet>,
+}
+
+/// A schema constraint which can be checked on the client.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Constraint {
+	MinValue(String),
+	MinExValue(String),
+	MaxValue(String),
+	MaxExValue(String),
+	MinLenValue(usize),
+	MaxLenValue(usize),
+	Regexp(String),
+	OneOf(Vec<String>),
+}
+
+impl Constraint {
+	/// Convert the introspected constraint. Returns `None` for constraints
+	/// which can't be checked on the client.
+	pub fn from_set(set: &ConstraintsSet) -> Option<Self> {
+		let value = set.params.first()?.value.as_deref()?.trim();
+
+		let constraint = match set.name.as_str() {
+			"std::min_value" => Self::MinValue(unquote(value).unwrap_or(value).to_string()),
+			"std::min_ex_value" => Self::MinExValue(unquote(value).unwrap_or(value).to_string()),
+			"std::max_value" => Self::MaxValue(unquote(value).unwrap_or(value).to_string()),
+			"std::max_ex_value" => Self::MaxExValue(unquote(value).unwrap_or(value).to_string()),
+			"std::min_len_value" => Self::MinLenValue(parse_integer(value)?),
+			"std::max_len_value" => Self::MaxLenValue(parse_integer(value)?),
+			"std::regexp" => {
+				Self::Regexp(parse_string_literal(value).unwrap_or_else(|| value.to_string()))
+			}
+			"std::one_of" => Self::OneOf(split_array_literal(value)),
+			_ => return None,
+		};
+
+		Some(constraint)
+	}
+
+	/// Generate the tokens which check the `value` expression against this
+	/// constraint. The `value` expression must be a reference to the checked
+	/// value.
+	pub(crate) fn check_tokens(
+		&self,
+		target: ConstraintTarget,
+		subject: &str,
+		value: &TokenStream,
+		exports_ident: &Ident,
+	) -> Option<TokenStream> {
+		let validate = quote!(#exports_ident::validate);
+		let tokens = match (self, target) {
+			(Self::MinValue(min), ConstraintTarget::Number { float }) => {
+				let min = number_literal(min, float)?;
+				quote!(#validate::min_value(#subject, #value, &#min)?;)
+			}
+			(Self::MinExValue(min), ConstraintTarget::Number { float }) => {
+				let min = number_literal(min, float)?;
+				quote!(#validate::min_ex_value(#subject, #value, &#min)?;)
+			}
+			(Self::MaxValue(max), ConstraintTarget::Number { float }) => {
+				let max = number_literal(max, float)?;
+				quote!(#validate::max_value(#subject, #value, &#max)?;)
+			}
+			(Self::MaxExValue(max), ConstraintTarget::Number { float }) => {
+				let max = number_literal(max, float)?;
+				quote!(#validate::max_ex_value(#subject, #value, &#max)?;)
+			}
+			(Self::MinLenValue(min), ConstraintTarget::Str) => {
+				quote!(#validate::min_len_value(#subject, #value, #min)?;)
+			}
+			(Self::MaxLenValue(max), ConstraintTarget::Str) => {
+				quote!(#validate::max_len_value(#subject, #value, #max)?;)
+			}
+			(Self::Regexp(pattern), ConstraintTarget::Str) => {
+				quote!(#validate::regexp(#subject, #value, #pattern)?;)
+			}
+			(Self::OneOf(options), ConstraintTarget::Str) => {
+				let options = options
+					.iter()
+					.map(|option| parse_string_literal(option))
+					.collect::<Option<Vec<_>>>()?;
+				quote!(#validate::one_of(#subject, #value, &[#(#options),*])?;)
+			}
+			(Self::OneOf(options), ConstraintTarget::Number { float }) => {
+				let options = options
+					.iter()
+					.map(|option| number_literal(option, float))
+					.collect::<Option<Vec<_>>>()?;
+				quote!(#validate::one_of(#subject, #value, &[#(#options),*])?;)
+			}
+			_ => return None,
+		};
+
+		Some(tokens)
+	}
+}
+
+/// The kind of rust value which a constraint is checked against.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ConstraintTarget {
+	Str,
+	Number { float: bool },
+}
+
+impl ConstraintTarget {
+	/// Get the target for the material scalar type with the provided id.
+	pub(crate) fn from_material_id(id: &Uuid) -> Option<Self> {
+		match *id {
+			STD_STR => Some(Self::Str),
+			STD_INT16 | STD_INT32 | STD_INT64 => Some(Self::Number { float: false }),
+			STD_FLOAT32 | STD_FLOAT64 => Some(Self::Number { float: true }),
+			_ => None,
+		}
+	}
+}
+
+/// Generate the checks for all the constraints which are supported by the
+/// `target`. The `std::regexp` checks are only generated when the `regex`
+/// feature is enabled since they require the `regex` feature of `gelx`.
+pub(crate) fn constraint_checks(
+	constraints: &[Constraint],
+	target: ConstraintTarget,
+	subject: &str,
+	value: &TokenStream,
+	metadata: &GelxMetadata,
+	is_macro: bool,
+) -> Vec<TokenStream> {
+	let exports_ident = metadata.exports_alias_ident();
+	let has_regex = metadata.features.is_enabled(FeatureName::Regex, is_macro);
+	let regex_annotation = metadata.features.annotate(FeatureName::Regex, is_macro);
+
+	constraints
+		.iter()
+		.filter_map(|constraint| {
+			let tokens = constraint.check_tokens(target, subject, value, &exports_ident)?;
+
+			match constraint {
+				Constraint::Regexp(_) if !has_regex => None,
+				Constraint::Regexp(_) => Some(quote!(#regex_annotation #tokens)),
+				_ => Some(tokens),
+			}
+		})
+		.collect()
+}
+
+/// Generate the `<Type>ConstraintError` enum with a variant for every
+/// exclusive constraint of the object type. Returns `None` when the object type
+/// has no exclusive constraints.
+pub(crate) fn generate_constraint_error(
+	object_type: &ObjectType,
+	local_name: &str,
+	exports_ident: &Ident,
+) -> Option<TokenStream> {
+	let type_name = &object_type.name;
+	let enum_ident = format_ident!("{}ConstraintError", local_name.to_pascal_case());
+	let mut variants = object_type
+		.exclusives
+		.iter()
+		.map(|exclusive| {
+			let pointers = match exclusive {
+				Exclusives::One(pointer) => vec![pointer.name.as_str()],
+				Exclusives::Many(pointers) => pointers.iter().map(|p| p.name.as_str()).collect(),
+			};
+			let name = pointers
+				.iter()
+				.map(|p| p.to_pascal_case())
+				.collect::<String>();
+
+			(format_ident!("{name}Taken"), pointers)
+		})
+		.collect::<Vec<_>>();
+
+	if variants.is_empty() {
+		return None;
+	}
+
+	// The order of the introspected constraints isn't stable.
+	variants.sort_by_key(|(ident, _)| ident.to_string());
+	variants.dedup_by_key(|(ident, _)| ident.to_string());
+
+	let enum_doc =
+		format!(" The exclusive constraints of `{type_name}` which can be violated by a mutation.");
+	let variant_tokens = variants.iter().map(|(ident, pointers)| {
+		let doc = format!(" The {} is already taken.", violation_subject(pointers));
+		quote! {
+			#[doc = #doc]
+			#ident
+		}
+	});
+	let variant_idents = variants.iter().map(|(ident, _)| ident);
+	let constraints_tokens = variants
+		.iter()
+		.map(|(_, pointers)| quote!(&[#(#pointers),*]));
+	let pointers_tokens = variants
+		.iter()
+		.map(|(ident, pointers)| quote!(Self::#ident => &[#(#pointers),*]));
+	let message_tokens = variants.iter().map(|(ident, pointers)| {
+		let message = format!("the {} is already taken", violation_subject(pointers));
+		quote!(Self::#ident => #message)
+	});
+
+	let tokens = quote! {
+		#[doc = #enum_doc]
+		#[derive(::std::fmt::Debug, ::core::clone::Clone, ::core::marker::Copy, ::core::cmp::PartialEq, ::core::cmp::Eq)]
+		pub enum #enum_ident {
+			#(#variant_tokens),*
+		}
+		impl #enum_ident {
+			/// Classify the error returned by a mutation. Returns `None` when the
+			/// error isn't a violation of one of the exclusive constraints, or when
+			/// the violated constraint is ambiguous.
+			pub fn from_error(error: &#exports_ident::gel_errors::Error) -> Option<Self> {
+				let violation = #exports_ident::constraint::ExclusiveViolation::from_error(error)?;
+				let index = violation.find(#type_name, &[#(#constraints_tokens),*])?;
+				[#(Self::#variant_idents),*].get(index).copied()
+			}
+			/// The names of the pointers which are constrained.
+			pub fn pointers(&self) -> &'static [&'static str] {
+				match self {
+					#(#pointers_tokens),*
+				}
+			}
+		}
+		impl ::core::fmt::Display for #enum_ident {
+			fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+				let message = match self {
+					#(#message_tokens),*
+				};
+				f.write_str(message)
+			}
+		}
+		impl ::std::error::Error for #enum_ident {}
+	};
+
+	Some(tokens)
+}
+
+/// Describe the constrained pointers, e.g. `` combination of `creator` and
+/// `slug` ``.
+fn violation_subject(pointers: &[&str]) -> String {
+	match pointers {
+		[pointer] => format!("`{pointer}`"),
+		[rest @ .., last] => {
+			let rest = rest
+				.iter()
+				.map(|pointer| format!("`{pointer}`"))
+				.collect::<Vec<_>>()
+				.join(", ");
+			format!("combination of {rest} and `{last}`")
+		}
+		[] => String::new(),
+	}
+}
+
+fn number_literal(value: &str, float: bool) -> Option<Literal> {
+	let value = value.replace('_', "");
+
+	if float {
+		return value.parse::<f64>().ok().map(Literal::f64_unsuffixed);
+	}
+
+	let number = value.parse::<i64>().ok()?;
+	let digits = number.unsigned_abs().to_string();
+
+	if digits.len() <= 4 {
+		return Some(Literal::i64_unsuffixed(number));
+	}
+
+	// Group the digits to keep the generated code readable.
+	let mut grouped = String::new();
+
+	for (index, char) in digits.chars().enumerate() {
+		if index > 0 && (digits.len() - index) % 3 == 0 {
+			grouped.push('_');
+		}
+
+		grouped.push(char);
+	}
+
+	let sign = if number < 0 { "-" } else { "" };
+
+	format!("{sign}{grouped}").parse().ok()
+}
+
+fn parse_integer(value: &str) -> Option<usize> {
+	unquote(value)
+		.unwrap_or(value)
+		.replace('_', "")
+		.parse()
+		.ok()
+}
+
+/// Remove the surrounding quotes from a simple string literal.
+fn unquote(value: &str) -> Option<&str> {
+	value
+		.strip_prefix('\'')
+		.and_then(|value| value.strip_suffix('\''))
+		.or_else(|| {
+			value
+				.strip_prefix('"')
+				.and_then(|value| value.strip_suffix('"'))
+		})
+}
+
+/// Parse an `EdgeQL` string literal. Raw strings (`r'...'`), dollar quoted
+/// strings (`$$...$$`) and the common escape sequences are supported.
+pub(crate) fn parse_string_literal(value: &str) -> Option<String> {
+	let value = value.trim();
+
+	if let Some(raw) = value.strip_prefix('r') {
+		return unquote(raw).map(ToString::to_string);
+	}
+
+	if value.len() >= 4 && value.starts_with("$$") && value.ends_with("$$") {
+		return Some(value[2..value.len() - 2].to_string());
+	}
+
+	let inner = unquote(value)?;
+	let mut result = String::with_capacity(inner.len());
+	let mut chars = inner.chars();
+
+	while let Some(char) = chars.next() {
+		if char != '\\' {
+			result.push(char);
+			continue;
+		}
+
+		match chars.next()? {
+			'n' => result.push('\n'),
+			't' => result.push('\t'),
+			'r' => result.push('\r'),
+			'x' => {
+				let hex = chars.by_ref().take(2).collect::<String>();
+				result.push(char::from(u8::from_str_radix(&hex, 16).ok()?));
+			}
+			'u' => {
+				let hex = chars.by_ref().take(4).collect::<String>();
+				result.push(char::from_u32(u32::from_str_radix(&hex, 16).ok()?)?);
+			}
+			other => result.push(other),
+		}
+	}
+
+	Some(result)
+}
+
+/// Split the elements of an array or set literal, e.g. `['a', 'b']`, while
+/// respecting the quoted values.
+fn split_array_literal(value: &str) -> Vec<String> {
+	let value = value.trim();
+	let inner = value
+		.strip_prefix('[')
+		.and_then(|value| value.strip_suffix(']'))
+		.or_else(|| {
+			value
+				.strip_prefix('{')
+				.and_then(|value| value.strip_suffix('}'))
+		})
+		.unwrap_or(value);
+	let mut items = vec![];
+	let mut current = String::new();
+	let mut quote = None;
+	let mut escaped = false;
+
+	for char in inner.chars() {
+		match (quote, char) {
+			(Some(_), _) if escaped => escaped = false,
+			(Some(_), '\\') => escaped = true,
+			(Some(open), _) if open == char => quote = None,
+			(None, '\'' | '"') => quote = Some(char),
+			(None, ',') => {
+				items.push(current.trim().to_string());
+				current.clear();
+				continue;
+			}
+			_ => {}
+		}
+
+		current.push(char);
+	}
+
+	if !current.trim().is_empty() {
+		items.push(current.trim().to_string());
+	}
+
+	items
+}
@@ -1,50 +1,364 @@
+use std::path::Path;
+use std::path::PathBuf;
+
 use check_keyword::CheckKeyword;
 use gel_tokio::Client;
 use heck::ToPascalCase;
+use heck::ToSnakeCase;
 use indexmap::IndexMap;
 use proc_macro2::TokenStream;
 use quote::format_ident;
 use quote::quote;
+use syn::Ident;
+use tokio::fs;
 use uuid::Uuid;
 
 use super::*;
+use crate::DerivedDescriptors;
 use crate::FeatureName;
+use crate::GelxCoreError;
 use crate::GelxCoreResult;
 use crate::GelxMetadata;
+use crate::GelxRuleItem;
+use crate::QueryContext;
+use crate::QueryManifest;
+use crate::RuleItem;
+use crate::Shapes;
+use crate::describe_query_error;
+use crate::doc_tokens;
+use crate::gelx_error;
+use crate::generate_query_token_stream_with_context;
+use crate::get_descriptor;
 use crate::maybe_uuid_to_import;
 use crate::maybe_uuid_to_token_name;
 
 /// Generate the custom types.
 pub async fn generate_module_outputs(metadata: &GelxMetadata) -> GelxCoreResult<ModuleOutputs> {
-	let config = metadata.gel_config()?;
-	let client = Client::new(&config);
-	let fetched_types = query_types(&client).await?;
-	let fetched_globals = query_globals(&client).await?;
-	let types = map_fetched_types(&fetched_types);
-	let module_tree = ModuleTree::new(&types, &fetched_globals, metadata);
-	let outputs = module_tree.generate_modules();
-
-	Ok(outputs)
+	let schema = Schema::try_new(metadata).await?;
+
+	schema.generate_module_outputs(metadata)
+}
+
+/// The types and globals fetched from the database schema.
+#[derive(Debug, Clone)]
+pub struct Schema {
+	pub types: Types,
+	pub globals: Vec<GlobalsOutput>,
+	/// The read only queries for the computed globals.
+	pub accessors: Vec<GlobalAccessor>,
+	/// The upsert queries for the exclusive constraints of the object types
+	/// configured in the `upserts` of the metadata.
+	pub upserts: Vec<UpsertQuery>,
+	/// The config types with the settings which can be configured for a
+	/// session.
+	pub config_types: Vec<ConfigTypeOutput>,
+}
+
+impl Schema {
+	/// Fetch the schema from the database configured in the metadata.
+	pub async fn try_new(metadata: &GelxMetadata) -> GelxCoreResult<Self> {
+		let config = metadata.gel_config()?;
+		let client = Client::new(&config);
+		let fetched_types = query_types(&client).await?;
+		let globals = query_globals(&client).await?;
+		let config_types = query_config_types(&client).await?;
+		let types = map_fetched_types(&fetched_types);
+		let mut accessors = vec![];
+
+		for global in &globals {
+			let Some(query) = global.accessor_query() else {
+				continue;
+			};
+
+			let module_name = global.accessor_module_name();
+			let descriptor = get_descriptor(&query, metadata).await?;
+			let derived = DerivedDescriptors::try_new(&module_name, &query, metadata).await?;
+			accessors.push(GlobalAccessor {
+				module_name,
+				query,
+				descriptor,
+				derived,
+			});
+		}
+
+		let mut upserts = vec![];
+
+		for object_type in types.values().filter_map(|type_info| {
+			match type_info {
+				Type::Object(object_type) => Some(object_type),
+				_ => None,
+			}
+		}) {
+			let Some(upsert) = Upsert::try_new(metadata, &types, object_type) else {
+				continue;
+			};
+
+			for (module_name, query) in upsert.queries() {
+				// Constraints which can't be used with `unless conflict`, e.g.
+				// partial constraints, are reported when describing the query.
+				let descriptor = get_descriptor(&query, metadata).await.map_err(|error| {
+					gelx_error!("The upsert `{module_name}` is invalid: {error}")
+				})?;
+				let derived = DerivedDescriptors::try_new(&module_name, &query, metadata).await?;
+				upserts.push(UpsertQuery {
+					module_name,
+					query,
+					descriptor,
+					derived,
+					field_enum: upsert.field_enum_path(),
+				});
+			}
+		}
+
+		Ok(Self {
+			types,
+			globals,
+			accessors,
+			upserts,
+			config_types,
+		})
+	}
+
+	/// Generate the custom types for this schema.
+	pub fn generate_module_outputs(
+		&self,
+		metadata: &GelxMetadata,
+	) -> GelxCoreResult<ModuleOutputs> {
+		let module_tree = ModuleTree::new(&self.types, &self.globals, metadata)
+			.with_root_tokens(generate_session_config(
+				metadata,
+				&self.config_types,
+				&self.types,
+			)?)
+			.with_root_tokens(self.generate_global_accessors(metadata)?)
+			.with_root_tokens(self.generate_upserts(metadata)?);
+
+		module_tree.generate_modules()
+	}
+
+	/// Generate the custom types for this schema as a single token stream with
+	/// all the modules inlined.
+	pub fn generate_inline_module(&self, metadata: &GelxMetadata) -> GelxCoreResult<TokenStream> {
+		let module_tree = ModuleTree::new(&self.types, &self.globals, metadata)
+			.with_root_tokens(generate_session_config(
+				metadata,
+				&self.config_types,
+				&self.types,
+			)?)
+			.with_root_tokens(self.generate_global_accessors(metadata)?)
+			.with_root_tokens(self.generate_upserts(metadata)?);
+
+		module_tree.root.to_inline_token_stream()
+	}
+
+	/// Generate the query modules which read the computed globals.
+	fn generate_global_accessors(&self, metadata: &GelxMetadata) -> GelxCoreResult<TokenStream> {
+		let context = QueryContext {
+			types: Some(&self.types),
+			shapes: None,
+			derived: None,
+			update_fields: None,
+		};
+
+		self.accessors
+			.iter()
+			.map(|accessor| {
+				generate_query_token_stream_with_context(
+					&accessor.descriptor,
+					&accessor.module_name,
+					&accessor.query,
+					metadata,
+					false,
+					QueryContext {
+						derived: Some(&accessor.derived),
+						..context
+					},
+				)
+				.map_err(|error| {
+					gelx_error!(
+						"The global accessor `{}` couldn't be generated: {error}",
+						accessor.module_name
+					)
+				})
+			})
+			.collect()
+	}
+
+	/// Generate the query modules for the upsert queries.
+	fn generate_upserts(&self, metadata: &GelxMetadata) -> GelxCoreResult<TokenStream> {
+		let context = QueryContext {
+			types: Some(&self.types),
+			shapes: None,
+			derived: None,
+			update_fields: None,
+		};
+
+		self.upserts
+			.iter()
+			.map(|upsert| {
+				generate_query_token_stream_with_context(
+					&upsert.descriptor,
+					&upsert.module_name,
+					&upsert.query,
+					metadata,
+					false,
+					QueryContext {
+						derived: Some(&upsert.derived),
+						update_fields: Some(&upsert.field_enum),
+						..context
+					},
+				)
+				.map_err(|error| {
+					gelx_error!(
+						"The upsert `{}` couldn't be generated: {error}",
+						upsert.module_name
+					)
+				})
+			})
+			.collect()
+	}
+
+	/// Generate the query modules for every query file in the `queries_path`
+	/// of the metadata. The queries are resolved relative to the `root_path`.
+	pub async fn generate_query_outputs(
+		&self,
+		metadata: &GelxMetadata,
+		root_path: impl AsRef<Path>,
+	) -> GelxCoreResult<TokenStream> {
+		let (query_tokens, _) = self
+			.generate_query_outputs_with_manifest(metadata, root_path)
+			.await?;
+
+		Ok(query_tokens)
+	}
+
+	/// Generate the query modules like [`Schema::generate_query_outputs`] and
+	/// the manifest of the queries, including the global accessors and upserts
+	/// of the schema.
+	pub async fn generate_query_outputs_with_manifest(
+		&self,
+		metadata: &GelxMetadata,
+		root_path: impl AsRef<Path>,
+	) -> GelxCoreResult<(TokenStream, QueryManifest)> {
+		let mut query_tokens = TokenStream::new();
+		let mut manifest = QueryManifest::new();
+
+		for accessor in &self.accessors {
+			manifest.insert_query(
+				&accessor.descriptor,
+				&accessor.module_name,
+				&accessor.query,
+				&accessor.derived,
+				metadata,
+			);
+		}
+
+		for upsert in &self.upserts {
+			manifest.insert_query(
+				&upsert.descriptor,
+				&upsert.module_name,
+				&upsert.query,
+				&upsert.derived,
+				metadata,
+			);
+		}
+
+		let shapes = Shapes::new();
+		let context = QueryContext {
+			types: Some(&self.types),
+			shapes: metadata.dedupe_shapes.then_some(&shapes),
+			derived: None,
+			update_fields: None,
+		};
+
+		for path in query_paths(metadata, root_path)? {
+			let query_content = fs::read_to_string(&path).await?;
+			let file_stem = path.file_stem().unwrap_or_default().to_string_lossy();
+			let module_name = file_stem.to_snake_case();
+
+			let descriptor = get_descriptor(&query_content, metadata)
+				.await
+				.map_err(|error| {
+					match error {
+						GelxCoreError::Gel(_) => {
+							let message =
+								describe_query_error(&error, Some(&query_content), Some(&path), 1);
+							GelxCoreError::Custom(message)
+						}
+						error => error,
+					}
+				})?;
+			let derived =
+				DerivedDescriptors::try_new(&module_name, &query_content, metadata).await?;
+			let token_stream = generate_query_token_stream_with_context(
+				&descriptor,
+				&module_name,
+				&query_content,
+				metadata,
+				false,
+				QueryContext {
+					derived: Some(&derived),
+					..context
+				},
+			)?;
+
+			query_tokens.extend(token_stream);
+			manifest.insert_query(
+				&descriptor,
+				&module_name,
+				&query_content,
+				&derived,
+				metadata,
+			);
+		}
+
+		query_tokens.extend(shapes.to_token_stream(metadata));
+
+		Ok((query_tokens, manifest))
+	}
+}
+
+/// The sorted paths of the `.edgeql` query files in the `queries_path` of the
+/// metadata.
+pub fn query_paths(
+	metadata: &GelxMetadata,
+	root_path: impl AsRef<Path>,
+) -> GelxCoreResult<Vec<PathBuf>> {
+	let queries_path = root_path.as_ref().join(&metadata.queries_path);
+
+	if !queries_path.is_dir() {
+		return Ok(vec![]);
+	}
+
+	// not async to make sorting easier
+	let mut paths = queries_path
+		.read_dir()?
+		.map(|entry| entry.map(|entry| entry.path()))
+		.collect::<Result<Vec<_>, _>>()?;
+	paths.retain(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "edgeql"));
+	paths.sort();
+
+	Ok(paths)
 }
 
 pub(crate) fn generate_enum(
 	metadata: &GelxMetadata,
 	enum_values: &[String],
+	type_name: &str,
 	local_name: &str,
+	doc: Option<&str>,
 	is_macro: bool,
-) -> TokenStream {
+) -> GelxCoreResult<TokenStream> {
 	let exports_ident = metadata.exports_alias_ident();
+	let doc_tokens = doc_tokens(doc);
 	let pascal_local_name = format_ident!("{}", local_name.to_pascal_case().into_safe());
 	let enum_values_tokens = enum_values.iter().map(|value| {
 		let pascal_value = value.to_pascal_case();
 		let mut annotations = TokenStream::new();
 
+		annotations.extend(metadata.serde_variant_annotation(value, &pascal_value, is_macro));
+
 		if value != &pascal_value {
-			annotations.extend(metadata.features.wrap_annotation(
-				FeatureName::Serde,
-				&quote!(serde(rename = #value)),
-				is_macro,
-			));
 			annotations.extend(metadata.features.wrap_annotation(
 				FeatureName::Query,
 				&quote!(gel(rename = #value)),
@@ -65,14 +379,25 @@ pub(crate) fn generate_enum(
 		}
 	});
 
-	let derive_macro_paths = metadata.enum_derive_macro_paths();
+	let rule_item = RuleItem {
+		kind: GelxRuleItem::Enum,
+		type_name: Some(type_name),
+		query_name: None,
+	};
+	let mut derive_macro_paths = metadata.enum_derive_macro_paths();
+	derive_macro_paths.extend(metadata.rule_derive_macro_paths(&rule_item)?);
+	let rule_attributes = metadata.rule_attributes(&rule_item)?;
 	let enum_derive =
 		metadata
 			.features
 			.get_enum_derive_features(&exports_ident, &derive_macro_paths, is_macro);
+	let serde_enum_annotation = metadata.serde_enum_annotation(is_macro);
 	let strum_annotation = metadata.features.annotate(FeatureName::Strum, false);
 	let enum_tokens = quote! {
+		#doc_tokens
 		#enum_derive
+		#serde_enum_annotation
+		#rule_attributes
 		pub enum #pascal_local_name {
 			#(#enum_values_tokens),*
 		}
@@ -85,7 +410,7 @@ pub(crate) fn generate_enum(
 		}
 	};
 
-	enum_tokens
+	Ok(enum_tokens)
 }
 
 pub(crate) fn generate_scalar(
@@ -94,11 +419,11 @@ pub(crate) fn generate_scalar(
 	module_name: &ModuleName,
 	types: &IndexMap<Uuid, Type>,
 	is_macro: bool,
-) -> TokenStream {
+) -> GelxCoreResult<TokenStream> {
 	let exports_ident = metadata.exports_alias_ident();
 	let Some(Type::Scalar(parent_scalar)) = scalar_type.material_id.and_then(|id| types.get(&id))
 	else {
-		return TokenStream::new();
+		return Ok(TokenStream::new());
 	};
 
 	let wrapped_struct_type =
@@ -106,7 +431,14 @@ pub(crate) fn generate_scalar(
 	let parent_uuid = maybe_uuid_to_import(&parent_scalar.id, &exports_ident).unwrap_or_default();
 
 	let struct_name = module_name.name_ident(false);
-	let derive_macro_paths = metadata.scalar_derive_macro_paths();
+	let rule_item = RuleItem {
+		kind: GelxRuleItem::Scalar,
+		type_name: Some(&scalar_type.name),
+		query_name: None,
+	};
+	let mut derive_macro_paths = metadata.scalar_derive_macro_paths();
+	derive_macro_paths.extend(metadata.rule_derive_macro_paths(&rule_item)?);
+	let rule_attributes = metadata.rule_attributes(&rule_item)?;
 	let struct_derive_tokens = metadata.features.get_derive_features(
 		&[FeatureName::Serde, FeatureName::Builder],
 		&exports_ident,
@@ -116,9 +448,23 @@ pub(crate) fn generate_scalar(
 	);
 	let type_name = &scalar_type.name;
 	let queryable_annotation = metadata.features.annotate(FeatureName::Query, false);
+	let doc_tokens = doc_tokens(scalar_type.annotations.doc().as_deref());
+	let checks = scalar_constraint_checks(scalar_type, metadata, is_macro);
+	let validate_tokens = (!checks.is_empty()).then(|| {
+		quote! {
+			impl #exports_ident::validate::Validate for #struct_name {
+				fn validate(&self) -> ::core::result::Result<(), #exports_ident::validate::ValidationError> {
+					#(#checks)*
+					Ok(())
+				}
+			}
+		}
+	});
 
-	quote! {
+	Ok(quote! {
+		#doc_tokens
 		#struct_derive_tokens
+		#rule_attributes
 		pub struct #struct_name(pub #wrapped_struct_type);
 
 		#queryable_annotation
@@ -173,12 +519,87 @@ pub(crate) fn generate_scalar(
 				&mut self.0
 			}
 		}
+
+		#validate_tokens
+	})
+}
+
+/// The path to a custom scalar or enum type relative to the root module where
+/// the `Globals` struct is generated.
+pub(crate) fn global_type_path(type_info: &Type) -> Option<TokenStream> {
+	if !matches!(type_info, Type::Scalar(_) | Type::Enum(_)) {
+		return None;
+	}
+
+	let module_name = type_info.name().to_module_name();
+
+	if !module_name.is_user_defined() {
+		return None;
+	}
+
+	// Types in nested modules, e.g. `default::nested::Tier`, are generated in
+	// the nested child modules of the module tree.
+	let module_idents = module_name
+		.modules
+		.iter()
+		.map(|module| format_ident!("{}", module.to_snake_case().into_safe()));
+	let type_ident = module_name.name_ident(false);
+
+	Some(quote!(#(#module_idents::)*#type_ident))
+}
+
+/// Custom scalars and enums aren't `ScalarArg`s so they're converted into a
+/// `Value` before being passed to the globals or config modifier.
+fn modifier_value(
+	value: TokenStream,
+	is_custom: bool,
+	is_many: bool,
+	exports_ident: &Ident,
+) -> TokenStream {
+	let value_path = quote!(#exports_ident::gel_protocol::value::Value);
+
+	match (is_custom, is_many) {
+		(false, _) => value,
+		(true, false) => quote!(#value_path::from(#value)),
+		(true, true) => {
+			quote!(#value_path::Array(#value.into_iter().map(#value_path::from).collect()))
+		}
 	}
 }
 
-pub(crate) fn generate_globals(metadata: &GelxMetadata, globals: &[GlobalsOutput]) -> TokenStream {
+/// The constraint checks for the wrapped value of a custom scalar type.
+pub(crate) fn scalar_constraint_checks(
+	scalar_type: &ScalarType,
+	metadata: &GelxMetadata,
+	is_macro: bool,
+) -> Vec<TokenStream> {
+	let Some(target) = scalar_type
+		.material_id
+		.as_ref()
+		.and_then(ConstraintTarget::from_material_id)
+	else {
+		return vec![];
+	};
+
+	constraint_checks(
+		&scalar_type.constraints,
+		target,
+		&scalar_type.name,
+		&quote!(&self.0),
+		metadata,
+		is_macro,
+	)
+}
+
+pub(crate) fn generate_globals(
+	metadata: &GelxMetadata,
+	globals: &[GlobalsOutput],
+	types: &IndexMap<Uuid, Type>,
+) -> TokenStream {
 	let mut tokens = TokenStream::new();
 	let exports_ident = metadata.exports_alias_ident();
+	let mut patch_fields = vec![];
+	let mut patch_setters = vec![];
 	let (fields, setters): (Vec<_>, Vec<_>) = globals
 		.iter()
 		.filter_map(|global| {
@@ -186,26 +607,56 @@ pub(crate) fn generate_globals(metadata: &GelxMetadata, globals: &[GlobalsOutput
 			let module_name = name.to_module_name();
 			let field_name = module_name.name_ident(true);
 			let target = global.target.as_ref()?;
-			let type_name = maybe_uuid_to_token_name(&target.id, &exports_ident)?;
 
-			if target.is_from_alias.unwrap_or_default() {
+			if global.is_computed() || target.is_from_alias.unwrap_or_default() {
 				return None;
 			}
 
-			let wrapped_type = if let Some(SchemaCardinality::Many) = global.cardinality {
-				quote!(Vec<#type_name>)
+			let builtin_type_name = maybe_uuid_to_token_name(&target.id, &exports_ident);
+			let is_custom = builtin_type_name.is_none();
+			let type_name =
+				builtin_type_name.or_else(|| global_type_path(types.get(&target.id)?))?;
+			let is_many = matches!(global.cardinality, Some(SchemaCardinality::Many));
+
+			let (wrapped_type, patch_type) = if is_many {
+				(quote!(Vec<#type_name>), quote!(Option<Vec<#type_name>>))
 			} else {
-				quote!(Option<#type_name>)
+				(quote!(Option<#type_name>), quote!(Option<#type_name>))
 			};
 
+			let doc_tokens = doc_tokens(
+				Annotations::from(global.annotations.clone())
+					.doc()
+					.as_deref(),
+			);
 			let field = quote! {
+				#doc_tokens
 				pub #field_name: #wrapped_type,
 			};
 
+			let value = if is_custom && !is_many {
+				quote!(self.#field_name.map_or(
+					#exports_ident::gel_protocol::value::Value::Nothing,
+					#exports_ident::gel_protocol::value::Value::from,
+				))
+			} else {
+				modifier_value(quote!(self.#field_name), is_custom, is_many, &exports_ident)
+			};
 			let setter = quote! {
-				modifier.set(#name, self.#field_name);
+				modifier.set(#name, #value);
 			};
 
+			let patch_value = modifier_value(quote!(value), is_custom, is_many, &exports_ident);
+			patch_fields.push(quote! {
+				#doc_tokens
+				pub #field_name: #patch_type,
+			});
+			patch_setters.push(quote! {
+				if let Some(value) = self.#field_name {
+					modifier.set(#name, #patch_value);
+				}
+			});
+
 			Some((field, setter))
 		})
 		.collect::<Vec<_>>()
@@ -254,9 +705,163 @@ pub(crate) fn generate_globals(metadata: &GelxMetadata, globals: &[GlobalsOutput
 					let client = self.clone().into_client().await?;
 					Ok(client)
 				}
+
+				/// Create a copy of an existing client with the globals. Every
+				/// global is set, even when the value is empty.
+				pub fn apply_to(&self, client: &#exports_ident::gel_tokio::Client) -> #exports_ident::gel_tokio::Client {
+					client.with_globals(self.clone())
+				}
+			}
+			/// A partial set of globals. Only the globals which are provided are
+			/// set, the rest keep the values of the client they are applied to.
+			#[derive(::core::default::Default)]
+			#struct_derive_tokens
+			#typed_builder_annotation
+			pub struct GlobalsPatch {
+				#(#patch_fields)*
+			}
+			#queryable_annotation
+			impl #exports_ident::gel_tokio::GlobalsDelta for GlobalsPatch {
+				fn apply(self, modifier: &mut #exports_ident::gel_tokio::state::GlobalsModifier<'_>) {
+					#(#patch_setters)*
+				}
+			}
+			#queryable_annotation
+			impl GlobalsPatch {
+				/// Create a copy of an existing client with the provided globals.
+				pub fn apply_to(&self, client: &#exports_ident::gel_tokio::Client) -> #exports_ident::gel_tokio::Client {
+					client.with_globals(self.clone())
+				}
 			}
 		});
 	}
 
 	tokens
 }
+
+pub(crate) fn generate_session_config(
+	metadata: &GelxMetadata,
+	config_types: &[ConfigTypeOutput],
+	types: &IndexMap<Uuid, Type>,
+) -> GelxCoreResult<TokenStream> {
+	let exports_ident = metadata.exports_alias_ident();
+	let mut enums = IndexMap::<String, TokenStream>::new();
+	let mut fields = vec![];
+	let mut setters = vec![];
+
+	for config_type in config_types {
+		for property in &config_type.properties {
+			let Some(target) = property.target.as_ref() else {
+				continue;
+			};
+
+			// Sets can only be configured with `configure ... insert` statements.
+			if let Some(SchemaCardinality::Many) = property.cardinality {
+				continue;
+			}
+
+			let builtin_type_name = maybe_uuid_to_token_name(&target.id, &exports_ident);
+			let is_custom = builtin_type_name.is_none();
+			let type_name = if let Some(type_name) = builtin_type_name {
+				type_name
+			} else {
+				let Some(Type::Enum(enum_type)) = types.get(&target.id) else {
+					continue;
+				};
+
+				let local_name = enum_type.name.to_module_name().name;
+				let enum_ident = format_ident!("{}", local_name.to_pascal_case().into_safe());
+
+				if !enums.contains_key(&local_name) {
+					let enum_tokens = generate_enum(
+						metadata,
+						&enum_type.enum_values,
+						&enum_type.name,
+						&local_name,
+						enum_type.annotations.doc().as_deref(),
+						false,
+					)?;
+					enums.insert(local_name, enum_tokens);
+				}
+
+				quote!(session_config::#enum_ident)
+			};
+
+			let key = config_type.config_key(property);
+			let field_name = format_ident!("{}", config_type.field_name(property).into_safe());
+			let doc_tokens = doc_tokens(
+				Annotations::from(property.annotations.clone())
+					.doc()
+					.as_deref(),
+			);
+			let value = modifier_value(quote!(value), is_custom, false, &exports_ident);
+
+			fields.push(quote! {
+				#doc_tokens
+				pub #field_name: Option<#type_name>,
+			});
+			setters.push(quote! {
+				if let Some(value) = self.#field_name {
+					modifier.set(#key, #value);
+				}
+			});
+		}
+	}
+
+	if fields.is_empty() {
+		return Ok(TokenStream::new());
+	}
+
+	let derive_macro_paths = metadata.struct_derive_macro_paths();
+	let struct_derive_tokens = metadata.features.get_struct_derive_features(
+		&exports_ident,
+		&derive_macro_paths,
+		true,
+		false,
+	);
+	let typed_builder_annotation = metadata.features.wrap_annotation(
+		FeatureName::Builder,
+		&quote!(builder(field_defaults(
+			default,
+			setter(into, strip_option(fallback_suffix = "_opt"))
+		))),
+		false,
+	);
+	let queryable_annotation = metadata.features.annotate(FeatureName::Query, false);
+	let enums_module = (!enums.is_empty()).then(|| {
+		let enums = enums.values();
+		quote! {
+			/// The enums used by the settings of the [`SessionConfig`].
+			pub mod session_config {
+				use super::*;
+				#(#enums)*
+			}
+		}
+	});
+
+	Ok(quote! {
+		/// The configuration for a session. Only the settings which are
+		/// provided are configured, the rest keep the values of the client they
+		/// are applied to.
+		#[derive(::core::default::Default)]
+		#struct_derive_tokens
+		#typed_builder_annotation
+		pub struct SessionConfig {
+			#(#fields)*
+		}
+		#queryable_annotation
+		impl #exports_ident::gel_tokio::ConfigDelta for SessionConfig {
+			fn apply(self, modifier: &mut #exports_ident::gel_tokio::state::ConfigModifier<'_>) {
+				#(#setters)*
+			}
+		}
+		#queryable_annotation
+		impl SessionConfig {
+			/// Create a copy of an existing client with the session configuration.
+			pub fn apply_to(&self, client: &#exports_ident::gel_tokio::Client) -> #exports_ident::gel_tokio::Client {
+				client.with_config(self.clone())
+			}
+		}
+		#enums_module
+	})
+}
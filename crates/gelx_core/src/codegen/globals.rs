@@ -1,7 +1,13 @@
+use gel_protocol::server_message::CommandDataDescription1;
 use gel_protocol::value::Value;
 use gel_tokio::Queryable;
+use heck::ToSnakeCase;
 use strum::AsRefStr;
 
+use super::AnnotationsSet;
+use super::ToModuleName;
+use crate::DerivedDescriptors;
+
 /// Execute the desired query.
 pub async fn query_globals(
 	client: &gel_tokio::Client,
@@ -26,14 +32,76 @@ pub struct GlobalsTarget {
 	pub id: uuid::Uuid,
 	pub name: String,
 	pub is_from_alias: Option<bool>,
+	pub is_object: Option<bool>,
 }
 #[derive(Debug, Clone, Queryable)]
 pub struct GlobalsOutput {
 	pub id: uuid::Uuid,
 	pub name: String,
 	pub cardinality: Option<SchemaCardinality>,
+	pub expr: Option<String>,
 	pub target: Option<GlobalsTarget>,
+	pub annotations: Vec<AnnotationsSet>,
+}
+
+impl GlobalsOutput {
+	/// Computed globals are derived from an expression and can't be set.
+	pub fn is_computed(&self) -> bool {
+		self.expr.is_some()
+	}
+
+	/// The query used to read a computed global which is defined in a user
+	/// module. Object types select all their properties.
+	pub fn accessor_query(&self) -> Option<String> {
+		if !self.is_computed() || !self.name.to_module_name().is_user_defined() {
+			return None;
+		}
+
+		let name = &self.name;
+		let is_object = self
+			.target
+			.as_ref()
+			.and_then(|target| target.is_object)
+			.unwrap_or_default();
+
+		if is_object {
+			Some(format!("select (global {name}) {{ * }};"))
+		} else {
+			Some(format!("select global {name};"))
+		}
+	}
+
+	/// The name of the generated accessor module, e.g. `global_current_user`
+	/// for `default::current_user` and `global_additional_name` for
+	/// `additional::name`.
+	pub fn accessor_module_name(&self) -> String {
+		let module_name = self.name.to_module_name();
+		let mut parts = vec!["global".to_string()];
+
+		if module_name.modules != ["default"] {
+			parts.extend(
+				module_name
+					.modules
+					.iter()
+					.map(|module| module.to_snake_case()),
+			);
+		}
+
+		parts.push(module_name.name.to_snake_case());
+		parts.join("_")
+	}
+}
+
+/// A read only query for a computed global.
+#[derive(Debug, Clone)]
+pub struct GlobalAccessor {
+	/// The name of the generated query module.
+	pub module_name: String,
+	pub query: String,
+	pub descriptor: CommandDataDescription1,
+	pub derived: DerivedDescriptors,
 }
 
-pub const GLOBALS_QUERY: &str =
-	"select schema::Global {id, name, cardinality, target: {id, name, is_from_alias}}";
+pub const GLOBALS_QUERY: &str = "select schema::Global {id, name, cardinality, expr, target: {id, \
+                                 name, is_from_alias, is_object := .__type__.name = \
+                                 'schema::ObjectType'}, annotations: {name, value := @value}}";
@@ -1,16 +1,29 @@
+pub use self::config::*;
+pub use self::constraints::*;
 pub use self::generate::*;
 pub use self::globals::*;
 pub use self::modules::*;
 pub use self::types::*;
+pub use self::upserts::*;
 
+mod config;
+mod constraints;
 mod generate;
 mod globals;
 mod modules;
 mod types;
+mod upserts;
 
 #[cfg(test)]
 mod tests {
+	use gel_protocol::codec::CFG_MEMORY;
+	use gel_protocol::codec::STD_BOOL;
+	use gel_protocol::codec::STD_DURATION;
+	use gel_protocol::codec::STD_INT64;
+	use gel_protocol::codec::STD_STR;
 	use quote::format_ident;
+	use quote::quote;
+	use rstest::rstest;
 
 	use super::*;
 	use crate::GelxCoreResult;
@@ -38,4 +51,417 @@ mod tests {
 		assert_eq!(module_name.name_ident(true), format_ident!("amazing"));
 		assert_eq!(module_name.name_ident(false), format_ident!("Amazing"));
 	}
+
+	#[rstest]
+	#[case::object(
+		"default::current_user",
+		Some(true),
+		Some("select (global default::current_user) { * };")
+	)]
+	#[case::scalar(
+		"additional::user_name",
+		Some(false),
+		Some("select global additional::user_name;")
+	)]
+	#[case::settable("default::current_user_id", None, None)]
+	#[case::extension("ext::auth::ClientTokenIdentity", Some(true), None)]
+	fn test_global_accessor_query(
+		#[case] name: &str,
+		#[case] is_object: Option<bool>,
+		#[case] expected: Option<&str>,
+	) {
+		let global = GlobalsOutput {
+			id: uuid::Uuid::nil(),
+			name: name.into(),
+			cardinality: Some(SchemaCardinality::One),
+			expr: is_object.map(|_| "select 1".into()),
+			target: Some(GlobalsTarget {
+				id: uuid::Uuid::nil(),
+				name: "default::User".into(),
+				is_from_alias: Some(true),
+				is_object,
+			}),
+			annotations: vec![],
+		};
+
+		assert_eq!(global.accessor_query().as_deref(), expected);
+	}
+
+	#[rstest]
+	#[case::default("default::current_user", "global_current_user")]
+	#[case::nested("additional::nested::userName", "global_additional_nested_user_name")]
+	fn test_global_accessor_module_name(#[case] name: &str, #[case] expected: &str) {
+		let global = GlobalsOutput {
+			id: uuid::Uuid::nil(),
+			name: name.into(),
+			cardinality: None,
+			expr: None,
+			target: None,
+			annotations: vec![],
+		};
+
+		assert_eq!(global.accessor_module_name(), expected);
+	}
+
+	#[rstest]
+	#[case::default("default::Tier", Some("default :: Tier"))]
+	#[case::nested("default::nested::Tier", Some("default :: nested :: Tier"))]
+	#[case::keyword("additional::type::Tier", Some("additional :: r#type :: Tier"))]
+	#[case::system("std::Tier", None)]
+	fn test_global_type_path(#[case] name: &str, #[case] expected: Option<&str>) {
+		let type_info = Type::Enum(EnumType {
+			id: uuid::Uuid::nil(),
+			name: name.into(),
+			enum_values: vec!["Free".into()],
+			bases: vec![],
+			annotations: Annotations::default(),
+		});
+
+		assert_eq!(
+			global_type_path(&type_info).map(|path| path.to_string()),
+			expected.map(ToString::to_string)
+		);
+	}
+
+	#[rstest]
+	#[case::config(
+		"cfg::Config",
+		"apply_access_policies",
+		"apply_access_policies",
+		"apply_access_policies"
+	)]
+	#[case::extension(
+		"ext::pgvector::Config",
+		"probes",
+		"ext::pgvector::Config::probes",
+		"pgvector_probes"
+	)]
+	fn test_config_property_names(
+		#[case] type_name: &str,
+		#[case] name: &str,
+		#[case] key: &str,
+		#[case] field_name: &str,
+	) {
+		let property = ConfigProperty {
+			name: name.into(),
+			cardinality: Some(SchemaCardinality::One),
+			target: None,
+			annotations: vec![],
+		};
+		let config_type = ConfigTypeOutput {
+			name: type_name.into(),
+			properties: vec![property.clone()],
+		};
+
+		assert_eq!(config_type.config_key(&property), key);
+		assert_eq!(config_type.field_name(&property), field_name);
+	}
+
+	fn config_property(name: &str, target_id: uuid::Uuid) -> ConfigProperty {
+		ConfigProperty {
+			name: name.into(),
+			cardinality: Some(SchemaCardinality::One),
+			target: Some(ConfigTarget {
+				id: target_id,
+				name: String::new(),
+			}),
+			annotations: vec![],
+		}
+	}
+
+	#[test]
+	fn test_generate_session_config() -> GelxCoreResult<()> {
+		let enum_id = uuid::Uuid::from_u128(1);
+		let types = Types::from_iter([(
+			enum_id,
+			Type::Enum(EnumType {
+				id: enum_id,
+				name: "cfg::AllowBareDDL".into(),
+				enum_values: vec!["AlwaysAllow".into(), "NeverAllow".into()],
+				bases: vec![],
+				annotations: Annotations::default(),
+			}),
+		)]);
+		let mut origins = config_property("cors_allow_origins", STD_STR);
+		origins.cardinality = Some(SchemaCardinality::Many);
+		let mut untyped = config_property("untyped", STD_STR);
+		untyped.target = None;
+		let config_types = [
+			ConfigTypeOutput {
+				name: "cfg::Config".into(),
+				properties: vec![
+					config_property("allow_bare_ddl", enum_id),
+					config_property("apply_access_policies", STD_BOOL),
+					config_property("query_execution_timeout", STD_DURATION),
+					config_property("query_work_mem", CFG_MEMORY),
+					origins,
+					untyped,
+				],
+			},
+			ConfigTypeOutput {
+				name: "ext::pgvector::Config".into(),
+				properties: vec![config_property("probes", STD_INT64)],
+			},
+		];
+		let metadata = GelxMetadata::default();
+		let tokens = generate_session_config(&metadata, &config_types, &types)?.to_string();
+		let expected_fields = [
+			quote!(pub allow_bare_ddl: Option<session_config::AllowBareDdl>,),
+			quote!(pub apply_access_policies: Option<bool>,),
+			quote!(pub query_execution_timeout: Option<__g::gel_protocol::model::Duration>,),
+			quote!(pub query_work_mem: Option<__g::gel_protocol::model::ConfigMemory>,),
+			quote!(pub pgvector_probes: Option<i64>,),
+		];
+
+		assert!(
+			tokens.contains(&quote!(pub struct SessionConfig { #(#expected_fields)* }).to_string())
+		);
+		assert!(tokens.contains(
+			&quote!(modifier.set("allow_bare_ddl", __g::gel_protocol::value::Value::from(value));)
+				.to_string()
+		));
+		assert!(
+			tokens.contains(
+				&quote!(modifier.set("ext::pgvector::Config::probes", value);).to_string()
+			)
+		);
+		assert!(tokens.contains(&quote!(pub fn apply_to).to_string()));
+		assert!(tokens.contains(&quote!(pub mod session_config).to_string()));
+		assert!(tokens.contains(&quote!(pub enum AllowBareDdl).to_string()));
+		assert!(
+			generate_session_config(&metadata, &[], &types)?.is_empty(),
+			"nothing is generated without any settings"
+		);
+
+		Ok(())
+	}
+
+	#[rstest]
+	#[case::single_quotes("'^[a-z]+$'", Some("^[a-z]+$"))]
+	#[case::double_quotes(r#""it's""#, Some("it's"))]
+	#[case::raw(r"r'^\d+$'", Some(r"^\d+$"))]
+	#[case::escapes(r"'a\\b\'c\n'", Some("a\\b'c\n"))]
+	#[case::dollar("$$a'b$$", Some("a'b"))]
+	#[case::unquoted("abc", None)]
+	fn test_parse_string_literal(#[case] value: &str, #[case] expected: Option<&str>) {
+		assert_eq!(parse_string_literal(value).as_deref(), expected);
+	}
+
+	#[rstest]
+	#[case::max_value("std::max_value", "1_000_000", Some(Constraint::MaxValue("1_000_000".into())))]
+	#[case::max_len_value("std::max_len_value", "40", Some(Constraint::MaxLenValue(40)))]
+	#[case::regexp("std::regexp", "r'^[a-z]+$'", Some(Constraint::Regexp("^[a-z]+$".into())))]
+	#[case::one_of("std::one_of", "['a', 'b,c']", Some(Constraint::OneOf(vec!["'a'".into(), "'b,c'".into()])))]
+	#[case::expression("std::expression", "__subject__ > 0", None)]
+	fn test_constraint_from_set(
+		#[case] name: &str,
+		#[case] value: &str,
+		#[case] expected: Option<Constraint>,
+	) {
+		let set = ConstraintsSet {
+			name: name.into(),
+			params: vec![ConstraintParamsSet {
+				name: "max".into(),
+				value: Some(value.into()),
+			}],
+		};
+
+		assert_eq!(Constraint::from_set(&set), expected);
+	}
+
+	#[test]
+	fn test_constraint_checks() {
+		let metadata = GelxMetadata::default();
+		let constraints = [
+			Constraint::MinValue("0".into()),
+			Constraint::MaxLenValue(40),
+			Constraint::MaxValue("1_000_000".into()),
+		];
+		let checks = constraint_checks(
+			&constraints,
+			ConstraintTarget::Number { float: false },
+			"default::Position",
+			&quote!(&self.0),
+			&metadata,
+			false,
+		);
+		let expected = quote! {
+			__g::validate::min_value("default::Position", &self.0, &0)?;
+			__g::validate::max_value("default::Position", &self.0, &1_000_000)?;
+		};
+
+		assert_eq!(quote!(#(#checks)*).to_string(), expected.to_string());
+	}
+
+	#[rstest]
+	#[case::disabled("", quote!())]
+	#[case::enabled(
+		"features = { regex = true }",
+		quote!(__g::validate::regexp("default::Actor.slug", &self.0, "^[a-z]+$")?;)
+	)]
+	#[case::alias(
+		"features = { regex = \"ssr\" }",
+		quote!(#[cfg(feature = "ssr")] __g::validate::regexp("default::Actor.slug", &self.0, "^[a-z]+$")?;)
+	)]
+	fn test_regexp_checks_require_the_regex_feature(
+		#[case] config: &str,
+		#[case] expected: proc_macro2::TokenStream,
+	) {
+		let metadata: GelxMetadata = toml::from_str(config).unwrap();
+		let checks = constraint_checks(
+			&[Constraint::Regexp("^[a-z]+$".into())],
+			ConstraintTarget::Str,
+			"default::Actor.slug",
+			&quote!(&self.0),
+			&metadata,
+			false,
+		);
+
+		assert_eq!(quote!(#(#checks)*).to_string(), expected.to_string());
+	}
+
+	fn pointer(name: &str) -> Pointer {
+		Pointer {
+			card: gel_protocol::common::Cardinality::One,
+			kind: PointerKind::Property,
+			name: name.into(),
+			target_id: uuid::Uuid::nil(),
+			flags: PointerFlags::IS_EXCLUSIVE,
+			pointers: None,
+			annotations: Annotations::default(),
+			constraints: vec![],
+		}
+	}
+
+	#[test]
+	fn test_generate_constraint_error() {
+		let mut object_type = ObjectType {
+			id: uuid::Uuid::nil(),
+			name: "default::Project".into(),
+			is_abstract: false,
+			bases: vec![],
+			union_of: vec![],
+			intersection_of: vec![],
+			pointers: vec![],
+			backlinks: vec![],
+			exclusives: vec![],
+			annotations: Annotations::default(),
+		};
+		let exports_ident = format_ident!("__g");
+
+		assert!(generate_constraint_error(&object_type, "Project", &exports_ident).is_none());
+
+		object_type.exclusives = vec![
+			Exclusives::One(pointer("slug")),
+			Exclusives::Many(vec![pointer("creator"), pointer("slug")]),
+		];
+		let tokens = generate_constraint_error(&object_type, "Project", &exports_ident)
+			.unwrap()
+			.to_string();
+
+		assert!(tokens.contains("pub enum ProjectConstraintError"));
+		assert!(
+			tokens.contains(
+				&quote!(violation.find("default::Project", &[&["creator", "slug"], &["slug"]]))
+					.to_string()
+			)
+		);
+		assert!(
+			tokens.contains(&quote!([Self::CreatorSlugTaken, Self::SlugTaken]).to_string()),
+			"the variants are sorted by name"
+		);
+	}
+
+	#[test]
+	fn test_upsert_queries() {
+		let str_id = uuid::Uuid::from_u128(1);
+		let actor_id = uuid::Uuid::from_u128(2);
+		let mut bio = pointer("bio");
+		bio.card = gel_protocol::common::Cardinality::AtMostOne;
+		let mut creator = pointer("creator");
+		creator.kind = PointerKind::Link;
+		creator.target_id = actor_id;
+		let mut created_at = pointer("created_at");
+		created_at.flags = PointerFlags::HAS_DEFAULT | PointerFlags::IS_READONLY;
+		let mut name = pointer("name");
+		name.flags = PointerFlags::IS_READONLY;
+		let pointers = [name, bio, pointer("slug"), creator, created_at]
+			.into_iter()
+			.map(|mut pointer| {
+				if pointer.target_id.is_nil() {
+					pointer.target_id = str_id;
+				}
+
+				pointer
+			})
+			.collect::<Vec<_>>();
+		let object_type = ObjectType {
+			id: uuid::Uuid::nil(),
+			name: "default::Project".into(),
+			is_abstract: false,
+			bases: vec![],
+			union_of: vec![],
+			intersection_of: vec![],
+			exclusives: vec![Exclusives::Many(vec![
+				pointers[3].clone(),
+				pointers[2].clone(),
+			])],
+			pointers,
+			backlinks: vec![],
+			annotations: Annotations::default(),
+		};
+		let types = Types::from_iter([
+			(
+				str_id,
+				Type::Base(BaseType {
+					id: str_id,
+					name: "std::str".into(),
+					is_abstract: false,
+				}),
+			),
+			(
+				actor_id,
+				Type::Base(BaseType {
+					id: actor_id,
+					name: "default::Actor".into(),
+					is_abstract: true,
+				}),
+			),
+		]);
+		let metadata = GelxMetadata::builder()
+			.upserts(vec!["default::*".into()])
+			.build();
+		let upsert = Upsert::try_new(&metadata, &types, &object_type).unwrap();
+		let queries = upsert.queries();
+		let expected = "with update_fields := <array<str>>$update_fields
+select (
+  insert default::Project {
+    name := <std::str>$name,
+    bio := <optional std::str>$bio,
+    slug := <std::str>$slug,
+    creator := <default::Actor>(<uuid>$creator),
+  }
+  unless conflict on (.creator, .slug)
+  else (
+    update default::Project set {
+      bio := <optional std::str>$bio if 'bio' in array_unpack(update_fields) else .bio,
+      slug := <std::str>$slug if 'slug' in array_unpack(update_fields) else .slug,
+      creator := <default::Actor>(<uuid>$creator) if 'creator' in array_unpack(update_fields) else \
+		                .creator,
+    }
+  )
+) { * };";
+
+		assert_eq!(queries.len(), 1);
+		assert_eq!(queries[0].0, "upsert_project_by_creator_slug");
+		assert_eq!(queries[0].1, expected);
+		assert_eq!(
+			upsert.field_enum_path().to_string(),
+			quote!(default::ProjectField).to_string()
+		);
+		assert!(
+			Upsert::try_new(&GelxMetadata::default(), &types, &object_type).is_none(),
+			"upserts are opt in"
+		);
+	}
 }
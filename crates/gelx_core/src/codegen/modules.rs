@@ -246,6 +246,7 @@ impl<'a> ModuleTree<'a> {
 			types_ref,
 			globals_ref,
 			metadata,
+			root_tokens: TokenStream::new(),
 		};
 
 		for (_, type_info) in types_ref {
@@ -268,15 +269,23 @@ impl<'a> ModuleTree<'a> {
 		}
 	}
 
+	/// Add items to the root module after the globals, e.g. the session config
+	/// and the query modules for the computed globals.
+	#[must_use]
+	pub fn with_root_tokens(mut self, tokens: TokenStream) -> Self {
+		self.root.root_tokens.extend(tokens);
+		self
+	}
+
 	/// Generate the module outputs.
 	///
 	/// The module outputs can be used to generate the file modules.
-	pub fn generate_modules(&self) -> ModuleOutputs {
+	pub fn generate_modules(&self) -> GelxCoreResult<ModuleOutputs> {
 		let mut outputs = Vec::new();
 		self.root
-			.generate_module_output(PathBuf::new(), &mut outputs);
+			.generate_module_output(PathBuf::new(), &mut outputs)?;
 
-		ModuleOutputs::new(outputs)
+		Ok(ModuleOutputs::new(outputs))
 	}
 }
 
@@ -288,6 +297,8 @@ pub struct ModuleNode<'a> {
 	types_ref: &'a IndexMap<Uuid, Type>,
 	globals_ref: &'a Vec<GlobalsOutput>,
 	metadata: &'a GelxMetadata,
+	/// The items added after the globals, only set for the root.
+	root_tokens: TokenStream,
 }
 
 impl<'a> ModuleNode<'a> {
@@ -328,6 +339,7 @@ impl<'a> ModuleNode<'a> {
 				types_ref: self.types_ref,
 				globals_ref: self.globals_ref,
 				metadata: self.metadata,
+				root_tokens: TokenStream::new(),
 			};
 
 			node.insert(remaining, type_info);
@@ -335,23 +347,29 @@ impl<'a> ModuleNode<'a> {
 		}
 	}
 
-	pub fn generate_module_output(&self, path: PathBuf, outputs: &mut Vec<ModuleOutput>) {
+	pub fn generate_module_output(
+		&self,
+		path: PathBuf,
+		outputs: &mut Vec<ModuleOutput>,
+	) -> GelxCoreResult<()> {
 		if !self.is_user_defined() {
-			return;
+			return Ok(());
 		}
 
 		let current_path = path.join(self.filename());
 
 		outputs.push(ModuleOutput {
 			path: current_path.clone(),
-			tokens: self.to_token_stream(),
+			tokens: self.to_token_stream()?,
 		});
 
 		let child_path = current_path.parent().map_or(path, Path::to_path_buf);
 
 		for child in self.children.values() {
-			child.generate_module_output(child_path.clone(), outputs);
+			child.generate_module_output(child_path.clone(), outputs)?;
 		}
+
+		Ok(())
 	}
 
 	pub fn filename(&self) -> String {
@@ -369,19 +387,25 @@ impl<'a> ModuleNode<'a> {
 	}
 
 	/// Generate the import tokens for the module.
-	fn imports_token_stream(&self) -> TokenStream {
+	///
+	/// When `inline` is true the child modules are generated inline rather than
+	/// being declared as file modules.
+	fn imports_token_stream(&self, inline: bool) -> GelxCoreResult<TokenStream> {
 		let mut tokens = TokenStream::new();
 		let exports_ident = self.metadata.exports_alias_ident();
 
-		tokens.extend(quote!(
-			//! This file is generated by `gelx generate`.
-			//! It is not intended for manual editing.
-			//! To update it, run `gelx generate`.
-			#![cfg_attr(rustfmt, rustfmt_skip)]
-			#![allow(unused)]
-			#![allow(unused_qualifications)]
-			#![allow(clippy::all)]
-		));
+		// Inner attributes aren't supported within an inline module.
+		if !inline {
+			tokens.extend(quote!(
+				//! This file is generated by `gelx generate`.
+				//! It is not intended for manual editing.
+				//! To update it, run `gelx generate`.
+				#![cfg_attr(rustfmt, rustfmt_skip)]
+				#![allow(unused)]
+				#![allow(unused_qualifications)]
+				#![allow(clippy::all)]
+			));
+		}
 
 		if self.is_root() {
 			let default_import = self.children.get("default").map(|default_child| {
@@ -404,16 +428,28 @@ impl<'a> ModuleNode<'a> {
 				continue;
 			}
 
-			let filename = node.filename();
 			let safe_name = format_ident!("{}", node.safe_name());
 
-			tokens.extend(quote!(
-				#[path = #filename]
-				pub mod #safe_name;
-			));
+			if inline {
+				let node_tokens = node.module_tokens(true)?;
+
+				tokens.extend(quote!(
+					#[allow(unused, unused_qualifications, clippy::all)]
+					pub mod #safe_name {
+						#node_tokens
+					}
+				));
+			} else {
+				let filename = node.filename();
+
+				tokens.extend(quote!(
+					#[path = #filename]
+					pub mod #safe_name;
+				));
+			}
 		}
 
-		tokens
+		Ok(tokens)
 	}
 
 	pub fn is_user_defined(&self) -> bool {
@@ -434,19 +470,30 @@ impl<'a> ModuleNode<'a> {
 			.collect()
 	}
 
-	pub fn to_token_stream(&self) -> TokenStream {
+	pub fn to_token_stream(&self) -> GelxCoreResult<TokenStream> {
+		self.module_tokens(false)
+	}
+
+	/// Generate the tokens for the module with all the child modules inlined.
+	/// The output can be included with the `include!` macro.
+	pub fn to_inline_token_stream(&self) -> GelxCoreResult<TokenStream> {
+		self.module_tokens(true)
+	}
+
+	fn module_tokens(&self, inline: bool) -> GelxCoreResult<TokenStream> {
 		let mut tokens = TokenStream::new();
 		let user_defined_types = self.user_defined_types();
 
-		tokens.extend(self.imports_token_stream());
+		tokens.extend(self.imports_token_stream(inline)?);
 
 		if self.is_root() {
-			let globals_tokens = generate_globals(self.metadata, self.globals_ref);
+			let globals_tokens = generate_globals(self.metadata, self.globals_ref, self.types_ref);
 			tokens.extend(globals_tokens);
+			tokens.extend(self.root_tokens.clone());
 		}
 
 		if user_defined_types.is_empty() {
-			return tokens;
+			return Ok(tokens);
 		}
 
 		for (_id, type_info) in user_defined_types {
@@ -459,7 +506,7 @@ impl<'a> ModuleNode<'a> {
 						&module_name,
 						self.types_ref,
 						false,
-					);
+					)?;
 					tokens.extend(scalar_tokens);
 				}
 
@@ -477,15 +524,26 @@ impl<'a> ModuleNode<'a> {
 						}
 					};
 					tokens.extend(struct_tokens);
+					tokens.extend(generate_constraint_error(
+						object_type,
+						&module_name.name,
+						&self.metadata.exports_alias_ident(),
+					));
+					tokens.extend(
+						Upsert::try_new(self.metadata, self.types_ref, object_type)
+							.map(|upsert| upsert.field_enum_tokens()),
+					);
 				}
 
 				Type::Enum(enum_type) => {
 					let enum_tokens = generate_enum(
 						self.metadata,
 						&enum_type.enum_values,
+						&enum_type.name,
 						&module_name.name,
+						enum_type.annotations.doc().as_deref(),
 						false,
-					);
+					)?;
 					tokens.extend(enum_tokens);
 				}
 
@@ -493,6 +551,6 @@ impl<'a> ModuleNode<'a> {
 			}
 		}
 
-		tokens
+		Ok(tokens)
 	}
 }
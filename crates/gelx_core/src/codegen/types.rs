@@ -7,6 +7,8 @@ use gel_tokio::Queryable;
 use indexmap::IndexMap;
 use uuid::Uuid;
 
+use super::Constraint;
+use super::ConstraintsSet;
 use crate::GelxCoreResult;
 
 /// Execute the types query to get the types of the current database.
@@ -31,6 +33,12 @@ pub struct IntersectionOfSet {
 	pub id: Uuid,
 }
 
+#[derive(Clone, Debug, Queryable)]
+pub struct AnnotationsSet {
+	pub name: String,
+	pub value: Option<String>,
+}
+
 #[derive(Clone, Debug, Queryable)]
 pub struct PointersSetPointersSet {
 	pub card: Option<String>,
@@ -58,6 +66,8 @@ pub struct PointersSet {
 	pub is_readonly: Option<bool>,
 	pub has_default: bool,
 	pub pointers: Vec<PointersSetPointersSet>,
+	pub annotations: Vec<AnnotationsSet>,
+	pub constraints: Vec<ConstraintsSet>,
 }
 
 impl PointersSet {
@@ -127,6 +137,8 @@ pub struct TypesOutput {
 	pub array_element_id: Option<Uuid>,
 	pub tuple_elements: Vec<TupleElementsSet>,
 	pub multirange_element_id: Option<Uuid>,
+	pub annotations: Vec<AnnotationsSet>,
+	pub constraints: Vec<ConstraintsSet>,
 }
 
 impl TypesOutput {
@@ -307,6 +319,8 @@ pub struct Pointer {
 	pub target_id: Uuid,
 	pub flags: PointerFlags,
 	pub pointers: Option<Vec<Pointer>>,
+	pub annotations: Annotations,
+	pub constraints: Vec<Constraint>,
 }
 
 impl From<PointersSet> for Pointer {
@@ -340,6 +354,12 @@ impl From<PointersSet> for Pointer {
 			target_id: value.target_id.unwrap(),
 			flags,
 			pointers: Some(value.pointers.iter().map(|p| p.clone().into()).collect()),
+			annotations: value.annotations.into(),
+			constraints: value
+				.constraints
+				.iter()
+				.filter_map(Constraint::from_set)
+				.collect(),
 		}
 	}
 }
@@ -367,6 +387,8 @@ impl From<PointersSetPointersSet> for Pointer {
 			target_id: value.target_id.unwrap(),
 			flags,
 			pointers: None,
+			annotations: Annotations::default(),
+			constraints: vec![],
 		}
 	}
 }
@@ -449,6 +471,37 @@ impl From<IntersectionOfSet> for IdRef {
 	}
 }
 
+/// The annotations attached to a schema item, keyed by the fully qualified
+/// annotation name, e.g. `std::description`.
+#[derive(Debug, Clone, Default, derive_more::Deref)]
+pub struct Annotations(IndexMap<String, String>);
+
+impl From<Vec<AnnotationsSet>> for Annotations {
+	fn from(value: Vec<AnnotationsSet>) -> Self {
+		Self(
+			value
+				.into_iter()
+				.filter_map(|annotation| Some((annotation.name, annotation.value?)))
+				.collect(),
+		)
+	}
+}
+
+impl Annotations {
+	/// The documentation for the annotated item. This combines the
+	/// `std::title` and `std::description` annotations when they are present.
+	pub fn doc(&self) -> Option<String> {
+		let parts = ["std::title", "std::description"]
+			.iter()
+			.filter_map(|name| self.get(*name))
+			.map(|value| value.trim())
+			.filter(|value| !value.is_empty())
+			.collect::<Vec<_>>();
+
+		(!parts.is_empty()).then(|| parts.join("\n\n"))
+	}
+}
+
 // Structs for each type kind. Note: 'kind' field is handled by the Type enum
 // tag.
 #[derive(Debug, Clone)]
@@ -460,6 +513,8 @@ pub struct ScalarType {
 	pub bases: Vec<IdRef>,
 	pub material_id: Option<Uuid>,
 	pub cast_type: Option<Uuid>,
+	pub annotations: Annotations,
+	pub constraints: Vec<Constraint>,
 }
 
 #[derive(Debug, Clone)]
@@ -468,6 +523,7 @@ pub struct EnumType {
 	pub name: String,
 	pub enum_values: Vec<String>,
 	pub bases: Vec<IdRef>,
+	pub annotations: Annotations,
 }
 
 #[derive(Clone, Debug)]
@@ -487,6 +543,7 @@ pub struct ObjectType {
 	pub pointers: Vec<Pointer>,
 	pub backlinks: Vec<Backlink>,
 	pub exclusives: Vec<Exclusives>,
+	pub annotations: Annotations,
 }
 
 #[derive(Debug, Clone)]
@@ -574,6 +631,17 @@ impl Type {
 		}
 	}
 
+	/// The schema annotations for the type. Only object, scalar and enum types
+	/// carry annotations.
+	pub fn annotations(&self) -> Option<&Annotations> {
+		match self {
+			Type::Object(obj) => Some(&obj.annotations),
+			Type::Scalar(scalar) => Some(&scalar.annotations),
+			Type::Enum(enum_type) => Some(&enum_type.annotations),
+			_ => None,
+		}
+	}
+
 	pub fn is_primitive(&self) -> bool {
 		matches!(
 			self,
@@ -588,6 +656,59 @@ impl Type {
 
 pub type Types = IndexMap<Uuid, Type>;
 
+/// Find the object type with the provided fully qualified name.
+pub(crate) fn find_object_type<'a>(types: &'a Types, name: &str) -> Option<&'a ObjectType> {
+	types.values().find_map(|type_info| {
+		match type_info {
+			Type::Object(object_type) if object_type.name == name => Some(object_type),
+			_ => None,
+		}
+	})
+}
+
+/// Find the named pointer of an object type. Inherited pointers are resolved
+/// by searching the bases of the object type.
+pub(crate) fn find_pointer<'a>(
+	types: &'a Types,
+	object_type: &'a ObjectType,
+	name: &str,
+) -> Option<&'a Pointer> {
+	if let Some(pointer) = object_type.pointers.iter().find(|p| p.name == name) {
+		return Some(pointer);
+	}
+
+	object_type.bases.iter().find_map(|base| {
+		match types.get(&base.id) {
+			Some(Type::Object(base_type)) => find_pointer(types, base_type, name),
+			_ => None,
+		}
+	})
+}
+
+/// Get the documentation for the named pointer of an object type.
+pub(crate) fn pointer_doc(types: &Types, object_type: &ObjectType, name: &str) -> Option<String> {
+	find_pointer(types, object_type, name)?.annotations.doc()
+}
+
+/// Find the object type which owns the named pointer, searching the bases of
+/// the provided object type.
+pub(crate) fn pointer_owner<'a>(
+	types: &'a Types,
+	object_type: &'a ObjectType,
+	name: &str,
+) -> Option<&'a ObjectType> {
+	if object_type.pointers.iter().any(|p| p.name == name) {
+		return Some(object_type);
+	}
+
+	object_type.bases.iter().find_map(|base| {
+		match types.get(&base.id) {
+			Some(Type::Object(base_type)) => pointer_owner(types, base_type, name),
+			_ => None,
+		}
+	})
+}
+
 pub(crate) fn map_fetched_types(fetched_types: &[TypesOutput]) -> Types {
 	let mut types = IndexMap::new();
 
@@ -604,6 +725,7 @@ pub(crate) fn map_fetched_types(fetched_types: &[TypesOutput]) -> Types {
 							.iter()
 							.map(|base| base.clone().into())
 							.collect(),
+						annotations: type_info.annotations.clone().into(),
 					};
 					types.insert(type_info.id, Type::Enum(enum_type));
 				} else {
@@ -620,6 +742,12 @@ pub(crate) fn map_fetched_types(fetched_types: &[TypesOutput]) -> Types {
 						material_id: type_info.material_id,
 						// TODO: doesn't seem useful in rust
 						cast_type: None,
+						annotations: type_info.annotations.clone().into(),
+						constraints: type_info
+							.constraints
+							.iter()
+							.filter_map(Constraint::from_set)
+							.collect(),
 					};
 					types.insert(type_info.id, Type::Scalar(scalar_type));
 				}
@@ -671,6 +799,7 @@ pub(crate) fn map_fetched_types(fetched_types: &[TypesOutput]) -> Types {
 						.collect(),
 					backlinks: type_info.backlinks(),
 					exclusives,
+					annotations: type_info.annotations.clone().into(),
 				};
 				types.insert(type_info.id, Type::Object(object_type));
 			}
@@ -738,6 +867,17 @@ pub const TYPES_QUERY: &str = r#"WITH
 	      is_computed := len(.computed_fields) != 0,
 	      is_readonly := .readonly
 	    } filter .name != '@source' and .name != '@target',
+	    annotations: {
+	      name,
+	      value := @value,
+	    },
+	    constraints: {
+	      name,
+	      params: {
+	        name,
+	        value := @value,
+	      } filter .name != '__subject__',
+	    } filter .name != 'std::exclusive',
 	  } FILTER @is_owned,
 	  exclusives := assert_distinct((
 	    [is schema::ObjectType].constraints
@@ -784,6 +924,17 @@ pub const TYPES_QUERY: &str = r#"WITH
 	    name
 	  } ORDER BY @index ASC),
 		 multirange_element_id := [IS MultiRange].element_type.id,
+	  annotations: {
+	    name,
+	    value := @value,
+	  },
+	  constraints := [IS ScalarType].constraints {
+	    name,
+	    params: {
+	      name,
+	      value := @value,
+	    } filter .name != '__subject__',
+	  },
 	}
 FILTER NOT .from_alias
 ORDER BY .name;
@@ -0,0 +1,295 @@
+use check_keyword::CheckKeyword;
+use gel_protocol::common::Cardinality;
+use gel_protocol::server_message::CommandDataDescription1;
+use heck::ToPascalCase;
+use heck::ToSnakeCase;
+use proc_macro2::TokenStream;
+use quote::format_ident;
+use quote::quote;
+use syn::Ident;
+
+use super::Exclusives;
+use super::ObjectType;
+use super::Pointer;
+use super::ToModuleName;
+use super::Type;
+use super::Types;
+use crate::DerivedDescriptors;
+use crate::GelxMetadata;
+
+/// The name of the query parameter with the fields which are updated when the
+/// inserted object conflicts with an existing object.
+pub const UPDATE_FIELDS_PARAM: &str = "update_fields";
+
+/// An upsert query for one of the exclusive constraints of an object type.
+#[derive(Debug, Clone)]
+pub struct UpsertQuery {
+	/// The name of the generated query module, e.g.
+	/// `upsert_project_by_creator_slug`.
+	pub module_name: String,
+	pub query: String,
+	pub descriptor: CommandDataDescription1,
+	pub derived: DerivedDescriptors,
+	/// The path of the `<Type>Field` enum relative to the root module, e.g.
+	/// `default::ProjectField`.
+	pub field_enum: TokenStream,
+}
+
+/// The pointers of an object type which are set by the generated upsert
+/// queries.
+#[derive(Debug, Clone)]
+pub(crate) struct Upsert<'a> {
+	pub object_type: &'a ObjectType,
+	/// The pointers which are set by the insert.
+	pub pointers: Vec<&'a Pointer>,
+	/// The types of the pointers, e.g. `std::str` or `default::Actor`.
+	pub targets: Vec<&'a str>,
+}
+
+impl<'a> Upsert<'a> {
+	/// Collect the pointers of the object type which can be set by an upsert.
+	/// Returns `None` when the object type isn't configured for upserts or
+	/// has a required pointer which isn't supported.
+	///
+	/// Computed pointers, multi pointers and pointers with a default value are
+	/// left to the database.
+	pub fn try_new(
+		metadata: &GelxMetadata,
+		types: &'a Types,
+		object_type: &'a ObjectType,
+	) -> Option<Self> {
+		if object_type.is_abstract
+			|| object_type.exclusives.is_empty()
+			|| !metadata.is_upsert_type(&object_type.name)
+		{
+			return None;
+		}
+
+		let mut pointers = vec![];
+		let mut targets = vec![];
+
+		for pointer in all_pointers(types, object_type) {
+			if pointer.name == "id"
+				|| pointer.name == "__type__"
+				|| pointer.is_computed()
+				|| pointer.has_default()
+			{
+				continue;
+			}
+
+			let is_multi = matches!(pointer.card, Cardinality::Many | Cardinality::AtLeastOne);
+
+			if is_multi {
+				if pointer.card == Cardinality::AtLeastOne {
+					return None;
+				}
+
+				continue;
+			}
+
+			targets.push(types.get(&pointer.target_id)?.name());
+			pointers.push(pointer);
+		}
+
+		Some(Self {
+			object_type,
+			pointers,
+			targets,
+		})
+	}
+
+	/// The pointers which can be updated when the inserted object conflicts
+	/// with an existing object.
+	pub fn update_pointers(&self) -> impl Iterator<Item = (&'a Pointer, &'a str)> + '_ {
+		self.pointers
+			.iter()
+			.zip(&self.targets)
+			.filter(|(pointer, _)| !pointer.is_readonly())
+			.map(|(pointer, target)| (*pointer, *target))
+	}
+
+	/// The upsert queries with their module names, one for every exclusive
+	/// constraint.
+	pub fn queries(&self) -> Vec<(String, String)> {
+		self.object_type
+			.exclusives
+			.iter()
+			.map(|exclusive| {
+				let names = exclusive_names(exclusive);
+				let module_name = format!(
+					"upsert_{}_by_{}",
+					self.local_name().to_snake_case(),
+					names.join("_").to_snake_case()
+				);
+
+				(module_name, self.query(&names))
+			})
+			.collect()
+	}
+
+	fn local_name(&self) -> &str {
+		self.object_type
+			.name
+			.rsplit("::")
+			.next()
+			.unwrap_or(&self.object_type.name)
+	}
+
+	/// The `insert ... unless conflict on ... else (update ...)` query for the
+	/// exclusive constraint on the pointers with the provided `names`.
+	fn query(&self, names: &[&str]) -> String {
+		let type_name = &self.object_type.name;
+		let conflict = match names {
+			[name] => format!(".{name}"),
+			names => {
+				let paths = names
+					.iter()
+					.map(|name| format!(".{name}"))
+					.collect::<Vec<_>>();
+				format!("({})", paths.join(", "))
+			}
+		};
+		let insert = self
+			.pointers
+			.iter()
+			.zip(&self.targets)
+			.map(|(pointer, target)| format!("    {} := {},", pointer.name, param(pointer, target)))
+			.collect::<Vec<_>>();
+		let update = self
+			.update_pointers()
+			.map(|(pointer, target)| {
+				let name = &pointer.name;
+				format!(
+					"      {name} := {} if '{name}' in array_unpack({UPDATE_FIELDS_PARAM}) else \
+					 .{name},",
+					param(pointer, target)
+				)
+			})
+			.collect::<Vec<_>>();
+		let otherwise = if update.is_empty() {
+			format!("select {type_name}")
+		} else {
+			format!("update {type_name} set {{\n{}\n    }}", update.join("\n"))
+		};
+
+		format!(
+			"with {UPDATE_FIELDS_PARAM} := <array<str>>${UPDATE_FIELDS_PARAM}\nselect (\n  insert \
+			 {type_name} {{\n{}\n  }}\n  unless conflict on {conflict}\n  else (\n    \
+			 {otherwise}\n  )\n) {{ * }};",
+			insert.join("\n")
+		)
+	}
+
+	/// The path of the `<Type>Field` enum relative to the root module, e.g.
+	/// `default::ProjectField`.
+	pub fn field_enum_path(&self) -> TokenStream {
+		let module_name = self.object_type.name.to_module_name();
+		let module_idents = module_name
+			.modules
+			.iter()
+			.map(|module| format_ident!("{}", module.to_snake_case().into_safe()));
+		let enum_ident = self.field_enum_ident();
+
+		quote!(#(#module_idents::)*#enum_ident)
+	}
+
+	fn field_enum_ident(&self) -> Ident {
+		format_ident!("{}Field", self.local_name().to_pascal_case())
+	}
+
+	/// Generate the `<Type>Field` enum with a variant for every pointer which
+	/// can be updated by the upsert queries.
+	pub fn field_enum_tokens(&self) -> TokenStream {
+		let enum_ident = self.field_enum_ident();
+		let enum_doc = format!(
+			" The fields of `{}` which can be updated by the generated upsert queries.",
+			self.object_type.name
+		);
+		let variants = self
+			.update_pointers()
+			.map(|(pointer, _)| {
+				(
+					format_ident!("{}", pointer.name.to_pascal_case()),
+					pointer.name.as_str(),
+				)
+			})
+			.collect::<Vec<_>>();
+		let variant_tokens = variants.iter().map(|(ident, _)| quote!(#ident));
+		let name_tokens = variants
+			.iter()
+			.map(|(ident, name)| quote!(Self::#ident => #name));
+		let all_tokens = variants.iter().map(|(ident, _)| quote!(Self::#ident));
+
+		quote! {
+			#[doc = #enum_doc]
+			#[derive(::std::fmt::Debug, ::core::clone::Clone, ::core::marker::Copy, ::core::cmp::PartialEq, ::core::cmp::Eq)]
+			pub enum #enum_ident {
+				#(#variant_tokens),*
+			}
+			impl #enum_ident {
+				/// Every field which can be updated.
+				pub const ALL: &'static [Self] = &[#(#all_tokens),*];
+				/// The name of the field in the schema.
+				pub fn as_str(&self) -> &'static str {
+					match *self {
+						#(#name_tokens,)*
+					}
+				}
+				/// Collect the names of the fields for the `update_fields` of an
+				/// upsert query.
+				pub fn names(fields: &[Self]) -> Vec<String> {
+					fields.iter().map(|field| field.as_str().to_string()).collect()
+				}
+			}
+			impl ::core::convert::From<#enum_ident> for String {
+				fn from(value: #enum_ident) -> Self {
+					value.as_str().to_string()
+				}
+			}
+		}
+	}
+}
+
+/// The cast of the query parameter for the pointer. Links are passed by the id
+/// of the target object.
+fn param(pointer: &Pointer, target: &str) -> String {
+	let name = &pointer.name;
+	let optional = if pointer.card == Cardinality::AtMostOne {
+		"optional "
+	} else {
+		""
+	};
+
+	if pointer.is_link() {
+		format!("<{target}>(<{optional}uuid>${name})")
+	} else {
+		format!("<{optional}{target}>${name}")
+	}
+}
+
+fn exclusive_names(exclusive: &Exclusives) -> Vec<&str> {
+	match exclusive {
+		Exclusives::One(pointer) => vec![pointer.name.as_str()],
+		Exclusives::Many(pointers) => pointers.iter().map(|p| p.name.as_str()).collect(),
+	}
+}
+
+/// The pointers of the object type including the pointers inherited from its
+/// bases. The pointers of the object type take precedence.
+fn all_pointers<'a>(types: &'a Types, object_type: &'a ObjectType) -> Vec<&'a Pointer> {
+	let mut pointers = object_type.pointers.iter().collect::<Vec<_>>();
+
+	for base in &object_type.bases {
+		let Some(Type::Object(base_type)) = types.get(&base.id) else {
+			continue;
+		};
+
+		for pointer in all_pointers(types, base_type) {
+			if !pointers.iter().any(|p| p.name == pointer.name) {
+				pointers.push(pointer);
+			}
+		}
+	}
+
+	pointers
+}
@@ -1,4 +1,7 @@
 /// Name of the query function.
 pub const QUERY_PROP_NAME: &str = "client";
 pub const TRANSACTION_PROP_NAME: &str = "conn";
+pub const EXECUTOR_PROP_NAME: &str = "executor";
 pub const PROPS_NAME: &str = "props";
+/// The maximum length in bytes of a query tag accepted by gel.
+pub const MAX_QUERY_TAG_LENGTH: usize = 128;
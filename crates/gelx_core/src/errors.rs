@@ -1,3 +1,7 @@
+use std::fmt::Write;
+use std::ops::Range;
+use std::path::Path;
+
 use gel_protocol::errors::DecodeError;
 use gel_tokio::dsn::error::ParseError;
 use proc_macro2::Span;
@@ -56,3 +60,137 @@ impl From<GelxCoreError> for syn::Error {
 		}
 	}
 }
+
+impl GelxCoreError {
+	/// The range of characters in the query which the database reported this
+	/// error for.
+	pub fn query_range(&self) -> Option<Range<usize>> {
+		let GelxCoreError::Gel(error) = self else {
+			return None;
+		};
+		let start = error.position_start()?;
+		let end = error.position_end().unwrap_or(start).max(start);
+
+		Some(start..end)
+	}
+}
+
+/// Describe the error for a query. Errors reported by the database include the
+/// hint provided by the server and, when the `query` is provided, the location
+/// and a caret underlined excerpt of the query.
+///
+/// The `path` is the file which contains the query and `first_line` is the
+/// line of the file which the query starts on.
+pub fn describe_query_error(
+	error: &GelxCoreError,
+	query: Option<&str>,
+	path: Option<&Path>,
+	first_line: usize,
+) -> String {
+	let GelxCoreError::Gel(gel_error) = error else {
+		return error.to_string();
+	};
+
+	let mut message = match gel_error.initial_message() {
+		Some(initial_message) => format!("{}: {initial_message}", gel_error.kind_name()),
+		None => gel_error.kind_name().to_string(),
+	};
+
+	if let Some((query, range)) = query.zip(error.query_range()) {
+		let prefix = query.chars().take(range.start).collect::<String>();
+		let line_index = prefix.matches('\n').count();
+		let column = prefix
+			.rsplit('\n')
+			.next()
+			.map_or(0, |value| value.chars().count());
+		let line = query.lines().nth(line_index).unwrap_or_default();
+		let length = range
+			.len()
+			.min(line.chars().count().saturating_sub(column))
+			.max(1);
+		let line_number = (line_index + first_line).to_string();
+		let gutter = " ".repeat(line_number.len());
+		let location = path.map_or_else(
+			|| format!("{line_number}:{}", column + 1),
+			|path| format!("{}:{line_number}:{}", path.display(), column + 1),
+		);
+
+		let _ = write!(
+			message,
+			"\n{gutter}--> {location}\n{gutter} |\n{line_number} | {line}\n{gutter} | {}{}",
+			" ".repeat(column),
+			"^".repeat(length)
+		);
+	} else if let Some(path) = path {
+		let _ = write!(message, "\n --> {}", path.display());
+	}
+
+	if let Some(hint) = gel_error.hint() {
+		let _ = write!(message, "\n  = hint: {hint}");
+	}
+
+	if let Some(details) = gel_error.details() {
+		let _ = write!(message, "\n  = details: {details}");
+	}
+
+	message
+}
+
+#[cfg(test)]
+mod tests {
+	use std::collections::HashMap;
+
+	use gel_errors::ErrorKind;
+	use gel_errors::InvalidReferenceError;
+
+	use super::*;
+
+	fn reference_error() -> GelxCoreError {
+		let headers = HashMap::from([
+			(0x0001, bytes::Bytes::from("did you mean 'default::User'?")),
+			(0xFFF1, bytes::Bytes::from("27")),
+			(0xFFF2, bytes::Bytes::from("30")),
+		]);
+		let error = InvalidReferenceError::with_message(
+			"object type or alias 'default::Usr' does not exist",
+		)
+		.with_headers(headers);
+
+		GelxCoreError::Gel(error)
+	}
+
+	#[test]
+	fn describe_error_without_query() {
+		let description = describe_query_error(&reference_error(), None, None, 1);
+
+		insta::assert_snapshot!(description, @r"
+		InvalidReferenceError: object type or alias 'default::Usr' does not exist
+		  = hint: did you mean 'default::User'?
+		");
+	}
+
+	#[test]
+	fn query_range() {
+		assert_eq!(reference_error().query_range(), Some(27..30));
+	}
+
+	#[test]
+	fn describe_error_with_excerpt() {
+		let query = "# Select the users.\nselect Usr { name };";
+		let description = describe_query_error(
+			&reference_error(),
+			Some(query),
+			Some(Path::new("queries/select_users.edgeql")),
+			1,
+		);
+
+		insta::assert_snapshot!(description, @r"
+		InvalidReferenceError: object type or alias 'default::Usr' does not exist
+		 --> queries/select_users.edgeql:2:8
+		  |
+		2 | select Usr { name };
+		  |        ^^^
+		  = hint: did you mean 'default::User'?
+		");
+	}
+}
@@ -5,13 +5,24 @@
 mod codegen;
 mod constants;
 mod errors;
+mod manifest;
 mod metadata;
+mod shapes;
+mod stats;
 mod utils;
 
 use std::collections::HashMap;
 use std::sync::Arc;
 
 use check_keyword::CheckKeyword;
+use gel_protocol::codec::STD_BOOL;
+use gel_protocol::codec::STD_FLOAT32;
+use gel_protocol::codec::STD_FLOAT64;
+use gel_protocol::codec::STD_INT16;
+use gel_protocol::codec::STD_INT32;
+use gel_protocol::codec::STD_INT64;
+use gel_protocol::codec::STD_STR;
+use gel_protocol::codec::STD_UUID;
 use gel_protocol::common::Capabilities;
 use gel_protocol::common::Cardinality;
 use gel_protocol::common::CompilationOptions;
@@ -29,6 +40,7 @@ use gel_tokio::raw::Pool;
 use gel_tokio::raw::PoolState;
 use heck::ToPascalCase;
 use heck::ToSnakeCase;
+use proc_macro2::Span;
 use proc_macro2::TokenStream;
 use quote::format_ident;
 use quote::quote;
@@ -37,11 +49,15 @@ use syn::Token;
 use syn::punctuated::Punctuated;
 use tokio::runtime::Runtime;
 use typed_builder::TypedBuilder;
+use uuid::Uuid;
 
 pub use crate::codegen::*;
 pub use crate::constants::*;
 pub use crate::errors::*;
+pub use crate::manifest::*;
 pub use crate::metadata::*;
+pub use crate::shapes::*;
+pub use crate::stats::*;
 pub use crate::utils::*;
 
 /// Get the query descriptor asynchronously.
@@ -59,7 +75,7 @@ pub async fn get_descriptor(
 		implicit_typenames: false,
 		implicit_typeids: false,
 		explicit_objectids: true,
-		allow_capabilities: Capabilities::ALL,
+		allow_capabilities: metadata.allowed_capabilities_flags(),
 		io_format: IoFormat::Binary,
 		expected_cardinality: Cardinality::Many,
 		input_language: InputLanguage::EdgeQL,
@@ -89,6 +105,96 @@ pub fn generate_query_token_stream(
 	query: &str,
 	metadata: &GelxMetadata,
 	is_macro: bool,
+) -> GelxCoreResult<TokenStream> {
+	generate_query_token_stream_with_context(
+		descriptor,
+		name,
+		query,
+		metadata,
+		is_macro,
+		QueryContext::default(),
+	)
+}
+
+/// Additional context used when generating the code for a query.
+#[derive(Clone, Copy, Debug, Default, TypedBuilder)]
+#[builder(field_defaults(default, setter(strip_option)))]
+pub struct QueryContext<'a> {
+	/// The types of the database schema. When provided the generated structs
+	/// and their fields are documented with the schema annotations.
+	pub types: Option<&'a Types>,
+	/// The registry of shared shapes. When provided the nested output shapes
+	/// are generated in the `shapes` module and shared between queries. The
+	/// module must be generated with [`Shapes::to_token_stream`] once all the
+	/// queries have been generated.
+	pub shapes: Option<&'a Shapes>,
+	/// The descriptors of the queries derived from the query. Required when
+	/// the metadata opts the query into functions which run a derived query,
+	/// e.g. the `query_stream` function.
+	pub derived: Option<&'a DerivedDescriptors>,
+	/// The path of the `<Type>Field` enum of an upsert query relative to the
+	/// root module. When provided the builder setter of the `update_fields`
+	/// parameter accepts a slice of the enum.
+	pub update_fields: Option<&'a TokenStream>,
+}
+
+/// The descriptors of the queries which are derived from a query, e.g. the
+/// `QUERY_STREAM` of the `query_stream` function. The derived queries are
+/// described when the code is generated so that a query which can't be
+/// derived fails the code generation rather than the first request.
+#[derive(Clone, Debug, Default)]
+pub struct DerivedDescriptors {
+	/// The descriptor of the `QUERY_STREAM`.
+	pub stream: Option<CommandDataDescription1>,
+	/// The descriptor of the `QUERY_BULK`.
+	pub bulk: Option<CommandDataDescription1>,
+}
+
+impl DerivedDescriptors {
+	/// Describe the queries derived from the `query` with the provided module
+	/// `name` which are enabled by the `metadata`.
+	pub async fn try_new(name: &str, query: &str, metadata: &GelxMetadata) -> GelxCoreResult<Self> {
+		let name = name.to_snake_case();
+		let mut derived = Self::default();
+
+		if metadata.is_stream_query(&name) {
+			let descriptor = get_descriptor(&keyset_query(query), metadata)
+				.await
+				.map_err(|error| gelx_error!("The stream query of `{name}` is invalid: {error}"))?;
+			derived.stream = Some(descriptor);
+		}
+
+		if metadata.is_bulk_query(&name) {
+			let Some(query) = bulk_query(query) else {
+				return Err(gelx_error!(
+					"The bulk query of `{name}` is invalid: every parameter must be cast"
+				));
+			};
+			let descriptor = get_descriptor(&query, metadata)
+				.await
+				.map_err(|error| gelx_error!("The bulk query of `{name}` is invalid: {error}"))?;
+			derived.bulk = Some(descriptor);
+		}
+
+		Ok(derived)
+	}
+
+	/// Describe the derived queries synchronously.
+	pub fn try_new_sync(name: &str, query: &str, metadata: &GelxMetadata) -> GelxCoreResult<Self> {
+		let rt = Runtime::new()?;
+
+		rt.block_on(Self::try_new(name, query, metadata))
+	}
+}
+
+/// Generate the query module with the additional [`QueryContext`].
+pub fn generate_query_token_stream_with_context(
+	descriptor: &CommandDataDescription1,
+	name: &str,
+	query: &str,
+	metadata: &GelxMetadata,
+	is_macro: bool,
+	context: QueryContext<'_>,
 ) -> GelxCoreResult<TokenStream> {
 	let input_ident = metadata.input_struct_ident();
 	let output_ident = metadata.output_struct_ident();
@@ -96,11 +202,14 @@ pub fn generate_query_token_stream(
 
 	let query_ident = metadata.query_function_ident();
 	let transaction_ident = metadata.transaction_function_ident();
+	let execute_ident = metadata.execute_function_ident();
 	let query_prop_ident = format_ident!("{QUERY_PROP_NAME}");
 	let transaction_prop_ident = format_ident!("{TRANSACTION_PROP_NAME}");
+	let executor_prop_ident = format_ident!("{EXECUTOR_PROP_NAME}");
 	let module_name: Ident = format_ident!("{}", name.to_snake_case());
 	let input = descriptor.input.decode()?;
 	let output = descriptor.output.decode()?;
+	let exports_ident = metadata.exports_alias_ident();
 	let mut tokens: TokenStream = TokenStream::new();
 
 	explore_descriptor(
@@ -112,6 +221,10 @@ pub fn generate_query_token_stream(
 			.root_name(&metadata.input_struct_name)
 			.metadata(metadata)
 			.is_macro_bool(is_macro)
+			.types(context.types)
+			.query_name(Some(name))
+			.shapes(context.shapes)
+			.update_fields(context.update_fields)
 			.build(),
 		&mut tokens,
 	)?;
@@ -123,61 +236,1134 @@ pub fn generate_query_token_stream(
 			.root_name(&metadata.output_struct_name)
 			.metadata(metadata)
 			.is_macro_bool(is_macro)
+			.types(context.types)
+			.query_name(Some(name))
+			.shapes(context.shapes)
 			.build(),
 		&mut tokens,
 	)?;
 
+	let validation = context.types.and_then(|types| {
+		generate_input_validation(&input, &output, query, metadata, types, is_macro)
+	});
+	let validate_call = (metadata.validate_input && validation.is_some())
+		.then(|| quote!(#exports_ident::validate::Validate::validate(#props_ident)?;));
+
 	let query_method = match descriptor.result_cardinality {
 		Cardinality::NoResult => quote!(execute),
 		Cardinality::AtMostOne => quote!(query_single),
 		Cardinality::One => quote!(query_required_single),
 		Cardinality::Many | Cardinality::AtLeastOne => quote!(query),
 	};
-	let exports_ident = metadata.exports_alias_ident();
 	let query_constant = metadata.query_constant_ident();
 	let mut query_props = vec![quote!(#query_prop_ident: &#exports_ident::gel_tokio::Client)];
 	let mut transaction_props =
 		vec![quote!(#transaction_prop_ident: &mut #exports_ident::gel_tokio::Transaction)];
+	let executor_trait = if is_read_only(descriptor.capabilities) {
+		quote!(#exports_ident::Executor)
+	} else {
+		quote!(#exports_ident::Modifying)
+	};
+	let capabilities = proc_macro2::Literal::u64_unsuffixed(descriptor.capabilities.bits());
+	let mut execute_props = vec![quote!(mut #executor_prop_ident: impl #executor_trait)];
+	let query_tag_constant = metadata.query_tag_constant_ident();
+	let query_tag = metadata.query_tag(&name.to_snake_case())?;
+	let query_hash_constant = metadata.query_hash_constant_ident();
+	let query_hash = query_hash(query);
+	// The functions which take a `Client` can't be wrapped with an allowlisted
+	// executor, so they check the hash of the query against the installed
+	// allowlist.
+	let allowlist_check = metadata
+		.features
+		.is_enabled(FeatureName::Allowlist, is_macro)
+		.then(|| {
+			let allowlist_annotation = metadata.features.annotate(FeatureName::Allowlist, is_macro);
+
+			quote! {
+				#allowlist_annotation
+				#exports_ident::allowlist::check_hash(#query_hash_constant)?;
+			}
+		});
+	let mut query_args = vec![quote!(&#query_prop_ident.with_tag(Some(#query_tag_constant))?)];
+	let mut transaction_args = vec![quote!(#transaction_prop_ident)];
+	let mut query_with_args = vec![quote!(#query_prop_ident)];
 	let args = vec![
 		quote!(#query_constant),
 		input.root().map_or(quote!(&()), |_| quote!(#props_ident)),
 	];
 	let inner_return = output.root().map_or(quote!(()), |_| quote!(#output_ident));
-	let returns = wrap_token_with_cardinality(Some(descriptor.result_cardinality), inner_return);
+	let returns =
+		wrap_token_with_cardinality(Some(descriptor.result_cardinality), inner_return.clone());
 
 	if input.root().is_some() {
 		query_props.push(quote!(#props_ident: &#input_ident));
 		transaction_props.push(quote!(#props_ident: &#input_ident));
+		execute_props.push(quote!(#props_ident: &#input_ident));
+		query_args.push(quote!(#props_ident));
+		transaction_args.push(quote!(#props_ident));
+		query_with_args.push(quote!(#props_ident));
 	}
 
+	// The `query_with` function applies the default options of the query.
+	let query_body =
+		if metadata.query_options_for(&name.to_snake_case()) == GelxQueryOptions::default() {
+			quote! {
+				#allowlist_check
+				#execute_ident(#(#query_args),*).await
+			}
+		} else {
+			let query_with_ident = metadata.query_with_function_ident();
+			quote!(#query_with_ident(#(#query_with_args,)* #exports_ident::QueryOptions::new()).await)
+		};
+
 	let query_annotation = metadata.features.annotate(FeatureName::Query, is_macro);
+	let query_call = quote!(#executor_prop_ident.#query_method(#(#args),*));
+	let execute_body = if let Some(instrument) =
+		generate_query_span(descriptor, &input, name, metadata, is_macro)
+	{
+		let tracing_annotation = metadata.features.annotate(FeatureName::Tracing, is_macro);
+
+		quote! {
+			let future = #query_call;
+			#tracing_annotation
+			let future = #instrument;
+			future.await
+		}
+	} else {
+		quote!(#query_call.await)
+	};
+	let stream_tokens = generate_query_stream(
+		descriptor,
+		&input,
+		&output,
+		name,
+		query,
+		metadata,
+		context.derived,
+		&query_annotation,
+		validate_call.as_ref(),
+		allowlist_check.as_ref(),
+	)?;
+	let comment_doc =
+		query_leading_comment(query).map(|comment| doc_tokens(Some(&format!("{comment}\n\n"))));
+	let query_with_tokens = generate_query_with(
+		&input,
+		&returns,
+		name,
+		metadata,
+		&query_annotation,
+		comment_doc.as_ref(),
+		allowlist_check.as_ref(),
+	);
+	let blocking_tokens = generate_query_blocking(
+		&input,
+		&returns,
+		metadata,
+		is_macro,
+		&query_annotation,
+		comment_doc.as_ref(),
+	);
+	let json_tokens = generate_query_json(
+		descriptor,
+		&input,
+		metadata,
+		&query_annotation,
+		validate_call.as_ref(),
+		allowlist_check.as_ref(),
+	);
+	let pagination_tokens = generate_query_pagination(
+		descriptor,
+		&input,
+		&output,
+		metadata,
+		&query_annotation,
+		validate_call.as_ref(),
+		allowlist_check.as_ref(),
+	);
+	let fingerprint_tokens = generate_query_fingerprint(descriptor, name, metadata, is_macro);
+	let bulk_tokens = generate_query_bulk(
+		descriptor,
+		&input,
+		&inner_return,
+		name,
+		query,
+		metadata,
+		is_macro,
+		context.derived,
+		validate_call.as_ref(),
+		allowlist_check.as_ref(),
+	)?;
 
 	let token_stream = quote! {
 		pub mod #module_name {
 			use ::gelx::exports as #exports_ident;
 
+			#comment_doc
+			/// Execute the query with any executor, e.g. a client or a transaction.
+			#query_annotation
+			pub async fn #execute_ident(#(#execute_props),*) -> ::core::result::Result<#returns, #exports_ident::gel_errors::Error> {
+				#validate_call
+				#execute_body
+			}
+
+			#comment_doc
 			/// Execute the desired query.
 			#query_annotation
 			pub async fn #query_ident(#(#query_props),*) -> ::core::result::Result<#returns, #exports_ident::gel_errors::Error> {
-				#query_prop_ident.#query_method(#(#args),*).await
+				#query_body
 			}
 
+			#query_with_tokens
+
+			#comment_doc
 			/// Compose the query as part of a larger transaction.
 			#query_annotation
 			pub async fn #transaction_ident(#(#transaction_props),*) -> ::core::result::Result<#returns, #exports_ident::gel_errors::Error> {
-				#transaction_prop_ident.#query_method(#(#args),*).await
+				#execute_ident(#(#transaction_args),*).await
 			}
 
+			#blocking_tokens
+
+			#stream_tokens
+
+			#pagination_tokens
+
+			#json_tokens
+
+			#bulk_tokens
+
 			#tokens
 
+			#validation
+
+			/// The tag which attributes the statistics of the query in `sys::QueryStats`.
+			pub const #query_tag_constant: &str = #query_tag;
+
+			/// The capabilities of the query reported by the database.
+			pub const CAPABILITIES: #exports_ident::Capabilities = #exports_ident::Capabilities::from_bits_retain(#capabilities);
+
+			#comment_doc
 			/// The original query string provided to the macro. Can be reused in your codebase.
 			pub const #query_constant: &str = #query;
+
+			/// The hash of the query which identifies it in the `queries.lock.json` manifest.
+			pub const #query_hash_constant: &str = #query_hash;
+
+			#fingerprint_tokens
 		}
 	};
 
 	Ok(token_stream)
 }
 
+/// Read only queries can run with any executor, including the
+/// `ReadOnly` executor used for replicas. Session config and transaction
+/// statements don't change the data so they are still read only.
+fn is_read_only(capabilities: Capabilities) -> bool {
+	!capabilities.intersects(
+		Capabilities::MODIFICATIONS | Capabilities::DDL | Capabilities::PERSISTENT_CONFIG,
+	)
+}
+
+/// Generate the `Validate` implementation for the query input from the schema
+/// constraints.
+///
+/// Custom scalar parameters are checked with their own implementation.
+/// Parameters which share a name with a constrained property of the object
+/// type returned by the query are checked against the constraints of that
+/// property.
+fn generate_input_validation(
+	input: &Typedesc,
+	output: &Typedesc,
+	query: &str,
+	metadata: &GelxMetadata,
+	types: &Types,
+	is_macro: bool,
+) -> Option<TokenStream> {
+	let elements = match input.root()? {
+		Descriptor::ObjectShape(shape) => StructElement::from_shape(&shape.elements),
+		Descriptor::InputShape(shape) => StructElement::from_input_shape(&shape.elements),
+		_ => return None,
+	};
+	let object_type = match output.root() {
+		Some(Descriptor::ObjectShape(shape)) => {
+			object_type_name(output, shape.type_pos).and_then(|name| find_object_type(types, name))
+		}
+		_ => None,
+	};
+	let exports_ident = metadata.exports_alias_ident();
+	let input_ident = metadata.input_struct_ident();
+	let mut checks = vec![];
+
+	for element in elements {
+		let name = element.name();
+		let field_ident = format_ident!("{}", name.to_snake_case().into_safe());
+
+		let scalar_id = match input.get(element.type_pos()).ok() {
+			Some(Descriptor::BaseScalar(scalar)) => *scalar.id,
+			Some(Descriptor::Scalar(scalar)) => {
+				let Some(module_name) = scalar.name.as_deref().map(ModuleName::from) else {
+					continue;
+				};
+
+				if module_name.is_system_namespace() {
+					*scalar.id
+				} else {
+					let has_checks = match types.get(&*scalar.id) {
+						Some(Type::Scalar(scalar_type)) => {
+							!scalar_constraint_checks(scalar_type, metadata, is_macro).is_empty()
+						}
+						_ => false,
+					};
+
+					if has_checks {
+						checks.push(
+							quote!(#exports_ident::validate::Validate::validate(&self.#field_ident)?;),
+						);
+					}
+
+					continue;
+				}
+			}
+			_ => continue,
+		};
+
+		// The constraints of a pointer only apply to the values which are assigned to
+		// it, not to the parameters of a `filter`.
+		let Some(pointer_name) = assigned_pointer(query, &name) else {
+			continue;
+		};
+		let Some((owner, pointer)) = object_type.and_then(|object_type| {
+			let owner = pointer_owner(types, object_type, pointer_name)?;
+			Some((owner, find_pointer(types, owner, pointer_name)?))
+		}) else {
+			continue;
+		};
+		let Some(target) = ConstraintTarget::from_material_id(&scalar_id) else {
+			continue;
+		};
+
+		if pointer.target_id != scalar_id {
+			continue;
+		}
+
+		let subject = format!("{}.{pointer_name}", owner.name);
+		let is_optional = element.cardinality() == Cardinality::AtMostOne;
+		let value = if is_optional {
+			quote!(value)
+		} else {
+			quote!(&self.#field_ident)
+		};
+		let pointer_checks = constraint_checks(
+			&pointer.constraints,
+			target,
+			&subject,
+			&value,
+			metadata,
+			is_macro,
+		);
+
+		if pointer_checks.is_empty() {
+			continue;
+		}
+
+		if is_optional {
+			checks.push(quote! {
+				if let Some(value) = &self.#field_ident {
+					#(#pointer_checks)*
+				}
+			});
+		} else {
+			checks.extend(pointer_checks);
+		}
+	}
+
+	if checks.is_empty() {
+		return None;
+	}
+
+	Some(quote! {
+		impl #exports_ident::validate::Validate for #input_ident {
+			fn validate(&self) -> ::core::result::Result<(), #exports_ident::validate::ValidationError> {
+				#(#checks)*
+				Ok(())
+			}
+		}
+	})
+}
+
+/// Generate the `query_with` function which runs the query with the
+/// `QueryOptions` of the call and the defaults of the matching
+/// `query_options` rules.
+fn generate_query_with(
+	input: &Typedesc,
+	returns: &TokenStream,
+	name: &str,
+	metadata: &GelxMetadata,
+	query_annotation: &TokenStream,
+	comment_doc: Option<&TokenStream>,
+	allowlist_check: Option<&TokenStream>,
+) -> TokenStream {
+	let exports_ident = metadata.exports_alias_ident();
+	let input_ident = metadata.input_struct_ident();
+	let props_ident = format_ident!("{PROPS_NAME}");
+	let query_prop_ident = format_ident!("{QUERY_PROP_NAME}");
+	let transaction_prop_ident = format_ident!("{TRANSACTION_PROP_NAME}");
+	let execute_ident = metadata.execute_function_ident();
+	let query_with_ident = metadata.query_with_function_ident();
+	let query_tag_constant = metadata.query_tag_constant_ident();
+	let (props_param, props_arg) = match input.root() {
+		Some(_) => (quote!(#props_ident: &#input_ident,), quote!(, #props_ident)),
+		None => (quote!(), quote!()),
+	};
+	let defaults = metadata.query_options_for(&name.to_snake_case());
+	let mut default_options = vec![];
+
+	if let Some(timeout_ms) = defaults.timeout_ms {
+		default_options.push(quote!(.timeout(::core::time::Duration::from_millis(#timeout_ms))));
+	}
+
+	if let Some(attempts) = defaults.retry_attempts {
+		default_options.push(quote!(.retry_attempts(#attempts)));
+	}
+
+	if let Some(read_only) = defaults.read_only {
+		default_options.push(quote! {
+			.transaction(#exports_ident::gel_tokio::TransactionOptions::default().read_only(#read_only))
+		});
+	}
+
+	let options_defaults = (!default_options.is_empty()).then(
+		|| quote!(let options = options.or(#exports_ident::QueryOptions::new() #(#default_options)*);),
+	);
+
+	quote! {
+		#comment_doc
+		/// Execute the query with the provided options, e.g. a timeout, a
+		/// retry policy or the options of the transaction which runs the query.
+		#query_annotation
+		pub async fn #query_with_ident(
+			#query_prop_ident: &#exports_ident::gel_tokio::Client,
+			#props_param
+			options: #exports_ident::QueryOptions,
+		) -> ::core::result::Result<#returns, #exports_ident::gel_errors::Error> {
+			#allowlist_check
+			#options_defaults
+			let #query_prop_ident = options.apply_to(&#query_prop_ident.with_tag(Some(#query_tag_constant))?);
+
+			if options.is_transaction() {
+				return #query_prop_ident
+					.transaction(|mut #transaction_prop_ident| async move {
+						#execute_ident(&mut *#transaction_prop_ident #props_arg).await
+					})
+					.await;
+			}
+
+			#execute_ident(&#query_prop_ident #props_arg).await
+		}
+	}
+}
+
+/// Generate the `query_blocking` and `transaction_blocking` functions which
+/// block on the `query` and `transaction` functions with the runtime of a
+/// `BlockingClient`.
+fn generate_query_blocking(
+	input: &Typedesc,
+	returns: &TokenStream,
+	metadata: &GelxMetadata,
+	is_macro: bool,
+	query_annotation: &TokenStream,
+	comment_doc: Option<&TokenStream>,
+) -> Option<TokenStream> {
+	if !metadata
+		.features
+		.is_enabled(FeatureName::Blocking, is_macro)
+	{
+		return None;
+	}
+
+	let exports_ident = metadata.exports_alias_ident();
+	let input_ident = metadata.input_struct_ident();
+	let props_ident = format_ident!("{PROPS_NAME}");
+	let query_prop_ident = format_ident!("{QUERY_PROP_NAME}");
+	let transaction_prop_ident = format_ident!("{TRANSACTION_PROP_NAME}");
+	let query_ident = metadata.query_function_ident();
+	let transaction_ident = metadata.transaction_function_ident();
+	let query_blocking_ident = metadata.query_blocking_function_ident();
+	let transaction_blocking_ident = metadata.transaction_blocking_function_ident();
+	let blocking_annotation = metadata.features.annotate(FeatureName::Blocking, is_macro);
+	let (props_param, props_arg) = match input.root() {
+		Some(_) => {
+			(
+				quote!(, #props_ident: &#input_ident),
+				quote!(, #props_ident),
+			)
+		}
+		None => (quote!(), quote!()),
+	};
+
+	Some(quote! {
+		#comment_doc
+		/// Execute the desired query from synchronous code.
+		#query_annotation
+		#blocking_annotation
+		pub fn #query_blocking_ident(#query_prop_ident: &#exports_ident::BlockingClient #props_param) -> ::core::result::Result<#returns, #exports_ident::gel_errors::Error> {
+			#query_prop_ident.block_on(#query_ident(#query_prop_ident.client() #props_arg))
+		}
+
+		#comment_doc
+		/// Compose the query as part of a larger transaction from synchronous code.
+		#query_annotation
+		#blocking_annotation
+		pub fn #transaction_blocking_ident(#transaction_prop_ident: &mut #exports_ident::BlockingTransaction<'_> #props_param) -> ::core::result::Result<#returns, #exports_ident::gel_errors::Error> {
+			let (handle, #transaction_prop_ident) = #transaction_prop_ident.split();
+			handle.block_on(#transaction_ident(#transaction_prop_ident #props_arg))
+		}
+	})
+}
+
+/// Generate the `FINGERPRINT` of the descriptor the module was generated from
+/// and register it for `gelx::verify_all`.
+fn generate_query_fingerprint(
+	descriptor: &CommandDataDescription1,
+	name: &str,
+	metadata: &GelxMetadata,
+	is_macro: bool,
+) -> Option<TokenStream> {
+	if !metadata.features.is_enabled(FeatureName::Verify, is_macro) {
+		return None;
+	}
+
+	let exports_ident = metadata.exports_alias_ident();
+	let query_constant = metadata.query_constant_ident();
+	let verify_annotation = metadata.features.annotate(FeatureName::Verify, is_macro);
+	let name = name.to_snake_case();
+	let input_id = uuid_literal(descriptor.input.id);
+	let output_id = uuid_literal(descriptor.output.id);
+	let cardinality = match descriptor.result_cardinality {
+		Cardinality::NoResult => quote!(NoResult),
+		Cardinality::AtMostOne => quote!(AtMostOne),
+		Cardinality::One => quote!(One),
+		Cardinality::Many => quote!(Many),
+		Cardinality::AtLeastOne => quote!(AtLeastOne),
+	};
+
+	Some(quote! {
+		/// The descriptor of the query when the module was generated. It is compared with the
+		/// descriptor of the current schema by `gelx::verify_all`.
+		#verify_annotation
+		pub const FINGERPRINT: #exports_ident::verify::QueryFingerprint = #exports_ident::verify::QueryFingerprint {
+			name: #name,
+			query: #query_constant,
+			input_id: #exports_ident::uuid::Uuid::from_u128(#input_id),
+			output_id: #exports_ident::uuid::Uuid::from_u128(#output_id),
+			cardinality: #exports_ident::gel_protocol::common::Cardinality::#cardinality,
+		};
+
+		#verify_annotation
+		#exports_ident::inventory::submit!(FINGERPRINT);
+	})
+}
+
+/// The `u128` literal of a uuid, e.g. for `Uuid::from_u128`. The digits are
+/// grouped by four to keep the literal readable.
+fn uuid_literal(id: Uuid) -> syn::LitInt {
+	let digits = format!("{:032x}", id.as_u128());
+	let groups = digits
+		.as_bytes()
+		.chunks(4)
+		.map(|group| String::from_utf8_lossy(group))
+		.collect::<Vec<_>>();
+
+	syn::LitInt::new(&format!("0x{}", groups.join("_")), Span::call_site())
+}
+
+/// Wrap the future of the query in a `tracing` span with the name of the query
+/// module and the cardinality of the result. The query arguments are only
+/// recorded when they are listed in the `record_fields` tracing option.
+fn generate_query_span(
+	descriptor: &CommandDataDescription1,
+	input: &Typedesc,
+	name: &str,
+	metadata: &GelxMetadata,
+	is_macro: bool,
+) -> Option<TokenStream> {
+	if !metadata.features.is_enabled(FeatureName::Tracing, is_macro) {
+		return None;
+	}
+
+	let exports_ident = metadata.exports_alias_ident();
+	let props_ident = format_ident!("{PROPS_NAME}");
+	let module_name = name.to_snake_case();
+	let empty = quote!(#exports_ident::tracing::field::Empty);
+	let (cardinality, instrument, rows) = match descriptor.result_cardinality {
+		Cardinality::NoResult => ("no_result", quote!(query), None),
+		Cardinality::AtMostOne => ("at_most_one", quote!(query), None),
+		Cardinality::One => ("one", quote!(query), None),
+		Cardinality::Many => ("many", quote!(query_many), Some(quote!(rows = #empty,))),
+		Cardinality::AtLeastOne => {
+			(
+				"at_least_one",
+				quote!(query_many),
+				Some(quote!(rows = #empty,)),
+			)
+		}
+	};
+	let elements = match input.root() {
+		Some(Descriptor::ObjectShape(shape)) => StructElement::from_shape(&shape.elements),
+		Some(Descriptor::InputShape(shape)) => StructElement::from_input_shape(&shape.elements),
+		_ => vec![],
+	};
+	let fields = elements
+		.iter()
+		.map(StructElement::name)
+		.filter(|name| metadata.tracing.record_fields.contains(name))
+		.map(|name| {
+			let field_ident = format_ident!("{}", name.to_snake_case().into_safe());
+			quote!(#name = ?#props_ident.#field_ident,)
+		});
+
+	Some(quote! {
+		#exports_ident::instrument::#instrument(
+			#exports_ident::tracing::info_span!(
+				"gelx.query",
+				query = #module_name,
+				cardinality = #cardinality,
+				#rows
+				elapsed_ms = #empty,
+				error = #empty,
+				#(#fields)*
+			),
+			future,
+		)
+	})
+}
+
+/// Generate the `query_json` function which returns the output rendered as
+/// JSON by the database. The output isn't decoded so it can be passed on
+/// without serializing it again.
+fn generate_query_json(
+	descriptor: &CommandDataDescription1,
+	input: &Typedesc,
+	metadata: &GelxMetadata,
+	query_annotation: &TokenStream,
+	validate_call: Option<&TokenStream>,
+	allowlist_check: Option<&TokenStream>,
+) -> Option<TokenStream> {
+	if !metadata.json_functions {
+		return None;
+	}
+
+	let exports_ident = metadata.exports_alias_ident();
+	let json_method = match descriptor.result_cardinality {
+		Cardinality::NoResult => return None,
+		Cardinality::AtMostOne => quote!(query_single_json),
+		Cardinality::One => quote!(query_required_single_json),
+		Cardinality::Many | Cardinality::AtLeastOne => quote!(query_json),
+	};
+	let json = quote!(#exports_ident::gel_protocol::model::Json);
+	let returns = match descriptor.result_cardinality {
+		Cardinality::AtMostOne => quote!(Option<#json>),
+		_ => json,
+	};
+	let input_ident = metadata.input_struct_ident();
+	let props_ident = format_ident!("{PROPS_NAME}");
+	let query_prop_ident = format_ident!("{QUERY_PROP_NAME}");
+	let json_ident = metadata.query_json_function_ident();
+	let query_constant = metadata.query_constant_ident();
+	let query_tag_constant = metadata.query_tag_constant_ident();
+	let (props_arg, args) = match input.root() {
+		Some(_) => (quote!(, #props_ident: &#input_ident), quote!(#props_ident)),
+		None => (quote!(), quote!(&())),
+	};
+
+	Some(quote! {
+		/// Execute the query and return the output as JSON rendered by the
+		/// database. A set of results is returned as a JSON array.
+		#query_annotation
+		pub async fn #json_ident(#query_prop_ident: &#exports_ident::gel_tokio::Client #props_arg) -> ::core::result::Result<#returns, #exports_ident::gel_errors::Error> {
+			#allowlist_check
+			#validate_call
+			#query_prop_ident.with_tag(Some(#query_tag_constant))?.#json_method(#query_constant, #args).await
+		}
+	})
+}
+
+/// Generate the `execute_bulk` and `query_bulk` functions for the queries
+/// matched by the `bulk` options. The inputs are serialized to a JSON array
+/// and the query runs once for every item, so each chunk of inputs only needs
+/// a single round trip.
+#[allow(clippy::too_many_arguments)]
+fn generate_query_bulk(
+	descriptor: &CommandDataDescription1,
+	input: &Typedesc,
+	inner_return: &TokenStream,
+	name: &str,
+	query: &str,
+	metadata: &GelxMetadata,
+	is_macro: bool,
+	derived: Option<&DerivedDescriptors>,
+	validate_call: Option<&TokenStream>,
+	allowlist_check: Option<&TokenStream>,
+) -> GelxCoreResult<Option<TokenStream>> {
+	let module_name = name.to_snake_case();
+
+	if !metadata.is_bulk_query(&module_name) {
+		return Ok(None);
+	}
+
+	let elements = match input.root() {
+		Some(Descriptor::ObjectShape(shape)) => StructElement::from_shape(&shape.elements),
+		Some(Descriptor::InputShape(shape)) => StructElement::from_input_shape(&shape.elements),
+		_ => vec![],
+	};
+	let names = elements.iter().map(StructElement::name).collect::<Vec<_>>();
+	let error = if !metadata.features.is_enabled(FeatureName::Serde, is_macro) {
+		Some("the `serde` feature is disabled".to_string())
+	} else if names.is_empty() {
+		Some("the query has no parameters".to_string())
+	} else if names
+		.iter()
+		.any(|name| name.chars().all(|char| char.is_ascii_digit()))
+	{
+		Some("positional parameters can't be read from the items".to_string())
+	} else if descriptor.result_cardinality == Cardinality::NoResult {
+		Some("the query doesn't return a result".to_string())
+	} else {
+		// The arguments are serialized with `serde_json` and cast back by the
+		// database, so only the types which round trip through JSON are supported.
+		elements
+			.iter()
+			.find(|element| !is_json_castable(input, element.type_pos(), metadata))
+			.map(|element| format!("the parameter `{}` can't be cast from JSON", element.name()))
+	};
+
+	if let Some(error) = error {
+		return Err(gelx_error!(
+			"The bulk functions of `{module_name}` can't be generated: {error}"
+		));
+	}
+
+	let Some(bulk_descriptor) = derived.and_then(|derived| derived.bulk.as_ref()) else {
+		return Err(gelx_error!(
+			"The bulk query of `{module_name}` wasn't described, see `DerivedDescriptors`"
+		));
+	};
+
+	if bulk_descriptor.output.id != descriptor.output.id {
+		return Err(gelx_error!(
+			"The bulk query of `{module_name}` returns a different type than the query"
+		));
+	}
+
+	let Some(bulk_query) = bulk_query(query) else {
+		return Err(gelx_error!(
+			"The bulk query of `{module_name}` is invalid: every parameter must be cast"
+		));
+	};
+
+	let exports_ident = metadata.exports_alias_ident();
+	let input_ident = metadata.input_struct_ident();
+	let props_ident = format_ident!("{PROPS_NAME}");
+	let query_prop_ident = format_ident!("{QUERY_PROP_NAME}");
+	let transaction_prop_ident = format_ident!("{TRANSACTION_PROP_NAME}");
+	let executor_prop_ident = format_ident!("{EXECUTOR_PROP_NAME}");
+	let execute_bulk_ident = metadata.execute_bulk_function_ident();
+	let query_bulk_ident = metadata.query_bulk_function_ident();
+	let bulk_constant = metadata.query_bulk_constant_ident();
+	let query_tag_constant = metadata.query_tag_constant_ident();
+	let chunk_size = metadata.bulk.chunk_size.max(1);
+	let executor_trait = if is_read_only(descriptor.capabilities) {
+		quote!(#exports_ident::Executor)
+	} else {
+		quote!(#exports_ident::Modifying)
+	};
+	let annotations = [
+		metadata.features.annotate(FeatureName::Query, is_macro),
+		metadata.features.annotate(FeatureName::Serde, is_macro),
+	];
+	let fields = names.iter().map(|name| {
+		let field_ident = format_ident!("{}", name.to_snake_case().into_safe());
+		quote!((#name, #exports_ident::bulk::to_value(&#props_ident.#field_ident)?),)
+	});
+
+	Ok(Some(quote! {
+		/// Execute the query once for every input. The inputs are sent in chunks of
+		/// `BULK_CHUNK_SIZE` with a single round trip for each chunk.
+		#(#annotations)*
+		pub async fn #execute_bulk_ident(
+			mut #executor_prop_ident: impl #executor_trait,
+			#props_ident: &[#input_ident],
+		) -> ::core::result::Result<Vec<#inner_return>, #exports_ident::gel_errors::Error> {
+			let mut outputs = Vec::with_capacity(#props_ident.len());
+
+			for chunk in #props_ident.chunks(BULK_CHUNK_SIZE) {
+				let mut items = #exports_ident::bulk::BulkItems::with_capacity(chunk.len());
+
+				for #props_ident in chunk {
+					#validate_call
+					items.push([#(#fields)*]);
+				}
+
+				outputs.extend(#executor_prop_ident.query::<#inner_return, _>(#bulk_constant, &items).await?);
+			}
+
+			Ok(outputs)
+		}
+
+		/// Execute the query once for every input. All the chunks run in a single
+		/// transaction so either every input or none of them is applied.
+		#(#annotations)*
+		pub async fn #query_bulk_ident(
+			#query_prop_ident: &#exports_ident::gel_tokio::Client,
+			#props_ident: &[#input_ident],
+		) -> ::core::result::Result<Vec<#inner_return>, #exports_ident::gel_errors::Error> {
+			#allowlist_check
+			#query_prop_ident
+				.with_tag(Some(#query_tag_constant))?
+				.transaction(|mut #transaction_prop_ident| async move {
+					#execute_bulk_ident(&mut *#transaction_prop_ident, #props_ident).await
+				})
+				.await
+		}
+
+		/// The maximum number of inputs sent with each bulk query.
+		pub const BULK_CHUNK_SIZE: usize = #chunk_size;
+
+		/// The query which runs the original query for every item of the `$items`
+		/// JSON array.
+		pub const #bulk_constant: &str = #bulk_query;
+	}))
+}
+
+/// Whether an argument of the type at `type_pos` is serialized by `serde_json`
+/// in a format which the database casts back to the same value, e.g. the
+/// `Duration` and `BigInt` models are serialized as objects and `Json` as a
+/// string, so they can't be used by the bulk queries.
+///
+/// The generated enums are serialized with their `serde` names, which only
+/// match the gel labels when the `serde.enum_rename_all` option isn't set.
+fn is_json_castable(typedesc: &Typedesc, type_pos: TypePos, metadata: &GelxMetadata) -> bool {
+	const JSON_CASTABLE_SCALARS: [Uuid; 8] = [
+		STD_UUID,
+		STD_STR,
+		STD_INT16,
+		STD_INT32,
+		STD_INT64,
+		STD_FLOAT32,
+		STD_FLOAT64,
+		STD_BOOL,
+	];
+
+	match typedesc.get(type_pos) {
+		Ok(Descriptor::BaseScalar(scalar)) => JSON_CASTABLE_SCALARS.contains(&scalar.id),
+		Ok(Descriptor::Scalar(scalar)) => {
+			JSON_CASTABLE_SCALARS.contains(&scalar.id)
+				|| scalar
+					.base_type_pos
+					.or_else(|| scalar.ancestors.last().copied())
+					.is_some_and(|base_type_pos| {
+						is_json_castable(typedesc, base_type_pos, metadata)
+					})
+		}
+		Ok(Descriptor::Enumeration(enumeration)) => {
+			enumeration.name.is_none() || metadata.serde.enum_rename_all.is_none()
+		}
+		Ok(Descriptor::Array(array)) => is_json_castable(typedesc, array.type_pos, metadata),
+		_ => false,
+	}
+}
+
+/// Generate the `query_stream` function for read only queries which return
+/// many objects with an `id`. The rows are fetched in pages with keyset
+/// pagination since `gel-tokio` doesn't support streaming.
+#[allow(clippy::too_many_arguments)]
+fn generate_query_stream(
+	descriptor: &CommandDataDescription1,
+	input: &Typedesc,
+	output: &Typedesc,
+	name: &str,
+	query: &str,
+	metadata: &GelxMetadata,
+	derived: Option<&DerivedDescriptors>,
+	query_annotation: &TokenStream,
+	validate_call: Option<&TokenStream>,
+	allowlist_check: Option<&TokenStream>,
+) -> GelxCoreResult<Option<TokenStream>> {
+	let name = name.to_snake_case();
+
+	if !metadata.is_stream_query(&name) {
+		return Ok(None);
+	}
+
+	let is_many = matches!(
+		descriptor.result_cardinality,
+		Cardinality::Many | Cardinality::AtLeastOne
+	);
+	// The pages are ordered by `id` and limited to the page size, so the ordering
+	// and pagination of the query would be discarded or applied to every page.
+	if let Some(clause) = top_level_clause(query, &["order by", "limit", "offset"]) {
+		return Err(gelx_error!(
+			"The query `{name}` can't be streamed: the top-level `{clause}` clause conflicts with \
+			 the `id` order of the pages"
+		));
+	}
+
+	// The `id` is used as the cursor so it must be part of the selected shape.
+	if !is_many || !has_required_id(output) || !is_read_only(descriptor.capabilities) {
+		return Err(gelx_error!(
+			"The query `{name}` can't be streamed: it must be read only and return a set of \
+			 objects which select the `id`"
+		));
+	}
+
+	let elements = match input.root() {
+		None => vec![],
+		Some(Descriptor::ObjectShape(shape)) => StructElement::from_shape(&shape.elements),
+		Some(Descriptor::InputShape(shape)) => StructElement::from_input_shape(&shape.elements),
+		Some(_) => {
+			return Err(gelx_error!(
+				"The query `{name}` can't be streamed: the input isn't supported"
+			));
+		}
+	};
+	let names = elements.iter().map(StructElement::name).collect::<Vec<_>>();
+
+	// Positional arguments can't be combined with the named pagination arguments.
+	if names
+		.iter()
+		.any(|name| name.chars().all(|char| char.is_ascii_digit()))
+	{
+		return Err(gelx_error!(
+			"The query `{name}` can't be streamed: it takes positional arguments"
+		));
+	}
+
+	let Some(stream_descriptor) = derived.and_then(|derived| derived.stream.as_ref()) else {
+		return Err(gelx_error!(
+			"The stream query of `{name}` wasn't described, see `DerivedDescriptors`"
+		));
+	};
+
+	if stream_descriptor.output.id != descriptor.output.id {
+		return Err(gelx_error!(
+			"The stream query of `{name}` returns a different type than the query"
+		));
+	}
+
+	let exports_ident = metadata.exports_alias_ident();
+	let output_ident = metadata.output_struct_ident();
+	let input_ident = metadata.input_struct_ident();
+	let props_ident = format_ident!("{PROPS_NAME}");
+	let query_prop_ident = format_ident!("{QUERY_PROP_NAME}");
+	let stream_ident = metadata.query_stream_function_ident();
+	let stream_constant = metadata.query_stream_constant_ident();
+	let query_tag_constant = metadata.query_tag_constant_ident();
+	let stream_query = keyset_query(query);
+	let props_arg = (!names.is_empty()).then(|| quote!(#props_ident: &'a #input_ident,));
+	let named_args = names.iter().map(|name| {
+		let field_ident = format_ident!("{}", name.to_snake_case().into_safe());
+		quote!(#name => #props_ident.#field_ident.clone(),)
+	});
+
+	Ok(Some(quote! {
+		/// Stream the results of the query without buffering them in memory. The
+		/// results are fetched in pages of `page_size` rows ordered by `id`.
+		#query_annotation
+		pub fn #stream_ident<'a>(
+			#query_prop_ident: &'a #exports_ident::gel_tokio::Client,
+			#props_arg
+			page_size: i64,
+		) -> impl #exports_ident::futures::Stream<Item = ::core::result::Result<#output_ident, #exports_ident::gel_errors::Error>> + 'a {
+			#exports_ident::stream::keyset(page_size, |output: &#output_ident| output.id, move |cursor, limit| {
+				async move {
+					#allowlist_check
+					#validate_call
+					let args = #exports_ident::gel_protocol::named_args! {
+						#(#named_args)*
+						"gelx_cursor" => cursor,
+						"gelx_limit" => limit,
+					};
+
+					#query_prop_ident.with_tag(Some(#query_tag_constant))?.query(#stream_constant, &args).await
+				}
+			})
+		}
+
+		/// The query used to fetch each page of results for the stream.
+		pub const #stream_constant: &str = #stream_query;
+	}))
+}
+
+/// Whether the output is an object shape which selects the `id`.
+fn has_required_id(output: &Typedesc) -> bool {
+	match output.root() {
+		Some(Descriptor::ObjectShape(shape)) => {
+			shape.elements.iter().any(|element| {
+				element.name == "id" && element.cardinality == Some(Cardinality::One)
+			})
+		}
+		_ => false,
+	}
+}
+
+/// The kind of pagination of a query, detected from the names of its
+/// parameters.
+enum Pagination<'a> {
+	/// The query skips the first `offset` results.
+	Offset(&'a StructElement<'a>),
+	/// The query returns the results after the `id` of the cursor.
+	Keyset,
+}
+
+/// Generate the `paginate`, `paginate_from` and `paginate_stream` functions for
+/// queries which return a set and take the limit parameter with either the
+/// offset or the cursor parameter of the `pagination` options.
+fn generate_query_pagination(
+	descriptor: &CommandDataDescription1,
+	input: &Typedesc,
+	output: &Typedesc,
+	metadata: &GelxMetadata,
+	query_annotation: &TokenStream,
+	validate_call: Option<&TokenStream>,
+	allowlist_check: Option<&TokenStream>,
+) -> Option<TokenStream> {
+	if !matches!(
+		descriptor.result_cardinality,
+		Cardinality::Many | Cardinality::AtLeastOne
+	) {
+		return None;
+	}
+
+	let elements = match input.root()? {
+		Descriptor::ObjectShape(shape) => StructElement::from_shape(&shape.elements),
+		Descriptor::InputShape(shape) => StructElement::from_input_shape(&shape.elements),
+		_ => return None,
+	};
+	let options = &metadata.pagination;
+	let find = |name: &str, scalar_id| {
+		elements.iter().find(|element| {
+			element.name() == name
+				&& match input.get(element.type_pos()).ok() {
+					Some(Descriptor::BaseScalar(scalar)) => *scalar.id == scalar_id,
+					Some(Descriptor::Scalar(scalar)) => *scalar.id == scalar_id,
+					_ => false,
+				}
+		})
+	};
+
+	find(&options.limit_param, STD_INT64)?;
+
+	let pagination = if let Some(offset) = find(&options.offset_param, STD_INT64) {
+		Pagination::Offset(offset)
+	} else if find(&options.cursor_param, STD_UUID)
+		.is_some_and(|cursor| cursor.cardinality() == Cardinality::AtMostOne)
+		&& has_required_id(output)
+	{
+		Pagination::Keyset
+	} else {
+		return None;
+	};
+
+	let exports_ident = metadata.exports_alias_ident();
+	let output_ident = metadata.output_struct_ident();
+	let input_ident = metadata.input_struct_ident();
+	let props_ident = format_ident!("{PROPS_NAME}");
+	let query_prop_ident = format_ident!("{QUERY_PROP_NAME}");
+	let query_constant = metadata.query_constant_ident();
+	let query_tag_constant = metadata.query_tag_constant_ident();
+	let paginate_ident = metadata.paginate_function_ident();
+	let paginate_from_ident = metadata.paginate_from_function_ident();
+	let paginate_stream_ident = metadata.paginate_stream_function_ident();
+	let limit_param = options.limit_param.as_str();
+	let named_args = elements
+		.iter()
+		.filter(|element| element.name() != options.limit_param)
+		.map(|element| {
+			let name = element.name();
+			let field_ident = format_ident!("{}", name.to_snake_case().into_safe());
+
+			match pagination {
+				Pagination::Offset(_) if name == options.offset_param => quote!(#name => offset,),
+				Pagination::Keyset if name == options.cursor_param => quote!(#name => cursor,),
+				_ => quote!(#name => #props_ident.#field_ident.clone(),),
+			}
+		});
+	let (cursor_type, start, page) = match pagination {
+		Pagination::Offset(offset) => {
+			let field_ident = format_ident!("{}", offset.name().to_snake_case().into_safe());
+			let start = if offset.cardinality() == Cardinality::AtMostOne {
+				quote!(let offset = cursor.or(#props_ident.#field_ident).unwrap_or_default();)
+			} else {
+				quote!(let offset = cursor.unwrap_or(#props_ident.#field_ident);)
+			};
+
+			(
+				quote!(i64),
+				start,
+				quote!(#exports_ident::stream::Page::from_offset(items, offset, page_size)),
+			)
+		}
+		Pagination::Keyset => {
+			let field_ident = format_ident!("{}", options.cursor_param.to_snake_case().into_safe());
+
+			(
+				quote!(#exports_ident::uuid::Uuid),
+				quote!(let cursor = cursor.or(#props_ident.#field_ident);),
+				quote!(#exports_ident::stream::Page::from_keyset(items, page_size, |output: &#output_ident| output.id)),
+			)
+		}
+	};
+	let page_type = quote!(#exports_ident::stream::Page<#output_ident, #cursor_type>);
+
+	Some(quote! {
+		/// Fetch the first page of `page_size` results. The page starts at the
+		/// offset or the cursor of the props.
+		#query_annotation
+		pub async fn #paginate_ident(
+			#query_prop_ident: &#exports_ident::gel_tokio::Client,
+			#props_ident: &#input_ident,
+			page_size: i64,
+		) -> ::core::result::Result<#page_type, #exports_ident::gel_errors::Error> {
+			#paginate_from_ident(#query_prop_ident, #props_ident, None, page_size).await
+		}
+
+		/// Fetch the page of `page_size` results which starts at the `cursor`, e.g.
+		/// the `next_cursor` of the previous page. The first page is fetched when
+		/// the `cursor` is `None`.
+		#query_annotation
+		pub async fn #paginate_from_ident(
+			#query_prop_ident: &#exports_ident::gel_tokio::Client,
+			#props_ident: &#input_ident,
+			cursor: Option<#cursor_type>,
+			page_size: i64,
+		) -> ::core::result::Result<#page_type, #exports_ident::gel_errors::Error> {
+			#allowlist_check
+			#validate_call
+			let page_size = page_size.max(1);
+			#start
+			let args = #exports_ident::gel_protocol::named_args! {
+				#(#named_args)*
+				#limit_param => page_size,
+			};
+			let items = #query_prop_ident.with_tag(Some(#query_tag_constant))?.query(#query_constant, &args).await?;
+
+			Ok(#page)
+		}
+
+		/// Stream the results of every page, starting with the page returned by
+		/// `paginate`.
+		#query_annotation
+		pub fn #paginate_stream_ident<'a>(
+			#query_prop_ident: &'a #exports_ident::gel_tokio::Client,
+			#props_ident: &'a #input_ident,
+			page_size: i64,
+		) -> impl #exports_ident::futures::Stream<Item = ::core::result::Result<#output_ident, #exports_ident::gel_errors::Error>> + 'a {
+			#exports_ident::stream::pages(move |cursor| #paginate_from_ident(#query_prop_ident, #props_ident, cursor, page_size))
+		}
+	})
+}
+
 fn wrap_token_with_cardinality(
 	cardinality: Option<Cardinality>,
 	token: TokenStream,
@@ -194,7 +1380,7 @@ fn wrap_token_with_cardinality(
 	}
 }
 
-#[derive(Debug, TypedBuilder)]
+#[derive(Clone, Copy, Debug, TypedBuilder)]
 struct ExploreDescriptorProps<'a> {
 	metadata: &'a GelxMetadata,
 	typedesc: &'a Typedesc,
@@ -206,6 +1392,16 @@ struct ExploreDescriptorProps<'a> {
 	is_root: bool,
 	descriptor: Option<&'a Descriptor>,
 	root_name: &'a str,
+	#[builder(default)]
+	types: Option<&'a Types>,
+	#[builder(default)]
+	query_name: Option<&'a str>,
+	#[builder(default)]
+	shapes: Option<&'a Shapes>,
+	/// The path of the `<Type>Field` enum of an upsert query, only set for the
+	/// root input.
+	#[builder(default)]
+	update_fields: Option<&'a TokenStream>,
 }
 
 type PartialExploreDescriptorProps<'a> = ExploreDescriptorPropsBuilder<
@@ -218,6 +1414,10 @@ type PartialExploreDescriptorProps<'a> = ExploreDescriptorPropsBuilder<
 		(bool,),
 		(),
 		(),
+		(Option<&'a Types>,),
+		(Option<&'a str>,),
+		(Option<&'a Shapes>,),
+		(),
 	),
 >;
 
@@ -228,6 +1428,9 @@ impl<'a> ExploreDescriptorProps<'a> {
 			is_input,
 			is_macro,
 			metadata,
+			types,
+			query_name,
+			shapes,
 			..
 		} = self;
 
@@ -237,6 +1440,9 @@ impl<'a> ExploreDescriptorProps<'a> {
 			.is_macro_bool(is_macro)
 			.is_root_bool(false)
 			.metadata(metadata)
+			.types(types)
+			.query_name(query_name)
+			.shapes(shapes)
 	}
 }
 
@@ -249,6 +1455,10 @@ fn explore_descriptor(
 		root_name,
 		metadata,
 		is_macro,
+		types,
+		query_name,
+		shapes,
+		..
 	}: ExploreDescriptorProps,
 	tokens: &mut TokenStream,
 ) -> GelxCoreResult<Option<TokenStream>> {
@@ -283,12 +1493,9 @@ fn explore_descriptor(
 
 		Descriptor::ObjectShape(object) => {
 			let result = explore_object_shape_descriptor(
+				props,
 				StructElement::from_shape(&object.elements),
-				typedesc,
-				root_name,
-				is_input,
-				metadata,
-				is_macro,
+				object_type_name(typedesc, object.type_pos),
 				tokens,
 			)?;
 
@@ -352,6 +1559,9 @@ fn explore_descriptor(
 						.descriptor(typedesc.get(*element).ok())
 						.root_name(&sub_root_name)
 						.metadata(metadata)
+						.types(types)
+						.query_name(query_name)
+						.shapes(shapes)
 						.build(),
 					tokens,
 				)?;
@@ -371,12 +1581,9 @@ fn explore_descriptor(
 
 		Descriptor::NamedTuple(named_tuple) => {
 			let result = explore_object_shape_descriptor(
+				props,
 				StructElement::from_named_tuple(&named_tuple.elements),
-				typedesc,
-				root_name,
-				is_input,
-				metadata,
-				is_macro,
+				None,
 				tokens,
 			)?;
 
@@ -405,7 +1612,7 @@ fn explore_descriptor(
 			// TODO: support ephemeral enums not defined in the schema
 			let result = if is_macro {
 				// Inline the enum in the macro output.
-				explore_enumeration_descriptor(enumeration, metadata, tokens, is_macro)
+				explore_enumeration_descriptor(enumeration, metadata, tokens, is_macro)?
 			} else {
 				// Otherwise reference the enum from the generated module which this is a part
 				// of.
@@ -430,12 +1637,9 @@ fn explore_descriptor(
 
 		Descriptor::InputShape(object) => {
 			let result = explore_object_shape_descriptor(
+				props,
 				StructElement::from_input_shape(&object.elements),
-				typedesc,
-				root_name,
-				is_input,
-				metadata,
-				is_macro,
+				None,
 				tokens,
 			)?;
 
@@ -476,35 +1680,70 @@ fn explore_enumeration_descriptor(
 	metadata: &GelxMetadata,
 	tokens: &mut TokenStream,
 	is_macro: bool,
-) -> TokenStream {
-	let Some(name) = &enumeration.name else {
-		return quote!(String);
+) -> GelxCoreResult<TokenStream> {
+	let Some(type_name) = &enumeration.name else {
+		return Ok(quote!(String));
 	};
 
-	let name = name.to_pascal_case().into_safe();
+	let name = type_name.to_pascal_case().into_safe();
 	let root_ident = format_ident!("{name}");
 
-	let enum_tokens = generate_enum(metadata, &enumeration.members, &name, is_macro);
+	let enum_tokens = generate_enum(
+		metadata,
+		&enumeration.members,
+		type_name,
+		&name,
+		None,
+		is_macro,
+	)?;
 
 	tokens.extend(enum_tokens);
-	quote!(#root_ident)
+	Ok(quote!(#root_ident))
+}
+
+/// Get the name of the object type at the provided position.
+fn object_type_name(typedesc: &Typedesc, type_pos: Option<TypePos>) -> Option<&str> {
+	match typedesc.get(type_pos?).ok()? {
+		Descriptor::Object(object) => object.name.as_deref(),
+		_ => None,
+	}
 }
 
 fn explore_object_shape_descriptor(
+	ExploreDescriptorProps {
+		typedesc,
+		is_input,
+		is_root,
+		root_name,
+		metadata,
+		is_macro,
+		types,
+		query_name,
+		shapes,
+		update_fields,
+		descriptor: shape_descriptor,
+		..
+	}: ExploreDescriptorProps,
 	elements: Vec<StructElement<'_>>,
-	typedesc: &Typedesc,
-	root_name: &str,
-	is_input: bool,
-	metadata: &GelxMetadata,
-	is_macro: bool,
+	object_name: Option<&str>,
 	tokens: &mut TokenStream,
 ) -> GelxCoreResult<Option<TokenStream>> {
 	let mut impl_named_args = vec![];
 	let mut struct_fields = vec![];
 	let root_ident = format_ident!("{root_name}");
 	let exports_ident = metadata.exports_alias_ident();
+	let find_object =
+		|name: Option<&str>| types.zip(name).and_then(|(t, n)| find_object_type(t, n));
+	let struct_doc = find_object(object_name).and_then(|object| object.annotations.doc());
+	let mut shape_elements = vec![];
+
 	for element in elements {
 		let descriptor = typedesc.get(element.type_pos()).ok();
+		shape_elements.push((
+			element.name(),
+			element.cardinality() as u8,
+			descriptor.map_or_else(Uuid::nil, |descriptor| *descriptor.id()),
+		));
 		let name = &element.name();
 		let safe_name = name.to_snake_case().into_safe();
 		let safe_name_ident = format_ident!("{safe_name}");
@@ -517,21 +1756,36 @@ fn explore_object_shape_descriptor(
 			.root_name(&sub_root_name)
 			.metadata(metadata)
 			.is_macro_bool(is_macro)
+			.types(types)
+			.query_name(query_name)
+			.shapes(shapes)
 			.build();
 		let output = explore_descriptor(sub_props, tokens)?;
 		let output_token = element.wrap(&output);
-		let serde_annotation = (&safe_name != name).then_some(metadata.features.wrap_annotation(
-			FeatureName::Serde,
-			&quote!(serde(rename = #name)),
+		let source_name = object_type_name(typedesc, element.source_type_pos()).or(object_name);
+		let field_doc = types
+			.zip(find_object(source_name))
+			.and_then(|(types, object)| pointer_doc(types, object, name));
+		let field_doc_tokens = doc_tokens(field_doc.as_deref());
+		let serde_annotation = metadata.serde_field_annotation(
+			name,
+			&safe_name,
+			element.cardinality() == Cardinality::AtMostOne,
 			is_macro,
-		));
+		);
 		let query_annotation = (&safe_name != name).then_some(metadata.features.wrap_annotation(
 			FeatureName::Query,
 			&quote!(gel(rename = #name)),
 			is_macro,
 		));
 
-		let builder_fields = {
+		let builder_fields = if let Some(field_enum) =
+			update_fields.filter(|_| is_root && is_input && *name == UPDATE_FIELDS_PARAM)
+		{
+			Some(quote! {
+				default, setter(transform = |fields: &[super::#field_enum]| super::#field_enum::names(fields))
+			})
+		} else {
 			match element.cardinality() {
 				Cardinality::AtMostOne => {
 					let fallback_ident = format_ident!("{safe_name_ident}_opt");
@@ -550,6 +1804,7 @@ fn explore_object_shape_descriptor(
 			));
 
 		struct_fields.push(quote! {
+			#field_doc_tokens
 			#serde_annotation
 			#query_annotation
 			#builder_annotation
@@ -561,8 +1816,9 @@ fn explore_object_shape_descriptor(
 		}
 	}
 
-	let impl_tokens = is_input.then_some(quote! {
-		impl #exports_ident::gel_protocol::query_arg::QueryArgs for #root_ident {
+	let impl_tokens = |ident: &Ident| {
+		is_input.then_some(quote! {
+		impl #exports_ident::gel_protocol::query_arg::QueryArgs for #ident {
 			fn encode(&self, encoder: &mut #exports_ident::gel_protocol::query_arg::Encoder) -> core::result::Result<(), #exports_ident::gel_errors::Error> {
 				let map = #exports_ident::gel_protocol::named_args! {
 					#(#impl_named_args)*
@@ -571,25 +1827,74 @@ fn explore_object_shape_descriptor(
 				map.encode(encoder)
 			}
 		}
-	});
+	})
+	};
 
-	let derive_macro_paths = metadata.struct_derive_macro_paths();
+	let rule_item = RuleItem {
+		kind: if is_input {
+			GelxRuleItem::Input
+		} else {
+			GelxRuleItem::Output
+		},
+		type_name: object_name,
+		query_name,
+	};
+	let mut derive_macro_paths = metadata.struct_derive_macro_paths();
+	derive_macro_paths.extend(metadata.rule_derive_macro_paths(&rule_item)?);
 	let struct_derive_tokens = metadata.features.get_struct_derive_features(
 		&exports_ident,
 		&derive_macro_paths,
 		is_input,
 		is_macro,
 	);
-	let struct_tokens = quote! {
-		#struct_derive_tokens
-		pub struct #root_ident {
-			#(#struct_fields)*
-		}
+	let rule_attributes = metadata.rule_attributes(&rule_item)?;
+	let serde_struct_annotation = metadata.serde_struct_annotation(is_macro);
+	let struct_doc_tokens = doc_tokens(struct_doc.as_deref());
+	let struct_tokens = |ident: &Ident| {
+		let impl_tokens = impl_tokens(ident);
+
+		quote! {
+			#struct_doc_tokens
+			#struct_derive_tokens
+			#serde_struct_annotation
+			#rule_attributes
+			pub struct #ident {
+				#(#struct_fields)*
+			}
 
-		#impl_tokens
+			#impl_tokens
+		}
 	};
 
-	tokens.extend(struct_tokens);
+	// Nested output shapes are shared between the queries when the registry is
+	// provided.
+	if let Some(shapes) = shapes.filter(|_| !is_root && !is_input) {
+		let shape_name = object_name.map_or_else(
+			|| "NamedTuple".to_string(),
+			|name| ModuleName::from(name).name.to_pascal_case().into_safe(),
+		);
+		// Version 1.0 of the protocol doesn't describe the object type of a shape.
+		let object_type = match shape_descriptor {
+			Some(Descriptor::ObjectShape(shape)) => {
+				shape.type_pos.and_then(|pos| typedesc.get(pos).ok())
+			}
+			_ => None,
+		};
+		let key = ShapeKey {
+			type_id: object_type
+				.or(shape_descriptor)
+				.map_or_else(Uuid::nil, |descriptor| *descriptor.id()),
+			elements: shape_elements,
+			attributes: quote!(#struct_derive_tokens #serde_struct_annotation #rule_attributes)
+				.to_string(),
+		};
+		let shape_ident = shapes.register(key, &shape_name, struct_tokens);
+		let module_ident = format_ident!("{SHAPES_MODULE_NAME}");
+
+		return Ok(Some(quote!(super::#module_ident::#shape_ident)));
+	}
+
+	tokens.extend(struct_tokens(&root_ident));
 
 	Ok(Some(quote!(#root_ident)))
 }
@@ -621,6 +1926,15 @@ impl<'a> StructElement<'a> {
 		}
 	}
 
+	/// The position of the object type which defines this element. Only
+	/// available for object shapes.
+	pub fn source_type_pos(&self) -> Option<TypePos> {
+		match self {
+			StructElement::Shape(shape) => shape.source_type_pos,
+			StructElement::InputShape(_) | StructElement::Tuple(_) => None,
+		}
+	}
+
 	pub fn type_pos(&self) -> TypePos {
 		match self {
 			StructElement::Shape(shape) => shape.type_pos,
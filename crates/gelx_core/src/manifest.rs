@@ -0,0 +1,235 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use gel_protocol::server_message::CommandDataDescription1;
+use heck::ToSnakeCase;
+use serde::Deserialize;
+use serde::Serialize;
+use sha2::Digest;
+use sha2::Sha256;
+use uuid::Uuid;
+
+use crate::DerivedDescriptors;
+use crate::GelxCoreResult;
+use crate::GelxMetadata;
+use crate::bulk_query;
+use crate::keyset_query;
+
+/// The version of the `queries.lock.json` format.
+pub const MANIFEST_VERSION: u32 = 1;
+
+/// The stable hash of a query which identifies it in the manifest. This is
+/// the hex encoded SHA-256 of the query text and must match
+/// `gelx::allowlist::query_hash`.
+pub fn query_hash(query: &str) -> String {
+	let mut hash = String::with_capacity(64);
+
+	for byte in Sha256::digest(query.as_bytes()) {
+		let _ = write!(hash, "{byte:02x}");
+	}
+
+	hash
+}
+
+/// The `queries.lock.json` manifest of every query string the generated code
+/// can run. It is used as an allowlist by the query guard of `gelx`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueryManifest {
+	pub version: u32,
+	/// The queries by the hash of their query text. Every module with the same
+	/// query text has an entry.
+	pub queries: BTreeMap<String, Vec<QueryManifestEntry>>,
+}
+
+impl Default for QueryManifest {
+	fn default() -> Self {
+		Self {
+			version: MANIFEST_VERSION,
+			queries: BTreeMap::new(),
+		}
+	}
+}
+
+impl QueryManifest {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Add the `QUERY` of a generated query module and the `derived` queries
+	/// of the module, i.e. the `QUERY_STREAM` and `QUERY_BULK`.
+	pub fn insert_query(
+		&mut self,
+		descriptor: &CommandDataDescription1,
+		name: &str,
+		query: &str,
+		derived: &DerivedDescriptors,
+		metadata: &GelxMetadata,
+	) {
+		let module = name.to_snake_case();
+		let capabilities = descriptor.capabilities.bits();
+		let derived_queries = [
+			derived.stream.as_ref().map(|stream| {
+				(
+					keyset_query(query),
+					metadata.query_stream_constant_ident(),
+					stream,
+				)
+			}),
+			derived.bulk.as_ref().and_then(|bulk| {
+				bulk_query(query).map(|query| (query, metadata.query_bulk_constant_ident(), bulk))
+			}),
+		];
+
+		for (derived_query, constant, derived_descriptor) in derived_queries.into_iter().flatten() {
+			self.insert_entry(
+				query_hash(&derived_query),
+				QueryManifestEntry {
+					module: module.clone(),
+					constant: constant.to_string(),
+					capabilities: derived_descriptor.capabilities.bits(),
+					fingerprint: QueryManifestFingerprint::new(derived_descriptor),
+				},
+			);
+		}
+
+		self.insert_entry(
+			query_hash(query),
+			QueryManifestEntry {
+				module,
+				constant: metadata.query_constant_ident().to_string(),
+				capabilities,
+				fingerprint: QueryManifestFingerprint::new(descriptor),
+			},
+		);
+	}
+
+	fn insert_entry(&mut self, hash: String, entry: QueryManifestEntry) {
+		self.queries.entry(hash).or_default().push(entry);
+	}
+
+	/// The manifest as pretty printed JSON with a trailing newline.
+	pub fn to_json(&self) -> GelxCoreResult<String> {
+		Ok(format!("{}\n", serde_json::to_string_pretty(self)?))
+	}
+}
+
+/// A query in the manifest.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueryManifestEntry {
+	/// The name of the generated query module.
+	pub module: String,
+	/// The name of the constant with the query text, e.g. `QUERY`.
+	pub constant: String,
+	/// The capabilities of the query reported by the database.
+	pub capabilities: u64,
+	/// The descriptor of the query.
+	pub fingerprint: QueryManifestFingerprint,
+}
+
+/// The type ids of the input and output descriptors and the cardinality of the
+/// result of a query.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueryManifestFingerprint {
+	pub input_id: Uuid,
+	pub output_id: Uuid,
+	pub cardinality: String,
+}
+
+impl QueryManifestFingerprint {
+	pub fn new(descriptor: &CommandDataDescription1) -> Self {
+		Self {
+			input_id: descriptor.input.id,
+			output_id: descriptor.output.id,
+			cardinality: format!("{:?}", descriptor.result_cardinality),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::collections::HashMap;
+
+	use bytes::Bytes;
+	use gel_protocol::common::Capabilities;
+	use gel_protocol::common::Cardinality;
+	use gel_protocol::common::RawTypedesc;
+	use gel_protocol::features::ProtocolVersion;
+
+	use super::*;
+	use crate::GelxBulkOptions;
+
+	fn descriptor() -> CommandDataDescription1 {
+		let typedesc = |id| {
+			RawTypedesc {
+				proto: ProtocolVersion::current(),
+				id,
+				data: Bytes::new(),
+			}
+		};
+
+		CommandDataDescription1 {
+			annotations: HashMap::default(),
+			capabilities: Capabilities::MODIFICATIONS,
+			result_cardinality: Cardinality::One,
+			input: typedesc(Uuid::from_u128(1)),
+			output: typedesc(Uuid::from_u128(2)),
+		}
+	}
+
+	#[test]
+	fn can_hash_queries() {
+		assert_eq!(
+			query_hash("select 1"),
+			"822ae07d4783158bc1912bb623e5107cc9002d519e1143a9c200ed6ee18b6d0f"
+		);
+		assert_ne!(query_hash("select 1"), query_hash("select 1 "));
+	}
+
+	#[test]
+	fn can_insert_derived_queries() {
+		let query = "insert User { name := <str>$name }";
+		let metadata = GelxMetadata::builder()
+			.bulk(GelxBulkOptions {
+				queries: vec!["insert_user".into()],
+				..Default::default()
+			})
+			.build();
+		let derived = DerivedDescriptors {
+			stream: Some(descriptor()),
+			bulk: Some(descriptor()),
+		};
+		let mut manifest = QueryManifest::new();
+		manifest.insert_query(&descriptor(), "insert_user", query, &derived, &metadata);
+
+		let entry = &manifest.queries[&query_hash(query)][0];
+		assert_eq!(entry.module, "insert_user");
+		assert_eq!(entry.constant, "QUERY");
+		assert_eq!(entry.capabilities, Capabilities::MODIFICATIONS.bits());
+		assert_eq!(entry.fingerprint.cardinality, "One");
+
+		let bulk_entry = &manifest.queries[&query_hash(&bulk_query(query).unwrap())][0];
+		assert_eq!(bulk_entry.constant, "QUERY_BULK");
+		assert_eq!(bulk_entry.fingerprint.input_id, Uuid::from_u128(1));
+
+		let stream_entry = &manifest.queries[&query_hash(&keyset_query(query))][0];
+		assert_eq!(stream_entry.constant, "QUERY_STREAM");
+		assert_eq!(manifest.queries.len(), 3);
+	}
+
+	#[test]
+	fn keeps_every_module_with_the_same_query() {
+		let query = "select User { name }";
+		let metadata = GelxMetadata::default();
+		let derived = DerivedDescriptors::default();
+		let mut manifest = QueryManifest::new();
+		manifest.insert_query(&descriptor(), "select_users", query, &derived, &metadata);
+		manifest.insert_query(&descriptor(), "list_users", query, &derived, &metadata);
+
+		let modules = manifest.queries[&query_hash(query)]
+			.iter()
+			.map(|entry| entry.module.as_str())
+			.collect::<Vec<_>>();
+		assert_eq!(modules, ["select_users", "list_users"]);
+		assert_eq!(manifest.queries.len(), 1);
+	}
+}
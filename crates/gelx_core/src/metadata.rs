@@ -5,6 +5,7 @@ use std::path::PathBuf;
 use std::str::FromStr;
 
 use base64::prelude::*;
+use gel_protocol::common::Capabilities;
 use gel_tokio::Builder;
 use gel_tokio::Config;
 use gel_tokio::InstanceName;
@@ -14,14 +15,18 @@ use quote::format_ident;
 use quote::quote;
 use serde::Deserialize;
 use serde::Serialize;
+use syn::Attribute;
 use syn::Ident;
+use syn::parse::Parser;
 use toml_edit::DocumentMut;
 use toml_edit::Item;
 use typed_builder::TypedBuilder;
 
 use crate::GelxCoreError;
 use crate::GelxCoreResult;
+use crate::MAX_QUERY_TAG_LENGTH;
 use crate::gelx_error;
+use crate::glob_match;
 
 /// The metadata for the `gelx` crate. This can either be specified in the
 /// `Cargo.toml` file or via CLI arguments.
@@ -36,6 +41,7 @@ use crate::gelx_error;
 	default,
 	setter(into, strip_option(ignore_invalid, fallback_suffix = "_opt"))
 ))]
+#[allow(clippy::struct_excessive_bools)]
 pub struct GelxMetadata {
 	#[builder(default = default_queries_path())]
 	#[serde(default = "default_queries_path")]
@@ -43,9 +49,44 @@ pub struct GelxMetadata {
 	#[builder(default = GelxFeatures::default())]
 	#[serde(default = "GelxFeatures::default")]
 	pub features: GelxFeatures,
+	/// Customize the `serde` annotations of the generated code. These are only
+	/// applied when the `serde` feature is enabled.
+	#[builder(default)]
+	#[serde(default)]
+	pub serde: GelxSerdeOptions,
+	/// The options for the spans of the generated queries. These are only
+	/// applied when the `tracing` feature is enabled.
+	#[builder(default)]
+	#[serde(default)]
+	pub tracing: GelxTracingOptions,
+	/// The queries which get the `query_bulk` functions for running the query
+	/// with many inputs in a single round trip.
+	#[builder(default)]
+	#[serde(default)]
+	pub bulk: GelxBulkOptions,
+	/// The names of the query parameters which are detected by the generated
+	/// `paginate` functions.
+	#[builder(default)]
+	#[serde(default)]
+	pub pagination: GelxPaginationOptions,
+	/// Additional derives and attributes for the generated items matched by
+	/// each rule.
+	#[builder(default)]
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub rules: Vec<GelxRule>,
+	/// The default options of the `query` and `query_with` functions for the
+	/// queries matched by each rule.
+	#[builder(default)]
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub query_options: Vec<GelxQueryOptions>,
 	#[builder(default = default_output_path())]
 	#[serde(default = "default_output_path")]
 	pub output_path: PathBuf,
+	/// The location of the `queries.lock.json` manifest of the generated
+	/// queries which is written by the `gelx` CLI.
+	#[builder(default = default_manifest_path())]
+	#[serde(default = "default_manifest_path")]
+	pub manifest_path: PathBuf,
 	#[builder(default = default_input_struct_name())]
 	#[serde(default = "default_input_struct_name")]
 	pub input_struct_name: String,
@@ -58,6 +99,9 @@ pub struct GelxMetadata {
 	#[builder(default = default_transaction_function_name())]
 	#[serde(default = "default_transaction_function_name")]
 	pub transaction_function_name: String,
+	#[builder(default = default_execute_function_name())]
+	#[serde(default = "default_execute_function_name")]
+	pub execute_function_name: String,
 	#[builder(default = default_query_constant_name())]
 	#[serde(default = "default_query_constant_name")]
 	pub query_constant_name: String,
@@ -73,6 +117,47 @@ pub struct GelxMetadata {
 	#[builder(default = default_enum_derive_macros())]
 	#[serde(default = "default_enum_derive_macros")]
 	pub enum_derive_macros: Vec<String>,
+	/// Validate the input against the schema constraints before running the
+	/// generated query and transaction functions.
+	#[builder(default)]
+	#[serde(default)]
+	pub validate_input: bool,
+	/// Generate the identical nested output shapes of the queries once in a
+	/// shared `shapes` module. Only supported by the `gelx` CLI.
+	#[builder(default)]
+	#[serde(default)]
+	pub dedupe_shapes: bool,
+	/// The capabilities the queries are allowed to use. The queries fail to
+	/// compile when they use any other capability, e.g. `["modifications"]`
+	/// prevents DDL and config statements. All capabilities are allowed by
+	/// default.
+	#[builder(default)]
+	#[serde(default)]
+	pub allowed_capabilities: Option<Vec<QueryCapability>>,
+	/// Generate a `query_json` function for every query which returns the
+	/// JSON rendered by the database instead of decoding the output.
+	#[builder(default)]
+	#[serde(default)]
+	pub json_functions: bool,
+	/// The prefix of the query tags which attribute the statistics of the
+	/// generated queries in `sys::QueryStats`, e.g. `api/`. The tag is the
+	/// prefix followed by the name of the query module.
+	#[builder(default)]
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub query_tag_prefix: Option<String>,
+	/// Patterns matched against the fully qualified names of the object types,
+	/// e.g. `default::*`, which get an upsert query for each of their exclusive
+	/// constraints. Only supported by the `gelx` CLI.
+	#[builder(default)]
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub upserts: Vec<String>,
+	/// Patterns matched against the name of the query modules, e.g.
+	/// `select_*`, which get the `query_stream` function for streaming the
+	/// results in pages ordered by `id`. The queries can't have a top-level
+	/// `order by`, `limit` or `offset` clause.
+	#[builder(default)]
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub stream_queries: Vec<String>,
 	#[builder(default)]
 	#[serde(default)]
 	pub gel_config_path: Option<PathBuf>,
@@ -151,6 +236,19 @@ impl GelxMetadata {
 		Ok(metadata)
 	}
 
+	/// The capabilities allowed when compiling the queries.
+	pub fn allowed_capabilities_flags(&self) -> Capabilities {
+		self.allowed_capabilities
+			.as_ref()
+			.map_or(Capabilities::ALL, |capabilities| {
+				capabilities
+					.iter()
+					.fold(Capabilities::empty(), |flags, capability| {
+						flags | Capabilities::from(*capability)
+					})
+			})
+	}
+
 	/// Create a `Config` instance from the `GelxMetadata`.
 	pub fn gel_config(&self) -> GelxCoreResult<Config> {
 		let mut builder = Builder::new();
@@ -177,6 +275,35 @@ impl GelxMetadata {
 		Ok(config)
 	}
 
+	/// The directory containing the `.gel` schema files.
+	///
+	/// This is the `schema-dir` of the `gel.toml` file, which defaults to
+	/// `dbschema`. The `gel.toml` file is either the configured
+	/// `gel_config_path` or the first one found in the ancestors of the `root`.
+	pub fn schema_dir(&self, root: impl AsRef<Path>) -> Option<PathBuf> {
+		let root = root.as_ref();
+		let config_path = match self.gel_config_path {
+			Some(ref path) => root.join(path),
+			None => {
+				root.ancestors()
+					.map(|dir| dir.join("gel.toml"))
+					.find(|path| path.is_file())?
+			}
+		};
+		let schema_dir = fs::read_to_string(&config_path)
+			.ok()
+			.and_then(|content| content.parse::<DocumentMut>().ok())
+			.and_then(|doc| {
+				doc.get("project")?
+					.get("schema-dir")?
+					.as_str()
+					.map(PathBuf::from)
+			})
+			.unwrap_or_else(|| PathBuf::from("dbschema"));
+
+		Some(config_path.parent()?.join(schema_dir))
+	}
+
 	pub fn input_struct_ident(&self) -> Ident {
 		format_ident!("{}", self.input_struct_name)
 	}
@@ -193,10 +320,92 @@ impl GelxMetadata {
 		format_ident!("{}", self.query_function_name)
 	}
 
+	pub fn query_stream_function_ident(&self) -> Ident {
+		format_ident!("{}_stream", self.query_function_name)
+	}
+
+	pub fn query_with_function_ident(&self) -> Ident {
+		format_ident!("{}_with", self.query_function_name)
+	}
+
+	pub fn query_blocking_function_ident(&self) -> Ident {
+		format_ident!("{}_blocking", self.query_function_name)
+	}
+
+	pub fn transaction_blocking_function_ident(&self) -> Ident {
+		format_ident!("{}_blocking", self.transaction_function_name)
+	}
+
+	pub fn query_json_function_ident(&self) -> Ident {
+		format_ident!("{}_json", self.query_function_name)
+	}
+
+	pub fn query_bulk_function_ident(&self) -> Ident {
+		format_ident!("{}_bulk", self.query_function_name)
+	}
+
+	pub fn execute_bulk_function_ident(&self) -> Ident {
+		format_ident!("{}_bulk", self.execute_function_name)
+	}
+
+	pub fn paginate_function_ident(&self) -> Ident {
+		format_ident!("paginate")
+	}
+
+	pub fn paginate_from_function_ident(&self) -> Ident {
+		format_ident!("paginate_from")
+	}
+
+	pub fn paginate_stream_function_ident(&self) -> Ident {
+		format_ident!("paginate_stream")
+	}
+
+	pub fn query_tag_constant_ident(&self) -> Ident {
+		format_ident!("{}_TAG", self.query_constant_name)
+	}
+
+	pub fn query_hash_constant_ident(&self) -> Ident {
+		format_ident!("{}_HASH", self.query_constant_name)
+	}
+
+	/// The query tag of the query module with the configured prefix.
+	pub fn query_tag(&self, module_name: &str) -> GelxCoreResult<String> {
+		let tag = format!(
+			"{}{module_name}",
+			self.query_tag_prefix.as_deref().unwrap_or_default()
+		);
+
+		if tag.starts_with("gel/") || tag.starts_with("edgedb/") {
+			return Err(gelx_error!(
+				"The query tag `{tag}` uses a prefix which is reserved by gel."
+			));
+		}
+
+		if tag.len() > MAX_QUERY_TAG_LENGTH {
+			return Err(gelx_error!(
+				"The query tag `{tag}` is longer than {MAX_QUERY_TAG_LENGTH} bytes."
+			));
+		}
+
+		Ok(tag)
+	}
+
+	pub fn query_stream_constant_ident(&self) -> Ident {
+		format_ident!("{}_STREAM", self.query_constant_name)
+	}
+
+	pub fn query_bulk_constant_ident(&self) -> Ident {
+		format_ident!("{}_BULK", self.query_constant_name)
+	}
+
 	pub fn transaction_function_ident(&self) -> Ident {
 		format_ident!("{}", self.transaction_function_name)
 	}
 
+	pub fn execute_function_ident(&self) -> Ident {
+		format_ident!("{}", self.execute_function_name)
+	}
+
 	pub fn exports_alias_ident(&self) -> Ident {
 		format_ident!("{}", self.exports_alias)
 	}
@@ -222,6 +431,181 @@ impl GelxMetadata {
 			.collect()
 	}
 
+	/// The derive macros added by the rules which match the `item`.
+	pub(crate) fn rule_derive_macro_paths(
+		&self,
+		item: &RuleItem<'_>,
+	) -> GelxCoreResult<Vec<syn::Path>> {
+		self.matching_rules(item)
+			.flat_map(|(index, rule)| rule.derives.iter().map(move |derive| (index, derive)))
+			.map(|(index, derive)| {
+				syn::parse_str::<syn::Path>(derive).map_err(|error| {
+					gelx_error!("Invalid derive `{derive}` in `rules[{index}].derives`: {error}")
+				})
+			})
+			.collect()
+	}
+
+	/// The raw attributes added by the rules which match the `item`.
+	pub(crate) fn rule_attributes(&self, item: &RuleItem<'_>) -> GelxCoreResult<TokenStream> {
+		self.matching_rules(item)
+			.flat_map(|(index, rule)| {
+				rule.attributes
+					.iter()
+					.map(move |attribute| (index, attribute))
+			})
+			.map(|(index, attribute)| {
+				let attribute = attribute.trim();
+				let tokens = if attribute.starts_with('#') {
+					Attribute::parse_outer
+						.parse_str(attribute)
+						.map(|attributes| quote!(#(#attributes)*))
+				} else {
+					syn::parse_str::<syn::Meta>(attribute).map(|meta| quote!(#[#meta]))
+				};
+
+				tokens.map_err(|error| {
+					gelx_error!(
+						"Invalid attribute `{attribute}` in `rules[{index}].attributes`: {error}"
+					)
+				})
+			})
+			.collect()
+	}
+
+	/// Whether upsert queries are generated for the object type with the
+	/// provided fully qualified name.
+	pub fn is_upsert_type(&self, type_name: &str) -> bool {
+		self.upserts
+			.iter()
+			.any(|pattern| glob_match(pattern, type_name))
+	}
+
+	/// Whether the `query_stream` function is generated for the query with the
+	/// provided module name.
+	pub fn is_stream_query(&self, query_name: &str) -> bool {
+		self.stream_queries
+			.iter()
+			.any(|pattern| glob_match(pattern, query_name))
+	}
+
+	/// Whether the `query_bulk` functions are generated for the query with the
+	/// provided module name.
+	pub fn is_bulk_query(&self, query_name: &str) -> bool {
+		self.bulk
+			.queries
+			.iter()
+			.any(|pattern| glob_match(pattern, query_name))
+	}
+
+	/// The default options of the query with the provided module name. The
+	/// options of later rules take precedence over earlier rules.
+	pub fn query_options_for(&self, query_name: &str) -> GelxQueryOptions {
+		self.query_options
+			.iter()
+			.filter(|options| {
+				options
+					.queries
+					.iter()
+					.any(|pattern| glob_match(pattern, query_name))
+			})
+			.fold(GelxQueryOptions::default(), |defaults, options| {
+				GelxQueryOptions {
+					queries: vec![],
+					timeout_ms: options.timeout_ms.or(defaults.timeout_ms),
+					retry_attempts: options.retry_attempts.or(defaults.retry_attempts),
+					read_only: options.read_only.or(defaults.read_only),
+				}
+			})
+	}
+
+	/// The rules which match the `item` with their index in `rules`.
+	fn matching_rules<'a>(
+		&'a self,
+		item: &'a RuleItem<'_>,
+	) -> impl Iterator<Item = (usize, &'a GelxRule)> {
+		self.rules
+			.iter()
+			.enumerate()
+			.filter(|(_, rule)| rule.matches(item))
+	}
+
+	/// The container level `serde` annotation for generated structs.
+	pub(crate) fn serde_struct_annotation(&self, is_macro: bool) -> TokenStream {
+		let mut args = vec![];
+
+		if let Some(rule) = self.serde.struct_rename_all {
+			let rule = rule.as_ref();
+			args.push(quote!(rename_all = #rule));
+		}
+
+		if self.serde.deny_unknown_fields {
+			args.push(quote!(deny_unknown_fields));
+		}
+
+		self.serde_annotation(&args, is_macro)
+	}
+
+	/// The container level `serde` annotation for generated enums.
+	pub(crate) fn serde_enum_annotation(&self, is_macro: bool) -> TokenStream {
+		let mut args = vec![];
+
+		if let Some(rule) = self.serde.enum_rename_all {
+			let rule = rule.as_ref();
+			args.push(quote!(rename_all = #rule));
+		}
+
+		self.serde_annotation(&args, is_macro)
+	}
+
+	/// The `serde` annotation for a struct field.
+	///
+	/// Fields are renamed to the original name when it differs from the rust
+	/// field name, unless a `rename_all` strategy has been configured for
+	/// structs.
+	pub(crate) fn serde_field_annotation(
+		&self,
+		name: &str,
+		safe_name: &str,
+		is_optional: bool,
+		is_macro: bool,
+	) -> TokenStream {
+		let mut args = vec![];
+
+		if safe_name != name && self.serde.struct_rename_all.is_none() {
+			args.push(quote!(rename = #name));
+		}
+
+		if is_optional && self.serde.skip_serializing_none {
+			args.push(quote!(skip_serializing_if = "Option::is_none"));
+		}
+
+		self.serde_annotation(&args, is_macro)
+	}
+
+	/// The `serde` annotation for an enum variant.
+	pub(crate) fn serde_variant_annotation(
+		&self,
+		value: &str,
+		pascal_value: &str,
+		is_macro: bool,
+	) -> TokenStream {
+		if value == pascal_value || self.serde.enum_rename_all.is_some() {
+			return TokenStream::new();
+		}
+
+		self.serde_annotation(&[quote!(rename = #value)], is_macro)
+	}
+
+	fn serde_annotation(&self, args: &[TokenStream], is_macro: bool) -> TokenStream {
+		if args.is_empty() {
+			return TokenStream::new();
+		}
+
+		self.features
+			.wrap_annotation(FeatureName::Serde, &quote!(serde(#(#args),*)), is_macro)
+	}
+
 	pub fn try_to_string(&self) -> GelxCoreResult<String> {
 		toml::to_string(self).map_err(Into::into)
 	}
@@ -248,6 +632,10 @@ fn default_output_path() -> PathBuf {
 	PathBuf::from("src/db")
 }
 
+fn default_manifest_path() -> PathBuf {
+	PathBuf::from("queries.lock.json")
+}
+
 fn default_input_struct_name() -> String {
 	"Input".to_string()
 }
@@ -264,6 +652,10 @@ fn default_transaction_function_name() -> String {
 	"transaction".to_string()
 }
 
+fn default_execute_function_name() -> String {
+	"execute".to_string()
+}
+
 fn default_query_constant_name() -> String {
 	"QUERY".to_string()
 }
@@ -276,6 +668,22 @@ fn default_struct_derive_macros() -> Vec<String> {
 	vec!["::std::fmt::Debug".into(), "::core::clone::Clone".into()]
 }
 
+fn default_bulk_chunk_size() -> usize {
+	1000
+}
+
+fn default_limit_param() -> String {
+	"limit".to_string()
+}
+
+fn default_offset_param() -> String {
+	"offset".to_string()
+}
+
+fn default_cursor_param() -> String {
+	"cursor".to_string()
+}
+
 fn default_enum_derive_macros() -> Vec<String> {
 	vec![
 		"::std::fmt::Debug".into(),
@@ -321,6 +729,10 @@ impl Default for GelxFeatureOptions {
 }
 
 impl GelxFeatureOptions {
+	pub fn disabled() -> Self {
+		GelxFeatureOptions::Enabled(false)
+	}
+
 	pub fn is_enabled(&self) -> bool {
 		match self {
 			GelxFeatureOptions::Alias(_) => true,
@@ -336,6 +748,315 @@ impl GelxFeatureOptions {
 	}
 }
 
+/// Additional derives and attributes for a subset of the generated items.
+///
+/// Every filter which is provided must match for the rule to apply. Patterns
+/// support the `*` and `?` wildcards.
+///
+/// ```toml
+/// [[package.metadata.gelx.rules]]
+/// types = ["default::Role"]
+/// derives = ["::core::hash::Hash"]
+/// attributes = ["#[non_exhaustive]"]
+///
+/// [[package.metadata.gelx.rules]]
+/// queries = ["api_*"]
+/// items = ["output"]
+/// derives = ["::utoipa::ToSchema"]
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize, Default, TypedBuilder, PartialEq)]
+#[builder(field_defaults(default, setter(into)))]
+pub struct GelxRule {
+	/// Patterns matched against the fully qualified name of the schema type,
+	/// e.g. `default::Role` or `default::*`.
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub types: Vec<String>,
+	/// Patterns matched against the name of the query module, e.g. `select_*`.
+	/// Only the structs generated for queries can match these patterns.
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub queries: Vec<String>,
+	/// The kinds of items the rule applies to. Defaults to all items.
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub items: Vec<GelxRuleItem>,
+	/// The additional derive macros.
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub derives: Vec<String>,
+	/// The additional raw attributes, e.g. `#[non_exhaustive]`.
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub attributes: Vec<String>,
+}
+
+impl GelxRule {
+	fn matches(&self, item: &RuleItem<'_>) -> bool {
+		let matches_any = |patterns: &[String], value: Option<&str>| {
+			patterns.is_empty()
+				|| value
+					.is_some_and(|value| patterns.iter().any(|pattern| glob_match(pattern, value)))
+		};
+
+		(self.items.is_empty() || self.items.contains(&item.kind))
+			&& matches_any(&self.types, item.type_name)
+			&& matches_any(&self.queries, item.query_name)
+	}
+}
+
+/// The kinds of generated items which can be targeted by a [`GelxRule`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GelxRuleItem {
+	/// The input structs of queries.
+	Input,
+	/// The output structs of queries, including the nested shapes.
+	Output,
+	/// The wrapper structs of custom scalar types.
+	Scalar,
+	/// The enums of the schema.
+	Enum,
+}
+
+/// A generated item which is checked against the [`GelxRule`]s.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RuleItem<'a> {
+	pub kind: GelxRuleItem,
+	pub type_name: Option<&'a str>,
+	pub query_name: Option<&'a str>,
+}
+
+/// The default options of the generated `query` and `query_with` functions
+/// for the queries which match any of the patterns. The options passed to
+/// `query_with` take precedence over these defaults. The `transaction` and
+/// `execute` functions run inside a transaction or executor which is
+/// configured by the caller, so the defaults don't apply to them.
+///
+/// ```toml
+/// [[package.metadata.gelx.query_options]]
+/// queries = ["report_*"]
+/// timeout_ms = 30000
+///
+/// [[package.metadata.gelx.query_options]]
+/// queries = ["insert_*", "update_*"]
+/// retry_attempts = 1
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize, Default, TypedBuilder, PartialEq)]
+#[builder(field_defaults(default, setter(into)))]
+pub struct GelxQueryOptions {
+	/// Patterns matched against the name of the query module, e.g. `report_*`.
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub queries: Vec<String>,
+	/// The timeout of the query in milliseconds.
+	#[builder(setter(strip_option))]
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub timeout_ms: Option<u64>,
+	/// The maximum number of attempts to run the query. Use `1` to disable
+	/// retries for mutations which aren't idempotent.
+	#[builder(setter(strip_option))]
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub retry_attempts: Option<u32>,
+	/// Run the query in a read only transaction.
+	#[builder(setter(strip_option))]
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub read_only: Option<bool>,
+}
+
+/// The options of the generated `query_bulk` functions.
+///
+/// The bulk query runs the original query once for every item of a JSON
+/// array. The inputs are sent in chunks of `chunk_size` items which run in a
+/// single transaction.
+///
+/// ```toml
+/// [package.metadata.gelx.bulk]
+/// queries = ["insert_*"]
+/// chunk_size = 500
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize, TypedBuilder, PartialEq)]
+#[builder(field_defaults(default, setter(into)))]
+pub struct GelxBulkOptions {
+	/// Patterns matched against the name of the query module, e.g. `insert_*`.
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub queries: Vec<String>,
+	/// The maximum number of items sent with each query.
+	#[builder(default = default_bulk_chunk_size())]
+	#[serde(default = "default_bulk_chunk_size")]
+	pub chunk_size: usize,
+}
+
+impl Default for GelxBulkOptions {
+	fn default() -> Self {
+		Self {
+			queries: vec![],
+			chunk_size: default_bulk_chunk_size(),
+		}
+	}
+}
+
+/// The names of the query parameters which are used for pagination.
+///
+/// Queries which return a set and take an `int64` limit parameter get the
+/// `paginate`, `paginate_from` and `paginate_stream` functions when they also
+/// take an `int64` offset parameter or an optional `uuid` cursor parameter.
+///
+/// ```toml
+/// [package.metadata.gelx.pagination]
+/// limit_param = "page_size"
+/// offset_param = "skip"
+/// cursor_param = "after"
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize, TypedBuilder, PartialEq)]
+#[builder(field_defaults(setter(into)))]
+pub struct GelxPaginationOptions {
+	/// The parameter with the maximum number of results of a page.
+	#[builder(default = default_limit_param())]
+	#[serde(default = "default_limit_param")]
+	pub limit_param: String,
+	/// The parameter with the number of results which are skipped.
+	#[builder(default = default_offset_param())]
+	#[serde(default = "default_offset_param")]
+	pub offset_param: String,
+	/// The parameter with the `id` of the last result of the previous page.
+	#[builder(default = default_cursor_param())]
+	#[serde(default = "default_cursor_param")]
+	pub cursor_param: String,
+}
+
+impl Default for GelxPaginationOptions {
+	fn default() -> Self {
+		Self {
+			limit_param: default_limit_param(),
+			offset_param: default_offset_param(),
+			cursor_param: default_cursor_param(),
+		}
+	}
+}
+
+/// The `tracing` options for the generated code.
+///
+/// The values of the query arguments are left out of the spans since they
+/// may contain sensitive data. Fields which are safe to record must be listed
+/// by the name of the query parameter.
+///
+/// ```toml
+/// [package.metadata.gelx.tracing]
+/// record_fields = ["slug", "provider"]
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize, Default, TypedBuilder, PartialEq)]
+#[builder(field_defaults(default, setter(into)))]
+pub struct GelxTracingOptions {
+	/// The query parameters which are recorded on the span with their `Debug`
+	/// representation.
+	#[serde(default)]
+	pub record_fields: Vec<String>,
+}
+
+/// The `serde` options for the generated code.
+///
+/// ```toml
+/// [package.metadata.gelx.serde]
+/// struct_rename_all = "camelCase"
+/// enum_rename_all = "SCREAMING_SNAKE_CASE"
+/// skip_serializing_none = true
+/// deny_unknown_fields = true
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize, Default, TypedBuilder, PartialEq)]
+#[builder(field_defaults(default, setter(into)))]
+pub struct GelxSerdeOptions {
+	/// The `rename_all` strategy for the fields of generated structs.
+	#[builder(setter(strip_option))]
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub struct_rename_all: Option<SerdeRenameRule>,
+	/// The `rename_all` strategy for the variants of generated enums.
+	#[builder(setter(strip_option))]
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub enum_rename_all: Option<SerdeRenameRule>,
+	/// Skip serializing optional struct fields when they are `None`.
+	#[serde(default)]
+	pub skip_serializing_none: bool,
+	/// Reject unknown fields when deserializing generated structs.
+	#[serde(default)]
+	pub deny_unknown_fields: bool,
+}
+
+/// The case conversion strategies supported by the `serde` `rename_all`
+/// attribute.
+#[derive(
+	Debug,
+	Clone,
+	Copy,
+	PartialEq,
+	Eq,
+	Serialize,
+	Deserialize,
+	strum::AsRefStr,
+	strum::Display,
+	strum::EnumString,
+)]
+pub enum SerdeRenameRule {
+	#[serde(rename = "lowercase")]
+	#[strum(serialize = "lowercase")]
+	LowerCase,
+	#[serde(rename = "UPPERCASE")]
+	#[strum(serialize = "UPPERCASE")]
+	UpperCase,
+	#[serde(rename = "PascalCase")]
+	#[strum(serialize = "PascalCase")]
+	PascalCase,
+	#[serde(rename = "camelCase")]
+	#[strum(serialize = "camelCase")]
+	CamelCase,
+	#[serde(rename = "snake_case")]
+	#[strum(serialize = "snake_case")]
+	SnakeCase,
+	#[serde(rename = "SCREAMING_SNAKE_CASE")]
+	#[strum(serialize = "SCREAMING_SNAKE_CASE")]
+	ScreamingSnakeCase,
+	#[serde(rename = "kebab-case")]
+	#[strum(serialize = "kebab-case")]
+	KebabCase,
+	#[serde(rename = "SCREAMING-KEBAB-CASE")]
+	#[strum(serialize = "SCREAMING-KEBAB-CASE")]
+	ScreamingKebabCase,
+}
+
+/// A capability which a query can require from the database.
+#[derive(
+	Debug,
+	Clone,
+	Copy,
+	PartialEq,
+	Eq,
+	Serialize,
+	Deserialize,
+	strum::AsRefStr,
+	strum::Display,
+	strum::EnumString,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum QueryCapability {
+	/// Insert, update and delete statements.
+	Modifications,
+	/// `configure session` statements.
+	SessionConfig,
+	/// Transaction control statements.
+	Transaction,
+	/// Schema changes.
+	Ddl,
+	/// `configure current branch` and `configure instance` statements.
+	PersistentConfig,
+}
+
+impl From<QueryCapability> for Capabilities {
+	fn from(value: QueryCapability) -> Self {
+		match value {
+			QueryCapability::Modifications => Capabilities::MODIFICATIONS,
+			QueryCapability::SessionConfig => Capabilities::SESSION_CONFIG,
+			QueryCapability::Transaction => Capabilities::TRANSACTION,
+			QueryCapability::Ddl => Capabilities::DDL,
+			QueryCapability::PersistentConfig => Capabilities::PERSISTENT_CONFIG,
+		}
+	}
+}
+
 /// The name of a feature.
 #[derive(
 	Debug,
@@ -356,6 +1077,11 @@ pub enum FeatureName {
 	Builder,
 	Query,
 	Strum,
+	Tracing,
+	Blocking,
+	Verify,
+	Allowlist,
+	Regex,
 }
 
 impl FeatureName {
@@ -380,11 +1106,36 @@ impl FeatureName {
 			return true;
 		}
 
+		#[cfg(feature = "tracing")]
+		if self == &FeatureName::Tracing {
+			return true;
+		}
+
+		#[cfg(feature = "blocking")]
+		if self == &FeatureName::Blocking {
+			return true;
+		}
+
+		#[cfg(feature = "verify")]
+		if self == &FeatureName::Verify {
+			return true;
+		}
+
+		#[cfg(feature = "allowlist")]
+		if self == &FeatureName::Allowlist {
+			return true;
+		}
+
+		#[cfg(feature = "regex")]
+		if self == &FeatureName::Regex {
+			return true;
+		}
+
 		false
 	}
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize, Default, TypedBuilder, PartialEq)]
+#[derive(Clone, Debug, Serialize, Deserialize, TypedBuilder, PartialEq)]
 #[builder(field_defaults(default, setter(into)))]
 pub struct GelxFeatures {
 	#[serde(default)]
@@ -395,6 +1146,43 @@ pub struct GelxFeatures {
 	pub builder: GelxFeatureOptions,
 	#[serde(default)]
 	pub serde: GelxFeatureOptions,
+	/// Wrap the generated queries in `tracing` spans. Unlike the other features
+	/// this is disabled by default since it requires the `tracing` feature of
+	/// `gelx`.
+	#[builder(default = GelxFeatureOptions::Enabled(false))]
+	#[serde(default = "GelxFeatureOptions::disabled")]
+	pub tracing: GelxFeatureOptions,
+	/// Generate the `query_blocking` and `transaction_blocking` functions which
+	/// run the queries with a `BlockingClient`. This is disabled by default
+	/// since it requires the `blocking` feature of `gelx`.
+	#[builder(default = GelxFeatureOptions::Enabled(false))]
+	#[serde(default = "GelxFeatureOptions::disabled")]
+	pub blocking: GelxFeatureOptions,
+	/// Register a `FINGERPRINT` of the query descriptor for
+	/// `gelx::verify_all`. This is disabled by default since it requires the
+	/// `verify` feature of `gelx`.
+	#[builder(default = GelxFeatureOptions::Enabled(false))]
+	#[serde(default = "GelxFeatureOptions::disabled")]
+	pub verify: GelxFeatureOptions,
+	/// Check the `QUERY_HASH` against the allowlist installed with
+	/// `gelx::allowlist::install` in every generated function which takes a
+	/// `Client`. This is disabled by default since it requires the `allowlist`
+	/// feature of `gelx`.
+	#[builder(default = GelxFeatureOptions::Enabled(false))]
+	#[serde(default = "GelxFeatureOptions::disabled")]
+	pub allowlist: GelxFeatureOptions,
+	/// Check the `std::regexp` constraints in the generated `Validate`
+	/// implementations. This is disabled by default since it requires the
+	/// `regex` feature of `gelx`.
+	#[builder(default = GelxFeatureOptions::Enabled(false))]
+	#[serde(default = "GelxFeatureOptions::disabled")]
+	pub regex: GelxFeatureOptions,
+}
+
+impl Default for GelxFeatures {
+	fn default() -> Self {
+		Self::builder().build()
+	}
 }
 
 impl GelxFeatures {
@@ -458,6 +1246,12 @@ impl GelxFeatures {
 						is_macro,
 					));
 				}
+				// Tracing, blocking, verify, allowlist and regex don't derive anything.
+				FeatureName::Tracing
+				| FeatureName::Blocking
+				| FeatureName::Verify
+				| FeatureName::Allowlist
+				| FeatureName::Regex => {}
 			}
 		}
 
@@ -519,6 +1313,17 @@ impl GelxFeatures {
 			}
 			FeatureName::Query => self.query.is_enabled() && (!is_macro || feature.is_enabled()),
 			FeatureName::Strum => self.strum.is_enabled() && (!is_macro || feature.is_enabled()),
+			FeatureName::Tracing => {
+				self.tracing.is_enabled() && (!is_macro || feature.is_enabled())
+			}
+			FeatureName::Blocking => {
+				self.blocking.is_enabled() && (!is_macro || feature.is_enabled())
+			}
+			FeatureName::Verify => self.verify.is_enabled() && (!is_macro || feature.is_enabled()),
+			FeatureName::Allowlist => {
+				self.allowlist.is_enabled() && (!is_macro || feature.is_enabled())
+			}
+			FeatureName::Regex => self.regex.is_enabled() && (!is_macro || feature.is_enabled()),
 		}
 	}
 
@@ -528,6 +1333,11 @@ impl GelxFeatures {
 			FeatureName::Builder => self.builder.alias(),
 			FeatureName::Query => self.query.alias(),
 			FeatureName::Strum => self.strum.alias(),
+			FeatureName::Tracing => self.tracing.alias(),
+			FeatureName::Blocking => self.blocking.alias(),
+			FeatureName::Verify => self.verify.alias(),
+			FeatureName::Allowlist => self.allowlist.alias(),
+			FeatureName::Regex => self.regex.alias(),
 		}
 	}
 
@@ -618,4 +1428,272 @@ mod tests {
 
 		assert_eq!(expected, metadata);
 	}
+
+	#[test]
+	fn test_serde_annotations_respect_alias() {
+		let metadata = GelxMetadata::builder()
+			.features(
+				GelxFeatures::builder()
+					.serde(GelxFeatureOptions::from("ssr"))
+					.build(),
+			)
+			.serde(
+				GelxSerdeOptions::builder()
+					.struct_rename_all(SerdeRenameRule::CamelCase)
+					.skip_serializing_none(true)
+					.deny_unknown_fields(true)
+					.build(),
+			)
+			.build();
+
+		assert_eq!(
+			metadata.serde_struct_annotation(false).to_string(),
+			quote!(#[cfg_attr(feature = "ssr", serde(rename_all = "camelCase", deny_unknown_fields))])
+				.to_string()
+		);
+		assert_eq!(
+			metadata
+				.serde_field_annotation("created_at", "created_at", true, false)
+				.to_string(),
+			quote!(#[cfg_attr(feature = "ssr", serde(skip_serializing_if = "Option::is_none"))])
+				.to_string()
+		);
+		assert!(metadata.serde_enum_annotation(false).is_empty());
+		assert!(
+			metadata
+				.serde_field_annotation("type", "r#type", false, false)
+				.is_empty()
+		);
+	}
+
+	#[test]
+	fn test_schema_dir() {
+		let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+		let schema_dir = GelxMetadata::default().schema_dir(&root).unwrap();
+
+		let workspace_root = root.ancestors().nth(2).unwrap();
+
+		assert_eq!(schema_dir, workspace_root.join("dbschema"));
+	}
+
+	#[test]
+	fn test_rules() {
+		let metadata: GelxMetadata = toml::from_str(
+			r#"
+			[[rules]]
+			types = ["default::*"]
+			items = ["enum"]
+			derives = ["::core::hash::Hash"]
+			attributes = ["non_exhaustive"]
+
+			[[rules]]
+			queries = ["api_*"]
+			items = ["output"]
+			derives = ["::utoipa::ToSchema"]
+			"#,
+		)
+		.unwrap();
+		let role = RuleItem {
+			kind: GelxRuleItem::Enum,
+			type_name: Some("default::Role"),
+			query_name: None,
+		};
+		let output = RuleItem {
+			kind: GelxRuleItem::Output,
+			type_name: Some("default::User"),
+			query_name: Some("api_select_user"),
+		};
+		let input = RuleItem {
+			kind: GelxRuleItem::Input,
+			..output
+		};
+
+		assert_eq!(
+			metadata.rule_attributes(&role).unwrap().to_string(),
+			quote!(#[non_exhaustive]).to_string()
+		);
+		assert_eq!(metadata.rule_derive_macro_paths(&role).unwrap().len(), 1);
+		assert_eq!(metadata.rule_derive_macro_paths(&output).unwrap().len(), 1);
+		assert!(metadata.rule_derive_macro_paths(&input).unwrap().is_empty());
+		assert!(metadata.rule_attributes(&output).unwrap().is_empty());
+	}
+
+	#[test]
+	fn test_invalid_rules() {
+		let metadata: GelxMetadata = toml::from_str(
+			r#"
+			[[rules]]
+			types = ["default::*"]
+			derives = ["::core::hash::Hash"]
+
+			[[rules]]
+			types = ["default::*"]
+			derives = ["not a path"]
+			attributes = ["= non_exhaustive"]
+			"#,
+		)
+		.unwrap();
+		let role = RuleItem {
+			kind: GelxRuleItem::Enum,
+			type_name: Some("default::Role"),
+			query_name: None,
+		};
+
+		let derive_error = metadata.rule_derive_macro_paths(&role).unwrap_err();
+		assert!(
+			derive_error
+				.to_string()
+				.starts_with("Invalid derive `not a path` in `rules[1].derives`")
+		);
+
+		let attribute_error = metadata.rule_attributes(&role).unwrap_err();
+		assert!(
+			attribute_error
+				.to_string()
+				.starts_with("Invalid attribute `= non_exhaustive` in `rules[1].attributes`")
+		);
+	}
+
+	#[test]
+	fn test_allowed_capabilities() {
+		let metadata: GelxMetadata =
+			toml::from_str(r#"allowed_capabilities = ["modifications", "transaction"]"#).unwrap();
+
+		assert_eq!(
+			metadata.allowed_capabilities_flags(),
+			Capabilities::MODIFICATIONS | Capabilities::TRANSACTION
+		);
+		assert_eq!(
+			GelxMetadata::default().allowed_capabilities_flags(),
+			Capabilities::ALL
+		);
+	}
+
+	#[test]
+	fn test_query_options_for() {
+		let metadata: GelxMetadata = toml::from_str(
+			r#"
+				[[query_options]]
+				queries = ["report_*"]
+				timeout_ms = 30000
+				retry_attempts = 3
+
+				[[query_options]]
+				queries = ["report_slow"]
+				retry_attempts = 1
+			"#,
+		)
+		.unwrap();
+		let options = metadata.query_options_for("report_slow");
+
+		assert_eq!(options.timeout_ms, Some(30000));
+		assert_eq!(options.retry_attempts, Some(1));
+		assert_eq!(options.read_only, None);
+		assert_eq!(
+			metadata.query_options_for("select_user"),
+			GelxQueryOptions::default()
+		);
+	}
+
+	#[test]
+	fn test_query_tag() {
+		let metadata = GelxMetadata::builder().query_tag_prefix("api/").build();
+
+		assert_eq!(
+			GelxMetadata::default().query_tag("select_user").unwrap(),
+			"select_user"
+		);
+		assert_eq!(
+			metadata.query_tag("select_user").unwrap(),
+			"api/select_user"
+		);
+		assert!(
+			GelxMetadata::builder()
+				.query_tag_prefix("gel/")
+				.build()
+				.query_tag("select_user")
+				.is_err()
+		);
+		assert!(metadata.query_tag(&"a".repeat(128)).is_err());
+	}
+
+	#[test]
+	fn test_tracing_is_disabled_by_default() {
+		let metadata: GelxMetadata = toml::from_str(
+			"features = { tracing = \"otel\" }\n[tracing]\nrecord_fields = [\"slug\"]",
+		)
+		.unwrap();
+
+		assert!(!GelxMetadata::default().features.tracing.is_enabled());
+		assert_eq!(metadata.features.tracing.alias().as_deref(), Some("otel"));
+		assert_eq!(metadata.tracing.record_fields, vec!["slug"]);
+	}
+
+	#[test]
+	fn test_blocking_is_disabled_by_default() {
+		let metadata: GelxMetadata = toml::from_str("features = { blocking = true }").unwrap();
+
+		assert!(!GelxMetadata::default().features.blocking.is_enabled());
+		assert!(metadata.features.blocking.is_enabled());
+		assert!(metadata.features.blocking.alias().is_none());
+	}
+
+	#[test]
+	fn test_verify_is_disabled_by_default() {
+		let metadata: GelxMetadata = toml::from_str("features = { verify = true }").unwrap();
+
+		assert!(!GelxMetadata::default().features.verify.is_enabled());
+		assert!(metadata.features.verify.is_enabled());
+	}
+
+	#[test]
+	fn test_allowlist_is_disabled_by_default() {
+		let metadata: GelxMetadata = toml::from_str("features = { allowlist = true }").unwrap();
+
+		assert!(!GelxMetadata::default().features.allowlist.is_enabled());
+		assert!(metadata.features.allowlist.is_enabled());
+	}
+
+	#[test]
+	fn test_regex_is_disabled_by_default() {
+		let metadata: GelxMetadata = toml::from_str("features = { regex = true }").unwrap();
+
+		assert!(!GelxMetadata::default().features.regex.is_enabled());
+		assert!(metadata.features.regex.is_enabled());
+	}
+
+	#[test]
+	fn test_bulk_queries() {
+		let metadata: GelxMetadata = toml::from_str("[bulk]\nqueries = [\"insert_*\"]").unwrap();
+
+		assert_eq!(metadata.bulk.chunk_size, 1000);
+		assert!(metadata.is_bulk_query("insert_user"));
+		assert!(!metadata.is_bulk_query("select_user"));
+		assert!(!GelxMetadata::default().is_bulk_query("insert_user"));
+	}
+
+	#[test]
+	fn test_pagination_params() {
+		let metadata: GelxMetadata =
+			toml::from_str("[pagination]\ncursor_param = \"after\"").unwrap();
+
+		assert_eq!(metadata.pagination.limit_param, "limit");
+		assert_eq!(metadata.pagination.offset_param, "offset");
+		assert_eq!(metadata.pagination.cursor_param, "after");
+	}
+
+	#[test]
+	fn test_serde_options_from_toml() {
+		let options: GelxSerdeOptions =
+			toml::from_str("enum_rename_all = \"SCREAMING-KEBAB-CASE\"").unwrap();
+
+		assert_eq!(
+			options.enum_rename_all,
+			Some(SerdeRenameRule::ScreamingKebabCase)
+		);
+		assert_eq!(
+			SerdeRenameRule::ScreamingKebabCase.as_ref(),
+			"SCREAMING-KEBAB-CASE"
+		);
+	}
 }
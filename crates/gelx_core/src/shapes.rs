@@ -0,0 +1,162 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+use proc_macro2::TokenStream;
+use quote::format_ident;
+use quote::quote;
+use syn::Ident;
+use uuid::Uuid;
+
+use crate::GelxMetadata;
+
+/// The name of the module which contains the shared shapes.
+pub const SHAPES_MODULE_NAME: &str = "shapes";
+
+/// A registry of the nested output shapes which are shared by the generated
+/// queries.
+///
+/// When provided via the [`QueryContext`](crate::QueryContext), identical
+/// nested shapes are generated once in the `shapes` module and referenced by
+/// every query which selects them.
+#[derive(Debug, Default)]
+pub struct Shapes {
+	inner: RefCell<ShapesInner>,
+}
+
+/// The normalized descriptor of a nested output shape which is used to
+/// identify identical shapes.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub(crate) struct ShapeKey {
+	/// The id of the object type, or of the descriptor when the shape isn't
+	/// backed by an object type.
+	pub type_id: Uuid,
+	/// The name, cardinality and target descriptor id of every element.
+	pub elements: Vec<(String, u8, Uuid)>,
+	/// The attributes added to the struct by the rules which match the query.
+	pub attributes: String,
+}
+
+#[derive(Debug, Default)]
+struct ShapesInner {
+	idents: HashMap<ShapeKey, Ident>,
+	names: HashSet<String>,
+	tokens: TokenStream,
+}
+
+impl Shapes {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Returns `true` when no shapes have been registered.
+	pub fn is_empty(&self) -> bool {
+		self.inner.borrow().idents.is_empty()
+	}
+
+	/// Register the shape generated by `generate` and return the identifier of
+	/// the shared struct. Shapes are compared by their normalized descriptor,
+	/// so the same identifier is returned for every identical shape.
+	///
+	/// The `name` is used for the first shape registered with it, later shapes
+	/// are suffixed with an increasing number.
+	pub(crate) fn register(
+		&self,
+		key: ShapeKey,
+		name: &str,
+		generate: impl Fn(&Ident) -> TokenStream,
+	) -> Ident {
+		let mut inner = self.inner.borrow_mut();
+
+		if let Some(ident) = inner.idents.get(&key) {
+			return ident.clone();
+		}
+
+		let mut unique_name = name.to_string();
+		let mut index = 1;
+
+		while inner.names.contains(&unique_name) {
+			index += 1;
+			unique_name = format!("{name}{index}");
+		}
+
+		let ident = format_ident!("{unique_name}");
+		let tokens = generate(&ident);
+
+		inner.tokens.extend(tokens);
+		inner.names.insert(unique_name);
+		inner.idents.insert(key, ident.clone());
+
+		ident
+	}
+
+	/// Generate the `shapes` module containing all the registered shapes.
+	pub fn to_token_stream(&self, metadata: &GelxMetadata) -> TokenStream {
+		if self.is_empty() {
+			return TokenStream::new();
+		}
+
+		let exports_ident = metadata.exports_alias_ident();
+		let module_ident = format_ident!("{SHAPES_MODULE_NAME}");
+		let tokens = &self.inner.borrow().tokens;
+
+		quote! {
+			pub mod #module_ident {
+				use ::gelx::exports as #exports_ident;
+
+				#tokens
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use assert2::check;
+
+	use super::*;
+
+	fn generate_user(ident: &Ident) -> TokenStream {
+		quote!(pub struct #ident { pub name: String, })
+	}
+
+	fn generate_user_with_slug(ident: &Ident) -> TokenStream {
+		quote!(pub struct #ident { pub name: String, pub slug: String, })
+	}
+
+	fn user_key(elements: &[&str]) -> ShapeKey {
+		ShapeKey {
+			type_id: Uuid::from_u128(1),
+			elements: elements
+				.iter()
+				.map(|name| ((*name).to_string(), b'A', Uuid::from_u128(0x101)))
+				.collect(),
+			attributes: String::new(),
+		}
+	}
+
+	#[test]
+	fn identical_shapes_are_shared() {
+		let shapes = Shapes::new();
+		let first = shapes.register(user_key(&["name"]), "User", generate_user);
+		let second = shapes.register(user_key(&["name"]), "User", generate_user);
+		let third = shapes.register(user_key(&["name", "slug"]), "User", generate_user_with_slug);
+
+		check!(first == "User");
+		check!(second == "User");
+		check!(third == "User2");
+
+		let metadata = GelxMetadata::default();
+		let tokens = shapes.to_token_stream(&metadata).to_string();
+		check!(tokens.matches("pub struct").count() == 2);
+		check!(tokens.starts_with("pub mod shapes"));
+	}
+
+	#[test]
+	fn empty_shapes_generate_nothing() {
+		let shapes = Shapes::new();
+		let metadata = GelxMetadata::default();
+
+		check!(shapes.to_token_stream(&metadata).is_empty());
+	}
+}
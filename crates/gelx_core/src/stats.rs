@@ -0,0 +1,159 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use gel_protocol::model::Duration;
+use gel_tokio::Client;
+use gel_tokio::Queryable;
+use heck::ToSnakeCase;
+use serde::Serialize;
+
+use crate::GelxCoreResult;
+use crate::GelxMetadata;
+use crate::query_paths;
+
+/// Query the statistics of the queries with the provided tags.
+pub async fn query_stats(
+	client: &Client,
+	tags: Vec<String>,
+) -> ::core::result::Result<Vec<QueryStatsOutput>, gel_errors::Error> {
+	client.query(QUERY_STATS_QUERY, &(tags,)).await
+}
+
+/// A row of `sys::QueryStats`. The statistics are recorded separately for
+/// every branch and compilation config, so a query can have multiple rows.
+#[derive(Debug, Clone, Queryable)]
+pub struct QueryStatsOutput {
+	pub tag: String,
+	pub calls: i64,
+	pub mean_exec_time: Duration,
+	pub stddev_exec_time: Duration,
+	pub max_exec_time: Duration,
+}
+
+/// The statistics of a generated query module.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct QueryStatsSummary {
+	/// The query tag, i.e. the name of the query module with the configured
+	/// prefix.
+	pub tag: String,
+	/// The number of times the query was executed.
+	pub calls: i64,
+	/// The mean execution time in milliseconds.
+	pub mean_ms: f64,
+	/// The standard deviation of the execution time in milliseconds.
+	pub stddev_ms: f64,
+	/// The slowest execution time in milliseconds.
+	pub max_ms: f64,
+}
+
+impl QueryStatsSummary {
+	/// Read the statistics of the queries in the `queries_path` of the
+	/// metadata from the gel instance.
+	pub async fn try_new(
+		metadata: &GelxMetadata,
+		root_path: impl AsRef<Path>,
+	) -> GelxCoreResult<Vec<Self>> {
+		let tags = query_paths(metadata, root_path)?
+			.iter()
+			.map(|path| {
+				let file_stem = path.file_stem().unwrap_or_default().to_string_lossy();
+				metadata.query_tag(&file_stem.to_snake_case())
+			})
+			.collect::<GelxCoreResult<Vec<_>>>()?;
+		let client = Client::new(&metadata.gel_config()?);
+		let rows = query_stats(&client, tags).await?;
+
+		Ok(Self::from_rows(&rows))
+	}
+
+	/// Combine the rows of each tag into a single summary. The summaries are
+	/// sorted by the mean execution time with the slowest first.
+	pub fn from_rows(rows: &[QueryStatsOutput]) -> Vec<Self> {
+		let mut tags = BTreeMap::<&str, Vec<&QueryStatsOutput>>::new();
+
+		for row in rows.iter().filter(|row| row.calls > 0) {
+			tags.entry(&row.tag).or_default().push(row);
+		}
+
+		let mut summaries = tags
+			.into_iter()
+			.map(|(tag, rows)| {
+				let calls = rows.iter().map(|row| row.calls).sum::<i64>();
+				let weight = |row: &QueryStatsOutput| row.calls as f64 / calls as f64;
+				let mean_ms = rows
+					.iter()
+					.map(|row| weight(row) * duration_ms(row.mean_exec_time))
+					.sum::<f64>();
+				// The pooled variance of the rows: E[x²] - E[x]²
+				let square_ms = rows
+					.iter()
+					.map(|row| {
+						let mean = duration_ms(row.mean_exec_time);
+						let stddev = duration_ms(row.stddev_exec_time);
+						weight(row) * (stddev.powi(2) + mean.powi(2))
+					})
+					.sum::<f64>();
+				let stddev_ms = (square_ms - mean_ms.powi(2)).max(0.0).sqrt();
+				let max_ms = rows
+					.iter()
+					.map(|row| duration_ms(row.max_exec_time))
+					.fold(0.0, f64::max);
+
+				Self {
+					tag: tag.to_string(),
+					calls,
+					mean_ms,
+					stddev_ms,
+					max_ms,
+				}
+			})
+			.collect::<Vec<_>>();
+
+		summaries.sort_by(|a, b| b.mean_ms.total_cmp(&a.mean_ms));
+		summaries
+	}
+}
+
+fn duration_ms(duration: Duration) -> f64 {
+	duration.to_micros() as f64 / 1000.0
+}
+
+pub const QUERY_STATS_QUERY: &str = "select sys::QueryStats {tag, calls, mean_exec_time, \
+                                     stddev_exec_time, max_exec_time} filter .tag in \
+                                     array_unpack(<array<str>>$0)";
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn row(tag: &str, calls: i64, mean_ms: i64, stddev_ms: i64, max_ms: i64) -> QueryStatsOutput {
+		QueryStatsOutput {
+			tag: tag.to_string(),
+			calls,
+			mean_exec_time: Duration::from_micros(mean_ms * 1000),
+			stddev_exec_time: Duration::from_micros(stddev_ms * 1000),
+			max_exec_time: Duration::from_micros(max_ms * 1000),
+		}
+	}
+
+	#[test]
+	fn test_summaries_combine_rows_by_tag() {
+		let summaries = QueryStatsSummary::from_rows(&[
+			row("select_user", 1, 10, 0, 10),
+			row("select_user", 3, 30, 0, 40),
+			row("insert_user", 2, 100, 10, 120),
+			row("remove_user", 0, 0, 0, 0),
+		]);
+
+		assert_eq!(summaries.len(), 2);
+		assert_eq!(summaries[0].tag, "insert_user");
+		assert_eq!(summaries[0].calls, 2);
+		assert!((summaries[0].stddev_ms - 10.0).abs() < 1e-6);
+		assert!((summaries[0].max_ms - 120.0).abs() < 1e-6);
+		assert_eq!(summaries[1].tag, "select_user");
+		assert_eq!(summaries[1].calls, 4);
+		assert!((summaries[1].mean_ms - 25.0).abs() < 1e-6);
+		assert!((summaries[1].stddev_ms - 75.0_f64.sqrt()).abs() < 1e-6);
+		assert!((summaries[1].max_ms - 40.0).abs() < 1e-6);
+	}
+}
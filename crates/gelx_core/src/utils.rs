@@ -143,11 +143,332 @@ pub fn resolve_path(path: impl AsRef<Path>, error_span: Span) -> syn::Result<Pat
 	Ok(base_dir_path.join(path))
 }
 
+/// Convert the provided documentation into `#[doc]` attributes. Each line is
+/// a separate attribute so that the formatted output uses `///` comments.
+pub(crate) fn doc_tokens(doc: Option<&str>) -> TokenStream {
+	let Some(doc) = doc else {
+		return TokenStream::new();
+	};
+
+	let lines = doc.lines().map(|line| {
+		let line = line.trim_end();
+
+		if line.is_empty() {
+			String::new()
+		} else {
+			format!(" {line}")
+		}
+	});
+
+	quote!(#(#[doc = #lines])*)
+}
+
+/// Get the comment at the start of an `EdgeQL` query with the leading `#`
+/// markers removed.
+///
+/// ```edgeql
+/// # Select the user with the provided slug.
+/// select User { name } filter .slug = <str>$slug;
+/// ```
+pub fn query_leading_comment(query: &str) -> Option<String> {
+	let lines = query
+		.lines()
+		.map(str::trim)
+		.skip_while(|line| line.is_empty())
+		.map_while(|line| line.strip_prefix('#'))
+		.map(|line| line.strip_prefix(' ').unwrap_or(line).trim_end())
+		.collect::<Vec<_>>();
+
+	(!lines.is_empty()).then(|| lines.join("\n"))
+}
+
 /// Will format the given source code using `prettyplease`.
 pub fn prettify(source: &str) -> syn::Result<String> {
 	Ok(prettyplease::unparse(&syn::parse_str(source)?))
 }
 
+/// Match the `value` against a pattern which supports the `*` (any sequence
+/// of characters) and `?` (any single character) wildcards.
+pub(crate) fn glob_match(pattern: &str, value: &str) -> bool {
+	let pattern = pattern.chars().collect::<Vec<_>>();
+	let value = value.chars().collect::<Vec<_>>();
+	let (mut p, mut v) = (0, 0);
+	let mut backtrack = None;
+
+	while v < value.len() {
+		match pattern.get(p) {
+			Some('*') => {
+				backtrack = Some((p, v));
+				p += 1;
+			}
+			Some(&char) if char == '?' || char == value[v] => {
+				p += 1;
+				v += 1;
+			}
+			_ => {
+				let Some((star, matched)) = backtrack else {
+					return false;
+				};
+
+				p = star + 1;
+				v = matched + 1;
+				backtrack = Some((star, v));
+			}
+		}
+	}
+
+	pattern[p..].iter().all(|char| *char == '*')
+}
+
+/// The name of the pointer which the `$param` is assigned to in the shape of
+/// an insert or update, e.g. `name` for `name := <str>$user_name`.
+///
+/// Returns `None` when the parameter is only used in other expressions, e.g.
+/// the `filter` of a select.
+pub fn assigned_pointer<'a>(query: &'a str, param: &str) -> Option<&'a str> {
+	let needle = format!("${param}");
+	let bytes = query.as_bytes();
+
+	query.match_indices(&needle).find_map(|(start, _)| {
+		let end = start + needle.len();
+
+		if bytes
+			.get(end)
+			.is_some_and(|byte| byte.is_ascii_alphanumeric() || *byte == b'_')
+		{
+			return None;
+		}
+
+		let mut before = query[..start].trim_end();
+
+		// Skip the `<...>` cast of the parameter.
+		if before.ends_with('>') {
+			let mut depth = 0;
+			let cast_start = before.char_indices().rev().find_map(|(position, char)| {
+				match char {
+					'>' => depth += 1,
+					'<' => depth -= 1,
+					_ => {}
+				}
+
+				(depth == 0).then_some(position)
+			})?;
+			before = before[..cast_start].trim_end();
+		}
+
+		let before = before.strip_suffix(":=")?.trim_end();
+		let name_start = before
+			.rfind(|char: char| !(char.is_ascii_alphanumeric() || char == '_'))
+			.map_or(0, |position| position + 1);
+		let name = &before[name_start..];
+
+		(!name.is_empty()).then_some(name)
+	})
+}
+
+/// The query with the contents of the string literals, quoted identifiers and
+/// comments replaced by spaces so that it can be scanned without matching the
+/// text of a literal. The quotes and the byte positions of the query are
+/// preserved.
+fn mask_literals(query: &str) -> String {
+	let mut masked = String::with_capacity(query.len());
+	let mut chars = query.chars();
+	let mut quote = None;
+	let mask = |masked: &mut String, char: char| {
+		masked.extend(std::iter::repeat_n(' ', char.len_utf8()));
+	};
+
+	while let Some(char) = chars.next() {
+		if let Some(delimiter) = quote {
+			if char == '\\' && delimiter != '`' {
+				mask(&mut masked, char);
+
+				if let Some(escaped) = chars.next() {
+					mask(&mut masked, escaped);
+				}
+			} else if char == delimiter {
+				quote = None;
+				masked.push(char);
+			} else {
+				mask(&mut masked, char);
+			}
+
+			continue;
+		}
+
+		match char {
+			'#' => {
+				mask(&mut masked, char);
+
+				for char in chars.by_ref() {
+					if char == '\n' {
+						masked.push(char);
+						break;
+					}
+
+					mask(&mut masked, char);
+				}
+			}
+			'\'' | '"' | '`' => {
+				quote = Some(char);
+				masked.push(char);
+			}
+			char => masked.push(char),
+		}
+	}
+
+	masked
+}
+
+/// The query without the trailing semicolon, whitespace and comments so that
+/// it can be wrapped in a larger query.
+///
+/// ```edgeql
+/// select User { name }; # The trailing comment is removed.
+/// ```
+pub fn query_body(query: &str) -> &str {
+	let masked = mask_literals(query);
+	let end = masked
+		.trim_end_matches(|char: char| char == ';' || char.is_whitespace())
+		.len();
+
+	query[..end].trim_start()
+}
+
+/// The first of the `clauses`, e.g. `order by`, which is applied to the result
+/// of the whole query rather than to a nested shape or subquery.
+pub fn top_level_clause<'a>(query: &str, clauses: &[&'a str]) -> Option<&'a str> {
+	let masked = mask_literals(query_body(query)).to_lowercase();
+	let mut depth = 0_usize;
+	let mut previous = ' ';
+
+	for (index, char) in masked.char_indices() {
+		match char {
+			'(' | '[' | '{' => depth += 1,
+			')' | ']' | '}' => depth = depth.saturating_sub(1),
+			_ => {}
+		}
+
+		let is_word_start = !(previous.is_alphanumeric() || matches!(previous, '_' | '.' | '@'));
+		previous = char;
+
+		if depth > 0 || !is_word_start {
+			continue;
+		}
+
+		let rest = &masked[index..];
+		let clause = clauses.iter().find(|clause| {
+			let mut words = clause.split_whitespace();
+			let mut rest = rest;
+
+			words.all(|word| {
+				let Some(after) = rest.trim_start().strip_prefix(word) else {
+					return false;
+				};
+
+				rest = after;
+				true
+			}) && !rest.starts_with(|char: char| char.is_alphanumeric() || char == '_')
+		});
+
+		if let Some(clause) = clause {
+			return Some(clause);
+		}
+	}
+
+	None
+}
+
+/// Wrap the query so that it returns a single page of results after the
+/// `$gelx_cursor` id, ordered by `id` and limited to `$gelx_limit` rows.
+pub fn keyset_query(query: &str) -> String {
+	let query = query_body(query);
+
+	[
+		"with",
+		"\tgelx_cursor := <optional uuid>$gelx_cursor,",
+		"\tgelx_rows := (",
+		query,
+		"\t)",
+		"select gelx_rows",
+		"filter not exists gelx_cursor or .id > gelx_cursor",
+		"order by .id",
+		"limit <int64>$gelx_limit;",
+	]
+	.join("\n")
+}
+
+/// Wrap the query so that it runs once for every item of the `$items` JSON
+/// array. Every named parameter is read from the matching field of the item,
+/// e.g. `<optional str>$name` becomes `<str>item['name']`. A JSON `null` is
+/// cast to an empty set so optional parameters don't need `json_get`.
+///
+/// Returns `None` when a parameter isn't preceded by a cast.
+pub fn bulk_query(query: &str) -> Option<String> {
+	let query = query_body(query);
+	// The parameters are found in the masked query so that a `$` in a string
+	// literal or comment isn't mistaken for a parameter.
+	let masked = mask_literals(query);
+	let bytes = masked.as_bytes();
+	let mut rewritten = String::with_capacity(query.len());
+	let mut last = 0;
+	let mut index = 0;
+
+	while let Some(offset) = masked[index..].find('$') {
+		let start = index + offset;
+		let name_end = masked[start + 1..]
+			.find(|char: char| !(char.is_ascii_alphanumeric() || char == '_'))
+			.map_or(masked.len(), |end| start + 1 + end);
+		index = start + 1;
+
+		if name_end == start + 1 {
+			continue;
+		}
+
+		// Find the `<...>` cast which ends right before the parameter.
+		let cast_end = masked[..start].trim_end().len().checked_sub(1)?;
+		let mut depth = 0;
+		let mut cast_start = None;
+
+		if bytes[cast_end] != b'>' {
+			return None;
+		}
+
+		for position in (0..=cast_end).rev() {
+			match bytes[position] {
+				b'>' => depth += 1,
+				b'<' => depth -= 1,
+				_ => {}
+			}
+
+			if depth == 0 {
+				cast_start = Some(position);
+				break;
+			}
+		}
+
+		let cast_start = cast_start?;
+		let cast = query[cast_start + 1..cast_end].trim();
+		let cast = cast.strip_prefix("optional ").unwrap_or(cast).trim_start();
+		let name = &query[start + 1..name_end];
+
+		rewritten.push_str(&query[last..cast_start]);
+		rewritten.push('<');
+		rewritten.push_str(cast);
+		rewritten.push_str(">item['");
+		rewritten.push_str(name);
+		rewritten.push_str("']");
+		last = name_end;
+		index = name_end;
+	}
+
+	rewritten.push_str(&query[last..]);
+
+	Some(format!(
+		"for item in json_array_unpack(<json>$items) union (\n{rewritten}\n);"
+	))
+}
+
 #[cfg(test)]
 mod tests {
 	use assert2::check;
@@ -168,6 +489,108 @@ mod tests {
 		Ok(())
 	}
 
+	#[rstest]
+	#[case::exact("default::Role", "default::Role", true)]
+	#[case::star("default::*", "default::Role", true)]
+	#[case::star_middle("select_*_query", "select_user_query", true)]
+	#[case::question("select_use?", "select_user", true)]
+	#[case::different_module("default::*", "additional::Role", false)]
+	#[case::too_short("select_use?", "select_use", false)]
+	fn test_glob_match(#[case] pattern: &str, #[case] value: &str, #[case] expected: bool) {
+		check!(glob_match(pattern, value) == expected);
+	}
+
+	#[rstest]
+	#[case::none("select User { name };", None)]
+	#[case::single("# Get the users.\nselect User;", Some("Get the users."))]
+	#[case::multiple(
+		"\n# Get the users.\n#\n#   Indented.\nselect User;\n# ignored",
+		Some("Get the users.\n\n  Indented.")
+	)]
+	fn test_query_leading_comment(#[case] query: &str, #[case] expected: Option<&str>) {
+		check!(query_leading_comment(query).as_deref() == expected);
+	}
+
+	#[rstest]
+	#[case::semicolon("select User;\n", "select User")]
+	#[case::trailing_comment("select User; # All the users.\n# Done\n", "select User")]
+	#[case::inline_comment("select User # All the users.\n;", "select User")]
+	#[case::leading_comment("# Users\nselect User;", "# Users\nselect User")]
+	#[case::hash_in_string("select '# not a comment;';", "select '# not a comment;'")]
+	#[case::escaped_quote(r"select 'it\'s; #';", r"select 'it\'s; #'")]
+	fn test_query_body(#[case] query: &str, #[case] expected: &str) {
+		check!(query_body(query) == expected);
+	}
+
+	#[rstest]
+	#[case::none("select User { name };", None)]
+	#[case::order_by("select User { name } order by .name;", Some("order by"))]
+	#[case::limit("select User { name }\nLIMIT 10", Some("limit"))]
+	#[case::offset("select User offset <int64>$offset;", Some("offset"))]
+	#[case::nested_shape("select User { friends: { name } order by .name limit 3 };", None)]
+	#[case::subquery(
+		"select User filter .id in (select Admin order by .name limit 1).id;",
+		None
+	)]
+	#[case::string("select User filter .name = 'order by';", None)]
+	#[case::comment("select User; # limit 10", None)]
+	#[case::property("select User { name } filter .limit > 0 and .order_by = 1;", None)]
+	fn test_top_level_clause(#[case] query: &str, #[case] expected: Option<&str>) {
+		check!(top_level_clause(query, &["order by", "limit", "offset"]) == expected);
+	}
+
+	#[test]
+	fn test_keyset_query() {
+		let query = keyset_query("\n# Select the users.\nselect User { name };\n");
+
+		insta::assert_snapshot!(query, @r"
+		with
+			gelx_cursor := <optional uuid>$gelx_cursor,
+			gelx_rows := (
+		# Select the users.
+		select User { name }
+			)
+		select gelx_rows
+		filter not exists gelx_cursor or .id > gelx_cursor
+		order by .id
+		limit <int64>$gelx_limit;
+		");
+	}
+
+	#[test]
+	fn test_bulk_query() {
+		let query = bulk_query(
+			"insert User {\n\tname := <str>$name,\n\tbio := <optional str>$bio,\n\ttags := \
+			 <array<str>>$tags,\n}; # Insert the user.\n",
+		);
+
+		insta::assert_snapshot!(query.unwrap(), @r"
+		for item in json_array_unpack(<json>$items) union (
+		insert User {
+			name := <str>item['name'],
+			bio := <str>item['bio'],
+			tags := <array<str>>item['tags'],
+		}
+		);
+		");
+		check!(bulk_query("select User filter .name = $name").is_none());
+	}
+
+	#[test]
+	fn test_bulk_query_skips_literals() {
+		let query = bulk_query(
+			"update Product filter .sku = <str>$sku # Costs $5.\nset { note := 'costs $5', price \
+			 := <float64>$price };",
+		);
+
+		insta::assert_snapshot!(query.unwrap(), @r"
+		for item in json_array_unpack(<json>$items) union (
+		update Product filter .sku = <str>item['sku'] # Costs $5.
+		set { note := 'costs $5', price := <float64>item['price'] }
+		);
+		");
+	}
+
 	#[test]
 	fn error_when_formatting_invalid_rust() {
 		let content = "struct Foo { content: String, allowed: bool, times: u64,,,,, INVALID}";
@@ -274,4 +697,22 @@ mod tests {
 		let result = maybe_uuid_to_import(&default_uuid, &exports_ident);
 		assert!(result.is_none());
 	}
+
+	#[rstest]
+	#[case::insert("insert User { name := <str>$name }", "name", Some("name"))]
+	#[case::renamed(
+		"update User set { bio := <optional str>$user_bio }",
+		"user_bio",
+		Some("bio")
+	)]
+	#[case::without_cast("insert User { name := $name }", "name", Some("name"))]
+	#[case::filter("select User filter .name = <str>$name", "name", None)]
+	#[case::prefix("insert User { name := <str>$name_2 }", "name", None)]
+	fn can_find_the_assigned_pointer(
+		#[case] query: &str,
+		#[case] param: &str,
+		#[case] expected: Option<&str>,
+	) {
+		check!(assigned_pointer(query, param) == expected);
+	}
 }
@@ -1,7 +1,14 @@
 #![doc(html_logo_url = "https://raw.githubusercontent.com/ifiokjr/gelx/main/setup/assets/logo.png")]
 
+use std::ops::Range;
+use std::path::PathBuf;
+
+use gelx_core::DerivedDescriptors;
+use gelx_core::GelxCoreError;
 use gelx_core::GelxMetadata;
-use gelx_core::generate_query_token_stream;
+use gelx_core::QueryContext;
+use gelx_core::describe_query_error;
+use gelx_core::generate_query_token_stream_with_context;
 use gelx_core::get_descriptor_sync;
 use gelx_core::resolve_path;
 use proc_macro::TokenStream;
@@ -38,6 +45,7 @@ pub fn gelx_raw(input: TokenStream) -> TokenStream {
 pub(crate) struct GelQueryInput {
 	pub(crate) module: syn::Ident,
 	pub(crate) query: String,
+	pub(crate) source: QuerySource,
 }
 
 impl Parse for GelQueryInput {
@@ -54,7 +62,7 @@ impl Parse for GelQueryInput {
 			if marker == "file" {
 				QueryContent::File(raw_content.value(), raw_content.span())
 			} else if marker == "query" {
-				QueryContent::Query(raw_content.value())
+				QueryContent::Query(raw_content)
 			} else {
 				let message = format!("unexpected marker token: {marker}");
 				return Err(syn::Error::new_spanned(marker, message));
@@ -63,9 +71,72 @@ impl Parse for GelQueryInput {
 			QueryContent::File(format!("queries/{module}.edgeql"), module.span())
 		};
 
-		let query = query_content.resolve()?;
-		Ok(Self { module, query })
+		let (query, source) = query_content.resolve()?;
+		Ok(Self {
+			module,
+			query,
+			source,
+		})
+	}
+}
+
+impl GelQueryInput {
+	/// Convert the error into a compile error. Errors reported by the database
+	/// point to the relevant part of inline queries and describe the location
+	/// within query files.
+	fn to_syn_error(&self, error: GelxCoreError) -> syn::Error {
+		if !matches!(error, GelxCoreError::Gel(_)) {
+			return error.into();
+		}
+
+		match &self.source {
+			QuerySource::Inline(literal) => {
+				let subspan = error
+					.query_range()
+					.and_then(|range| literal_subspan(literal, range));
+				// The excerpt is only needed when the error can't point to the relevant part
+				// of the literal.
+				let query = subspan.is_none().then_some(self.query.as_str());
+				let message = describe_query_error(&error, query, None, 1);
+
+				syn::Error::new(subspan.unwrap_or_else(|| literal.span()), message)
+			}
+			QuerySource::File {
+				path,
+				span,
+				first_line,
+			} => {
+				let message =
+					describe_query_error(&error, Some(&self.query), Some(path), *first_line);
+				syn::Error::new(*span, message)
+			}
+		}
+	}
+}
+
+/// Map the range of characters within the query onto the span of the string
+/// literal. This is only possible for literals without escape sequences and
+/// when the compiler supports subspans.
+fn literal_subspan(literal: &syn::LitStr, range: Range<usize>) -> Option<Span> {
+	let token = literal.token();
+	let source = token.to_string();
+	let value = literal.value();
+	let content_start = source.find('"')? + 1;
+
+	if source.get(content_start..content_start + value.len())? != value {
+		return None;
 	}
+
+	let byte_offset = |index: usize| {
+		value
+			.char_indices()
+			.nth(index)
+			.map_or(value.len(), |(offset, _)| offset)
+	};
+	let start = content_start + byte_offset(range.start);
+	let end = content_start + byte_offset(range.end.max(range.start + 1));
+
+	token.subspan(start..end)
 }
 
 impl ToTokens for GelQueryInput {
@@ -77,9 +148,19 @@ impl ToTokens for GelQueryInput {
 		let module_name = self.module.to_string();
 		let token_stream = get_descriptor_sync(&self.query, &metadata)
 			.and_then(|descriptor| {
-				generate_query_token_stream(&descriptor, &module_name, &self.query, &metadata, true)
+				let derived =
+					DerivedDescriptors::try_new_sync(&module_name, &self.query, &metadata)?;
+
+				generate_query_token_stream_with_context(
+					&descriptor,
+					&module_name,
+					&self.query,
+					&metadata,
+					true,
+					QueryContext::builder().derived(&derived).build(),
+				)
 			})
-			.unwrap_or_else(|error| syn::Error::from(error).to_compile_error());
+			.unwrap_or_else(|error| self.to_syn_error(error).to_compile_error());
 
 		tokens.extend(token_stream);
 	}
@@ -87,25 +168,43 @@ impl ToTokens for GelQueryInput {
 
 #[derive(Debug)]
 pub(crate) enum QueryContent {
-	Query(String),
+	Query(syn::LitStr),
 	File(String, Span),
 }
 
+/// Where the query was defined.
+#[derive(Debug)]
+pub(crate) enum QuerySource {
+	Inline(syn::LitStr),
+	File {
+		path: PathBuf,
+		span: Span,
+		/// The line of the file which the trimmed query starts on.
+		first_line: usize,
+	},
+}
+
 impl QueryContent {
-	pub fn resolve(self) -> syn::Result<String> {
+	pub fn resolve(self) -> syn::Result<(String, QuerySource)> {
 		match self {
-			QueryContent::Query(query) => Ok(query),
+			QueryContent::Query(literal) => Ok((literal.value(), QuerySource::Inline(literal))),
 			QueryContent::File(relative_path, span) => {
 				let path = resolve_path(relative_path, span)?;
+				let value = std::fs::read_to_string(&path).map_err(|error| {
+					syn::Error::new(
+						span,
+						format!("failed to read query file at {}: {}", path.display(), error),
+					)
+				})?;
+				let leading = value.len() - value.trim_start().len();
+				let first_line = value[..leading].matches('\n').count() + 1;
+				let source = QuerySource::File {
+					path,
+					span,
+					first_line,
+				};
 
-				std::fs::read_to_string(&path)
-					.map_err(|error| {
-						syn::Error::new(
-							span,
-							format!("failed to read query file at {}: {}", path.display(), error),
-						)
-					})
-					.map(|value| value.trim().to_string())
+				Ok((value.trim().to_string(), source))
 			}
 		}
 	}
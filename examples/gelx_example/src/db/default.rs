@@ -38,6 +38,50 @@ impl ::core::convert::From<AccountProvider> for __g::gel_protocol::value::Value
 mod email {
     use super::*;
 }
+/// The exclusive constraints of `default::Email` which can be violated by a mutation.
+#[derive(
+    ::std::fmt::Debug,
+    ::core::clone::Clone,
+    ::core::marker::Copy,
+    ::core::cmp::PartialEq,
+    ::core::cmp::Eq
+)]
+pub enum EmailConstraintError {
+    /// The `email` is already taken.
+    EmailTaken,
+    /// The combination of `user` and `primary` is already taken.
+    UserPrimaryTaken,
+}
+impl EmailConstraintError {
+    /// Classify the error returned by a mutation. Returns `None` when the
+    /// error isn't a violation of one of the exclusive constraints, or when
+    /// the violated constraint is ambiguous.
+    pub fn from_error(error: &__g::gel_errors::Error) -> Option<Self> {
+        let violation = __g::constraint::ExclusiveViolation::from_error(error)?;
+        let index = violation
+            .find("default::Email", &[&["email"], &["user", "primary"]])?;
+        [Self::EmailTaken, Self::UserPrimaryTaken].get(index).copied()
+    }
+    /// The names of the pointers which are constrained.
+    pub fn pointers(&self) -> &'static [&'static str] {
+        match self {
+            Self::EmailTaken => &["email"],
+            Self::UserPrimaryTaken => &["user", "primary"],
+        }
+    }
+}
+impl ::core::fmt::Display for EmailConstraintError {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        let message = match self {
+            Self::EmailTaken => "the `email` is already taken",
+            Self::UserPrimaryTaken => {
+                "the combination of `user` and `primary` is already taken"
+            }
+        };
+        f.write_str(message)
+    }
+}
+impl ::std::error::Error for EmailConstraintError {}
 mod location {
     use super::*;
 }
@@ -96,12 +140,103 @@ impl ::std::ops::DerefMut for Position {
         &mut self.0
     }
 }
+impl __g::validate::Validate for Position {
+    fn validate(&self) -> ::core::result::Result<(), __g::validate::ValidationError> {
+        __g::validate::min_value("default::Position", &self.0, &0)?;
+        __g::validate::max_value("default::Position", &self.0, &1_000_000)?;
+        Ok(())
+    }
+}
 mod project {
     use super::*;
 }
+/// The exclusive constraints of `default::Project` which can be violated by a mutation.
+#[derive(
+    ::std::fmt::Debug,
+    ::core::clone::Clone,
+    ::core::marker::Copy,
+    ::core::cmp::PartialEq,
+    ::core::cmp::Eq
+)]
+pub enum ProjectConstraintError {
+    /// The combination of `creator` and `slug` is already taken.
+    CreatorSlugTaken,
+}
+impl ProjectConstraintError {
+    /// Classify the error returned by a mutation. Returns `None` when the
+    /// error isn't a violation of one of the exclusive constraints, or when
+    /// the violated constraint is ambiguous.
+    pub fn from_error(error: &__g::gel_errors::Error) -> Option<Self> {
+        let violation = __g::constraint::ExclusiveViolation::from_error(error)?;
+        let index = violation.find("default::Project", &[&["creator", "slug"]])?;
+        [Self::CreatorSlugTaken].get(index).copied()
+    }
+    /// The names of the pointers which are constrained.
+    pub fn pointers(&self) -> &'static [&'static str] {
+        match self {
+            Self::CreatorSlugTaken => &["creator", "slug"],
+        }
+    }
+}
+impl ::core::fmt::Display for ProjectConstraintError {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        let message = match self {
+            Self::CreatorSlugTaken => {
+                "the combination of `creator` and `slug` is already taken"
+            }
+        };
+        f.write_str(message)
+    }
+}
+impl ::std::error::Error for ProjectConstraintError {}
 mod relationship {
     use super::*;
 }
+/// The exclusive constraints of `default::Relationship` which can be violated by a mutation.
+#[derive(
+    ::std::fmt::Debug,
+    ::core::clone::Clone,
+    ::core::marker::Copy,
+    ::core::cmp::PartialEq,
+    ::core::cmp::Eq
+)]
+pub enum RelationshipConstraintError {
+    /// The combination of `actor`, `target` and `relationship_type` is already taken.
+    ActorTargetRelationshipTypeTaken,
+}
+impl RelationshipConstraintError {
+    /// Classify the error returned by a mutation. Returns `None` when the
+    /// error isn't a violation of one of the exclusive constraints, or when
+    /// the violated constraint is ambiguous.
+    pub fn from_error(error: &__g::gel_errors::Error) -> Option<Self> {
+        let violation = __g::constraint::ExclusiveViolation::from_error(error)?;
+        if violation
+            .matches("default::Relationship", &["actor", "target", "relationship_type"])
+        {
+            return Some(Self::ActorTargetRelationshipTypeTaken);
+        }
+        None
+    }
+    /// The names of the pointers which are constrained.
+    pub fn pointers(&self) -> &'static [&'static str] {
+        match self {
+            Self::ActorTargetRelationshipTypeTaken => {
+                &["actor", "target", "relationship_type"]
+            }
+        }
+    }
+}
+impl ::core::fmt::Display for RelationshipConstraintError {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        let message = match self {
+            Self::ActorTargetRelationshipTypeTaken => {
+                "the combination of `actor`, `target` and `relationship_type` is already taken"
+            }
+        };
+        f.write_str(message)
+    }
+}
+impl ::std::error::Error for RelationshipConstraintError {}
 #[derive(
     ::std::fmt::Debug,
     ::core::clone::Clone,
@@ -169,6 +304,52 @@ mod team {
 mod test_user {
     use super::*;
 }
+/// The exclusive constraints of `default::TestUser` which can be violated by a mutation.
+#[derive(
+    ::std::fmt::Debug,
+    ::core::clone::Clone,
+    ::core::marker::Copy,
+    ::core::cmp::PartialEq,
+    ::core::cmp::Eq
+)]
+pub enum TestUserConstraintError {
+    /// The `name` is already taken.
+    NameTaken,
+    /// The `namelc` is already taken.
+    NamelcTaken,
+    /// The `public_id` is already taken.
+    PublicIdTaken,
+}
+impl TestUserConstraintError {
+    /// Classify the error returned by a mutation. Returns `None` when the
+    /// error isn't a violation of one of the exclusive constraints, or when
+    /// the violated constraint is ambiguous.
+    pub fn from_error(error: &__g::gel_errors::Error) -> Option<Self> {
+        let violation = __g::constraint::ExclusiveViolation::from_error(error)?;
+        let index = violation
+            .find("default::TestUser", &[&["name"], &["namelc"], &["public_id"]])?;
+        [Self::NameTaken, Self::NamelcTaken, Self::PublicIdTaken].get(index).copied()
+    }
+    /// The names of the pointers which are constrained.
+    pub fn pointers(&self) -> &'static [&'static str] {
+        match self {
+            Self::NameTaken => &["name"],
+            Self::NamelcTaken => &["namelc"],
+            Self::PublicIdTaken => &["public_id"],
+        }
+    }
+}
+impl ::core::fmt::Display for TestUserConstraintError {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        let message = match self {
+            Self::NameTaken => "the `name` is already taken",
+            Self::NamelcTaken => "the `namelc` is already taken",
+            Self::PublicIdTaken => "the `public_id` is already taken",
+        };
+        f.write_str(message)
+    }
+}
+impl ::std::error::Error for TestUserConstraintError {}
 mod user {
     use super::*;
 }
@@ -230,3 +411,47 @@ impl ::std::ops::DerefMut for UserId {
 mod wallet {
     use super::*;
 }
+/// The exclusive constraints of `default::Wallet` which can be violated by a mutation.
+#[derive(
+    ::std::fmt::Debug,
+    ::core::clone::Clone,
+    ::core::marker::Copy,
+    ::core::cmp::PartialEq,
+    ::core::cmp::Eq
+)]
+pub enum WalletConstraintError {
+    /// The combination of `actor` and `primary` is already taken.
+    ActorPrimaryTaken,
+    /// The `pubkey` is already taken.
+    PubkeyTaken,
+}
+impl WalletConstraintError {
+    /// Classify the error returned by a mutation. Returns `None` when the
+    /// error isn't a violation of one of the exclusive constraints, or when
+    /// the violated constraint is ambiguous.
+    pub fn from_error(error: &__g::gel_errors::Error) -> Option<Self> {
+        let violation = __g::constraint::ExclusiveViolation::from_error(error)?;
+        let index = violation
+            .find("default::Wallet", &[&["actor", "primary"], &["pubkey"]])?;
+        [Self::ActorPrimaryTaken, Self::PubkeyTaken].get(index).copied()
+    }
+    /// The names of the pointers which are constrained.
+    pub fn pointers(&self) -> &'static [&'static str] {
+        match self {
+            Self::ActorPrimaryTaken => &["actor", "primary"],
+            Self::PubkeyTaken => &["pubkey"],
+        }
+    }
+}
+impl ::core::fmt::Display for WalletConstraintError {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        let message = match self {
+            Self::ActorPrimaryTaken => {
+                "the combination of `actor` and `primary` is already taken"
+            }
+            Self::PubkeyTaken => "the `pubkey` is already taken",
+        };
+        f.write_str(message)
+    }
+}
+impl ::std::error::Error for WalletConstraintError {}
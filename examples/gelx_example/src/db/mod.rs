@@ -24,6 +24,8 @@ pub struct Globals {
     pub client_token: Option<String>,
     pub alternative: Option<String>,
     pub current_user_id: Option<__g::uuid::Uuid>,
+    pub current_role: Option<default::Role>,
+    pub current_position: Option<default::Position>,
 }
 #[cfg(feature = "with_query")]
 impl __g::gel_tokio::GlobalsDelta for Globals {
@@ -31,6 +33,26 @@ impl __g::gel_tokio::GlobalsDelta for Globals {
         modifier.set("ext::auth::client_token", self.client_token);
         modifier.set("additional::alternative", self.alternative);
         modifier.set("default::current_user_id", self.current_user_id);
+        modifier
+            .set(
+                "default::current_role",
+                self
+                    .current_role
+                    .map_or(
+                        __g::gel_protocol::value::Value::Nothing,
+                        __g::gel_protocol::value::Value::from,
+                    ),
+            );
+        modifier
+            .set(
+                "default::current_position",
+                self
+                    .current_position
+                    .map_or(
+                        __g::gel_protocol::value::Value::Nothing,
+                        __g::gel_protocol::value::Value::from,
+                    ),
+            );
     }
 }
 #[cfg(feature = "with_query")]
@@ -49,37 +71,421 @@ impl Globals {
         let client = self.clone().into_client().await?;
         Ok(client)
     }
+    /// Create a copy of an existing client with the globals. Every
+    /// global is set, even when the value is empty.
+    pub fn apply_to(&self, client: &__g::gel_tokio::Client) -> __g::gel_tokio::Client {
+        client.with_globals(self.clone())
+    }
+}
+/// A partial set of globals. Only the globals which are provided are
+/// set, the rest keep the values of the client they are applied to.
+#[derive(::core::default::Default)]
+#[derive(::std::fmt::Debug, ::core::clone::Clone, __g::typed_builder::TypedBuilder)]
+#[cfg_attr(
+    feature = "with_serde",
+    derive(__g::serde::Serialize, __g::serde::Deserialize)
+)]
+#[cfg_attr(feature = "with_query", derive(__g::gel_derive::Queryable))]
+#[builder(crate_module_path = __g::typed_builder)]
+#[cfg_attr(feature = "with_query", gel(crate_path = __g::gel_protocol))]
+#[builder(field_defaults(default, setter(into, strip_option(fallback_suffix = "_opt"))))]
+pub struct GlobalsPatch {
+    pub client_token: Option<String>,
+    pub alternative: Option<String>,
+    pub current_user_id: Option<__g::uuid::Uuid>,
+    pub current_role: Option<default::Role>,
+    pub current_position: Option<default::Position>,
+}
+#[cfg(feature = "with_query")]
+impl __g::gel_tokio::GlobalsDelta for GlobalsPatch {
+    fn apply(self, modifier: &mut __g::gel_tokio::state::GlobalsModifier<'_>) {
+        if let Some(value) = self.client_token {
+            modifier.set("ext::auth::client_token", value);
+        }
+        if let Some(value) = self.alternative {
+            modifier.set("additional::alternative", value);
+        }
+        if let Some(value) = self.current_user_id {
+            modifier.set("default::current_user_id", value);
+        }
+        if let Some(value) = self.current_role {
+            modifier
+                .set(
+                    "default::current_role",
+                    __g::gel_protocol::value::Value::from(value),
+                );
+        }
+        if let Some(value) = self.current_position {
+            modifier
+                .set(
+                    "default::current_position",
+                    __g::gel_protocol::value::Value::from(value),
+                );
+        }
+    }
+}
+#[cfg(feature = "with_query")]
+impl GlobalsPatch {
+    /// Create a copy of an existing client with the provided globals.
+    pub fn apply_to(&self, client: &__g::gel_tokio::Client) -> __g::gel_tokio::Client {
+        client.with_globals(self.clone())
+    }
+}
+/// The configuration for a session. Only the settings which are
+/// provided are configured, the rest keep the values of the client they
+/// are applied to.
+#[derive(::core::default::Default)]
+#[derive(::std::fmt::Debug, ::core::clone::Clone, __g::typed_builder::TypedBuilder)]
+#[cfg_attr(
+    feature = "with_serde",
+    derive(__g::serde::Serialize, __g::serde::Deserialize)
+)]
+#[cfg_attr(feature = "with_query", derive(__g::gel_derive::Queryable))]
+#[builder(crate_module_path = __g::typed_builder)]
+#[cfg_attr(feature = "with_query", gel(crate_path = __g::gel_protocol))]
+#[builder(field_defaults(default, setter(into, strip_option(fallback_suffix = "_opt"))))]
+pub struct SessionConfig {
+    pub session_idle_transaction_timeout: Option<__g::gel_protocol::model::Duration>,
+    pub query_execution_timeout: Option<__g::gel_protocol::model::Duration>,
+    pub allow_bare_ddl: Option<session_config::AllowBareDdl>,
+    pub store_migration_sdl: Option<session_config::StoreMigrationSdl>,
+    pub apply_access_policies: Option<bool>,
+    pub apply_access_policies_pg: Option<bool>,
+    pub allow_user_specified_id: Option<bool>,
+    pub simple_scoping: Option<bool>,
+    pub warn_old_scoping: Option<bool>,
+    pub query_work_mem: Option<__g::gel_protocol::model::ConfigMemory>,
+    pub maintenance_work_mem: Option<__g::gel_protocol::model::ConfigMemory>,
+    pub track_query_stats: Option<session_config::QueryStatsOption>,
+    pub auth_auth_signing_key: Option<String>,
+    pub auth_token_time_to_live: Option<__g::gel_protocol::model::Duration>,
+}
+#[cfg(feature = "with_query")]
+impl __g::gel_tokio::ConfigDelta for SessionConfig {
+    fn apply(self, modifier: &mut __g::gel_tokio::state::ConfigModifier<'_>) {
+        if let Some(value) = self.session_idle_transaction_timeout {
+            modifier.set("session_idle_transaction_timeout", value);
+        }
+        if let Some(value) = self.query_execution_timeout {
+            modifier.set("query_execution_timeout", value);
+        }
+        if let Some(value) = self.allow_bare_ddl {
+            modifier.set("allow_bare_ddl", __g::gel_protocol::value::Value::from(value));
+        }
+        if let Some(value) = self.store_migration_sdl {
+            modifier
+                .set(
+                    "store_migration_sdl",
+                    __g::gel_protocol::value::Value::from(value),
+                );
+        }
+        if let Some(value) = self.apply_access_policies {
+            modifier.set("apply_access_policies", value);
+        }
+        if let Some(value) = self.apply_access_policies_pg {
+            modifier.set("apply_access_policies_pg", value);
+        }
+        if let Some(value) = self.allow_user_specified_id {
+            modifier.set("allow_user_specified_id", value);
+        }
+        if let Some(value) = self.simple_scoping {
+            modifier.set("simple_scoping", value);
+        }
+        if let Some(value) = self.warn_old_scoping {
+            modifier.set("warn_old_scoping", value);
+        }
+        if let Some(value) = self.query_work_mem {
+            modifier.set("query_work_mem", value);
+        }
+        if let Some(value) = self.maintenance_work_mem {
+            modifier.set("maintenance_work_mem", value);
+        }
+        if let Some(value) = self.track_query_stats {
+            modifier
+                .set("track_query_stats", __g::gel_protocol::value::Value::from(value));
+        }
+        if let Some(value) = self.auth_auth_signing_key {
+            modifier.set("ext::auth::AuthConfig::auth_signing_key", value);
+        }
+        if let Some(value) = self.auth_token_time_to_live {
+            modifier.set("ext::auth::AuthConfig::token_time_to_live", value);
+        }
+    }
+}
+#[cfg(feature = "with_query")]
+impl SessionConfig {
+    /// Create a copy of an existing client with the session configuration.
+    pub fn apply_to(&self, client: &__g::gel_tokio::Client) -> __g::gel_tokio::Client {
+        client.with_config(self.clone())
+    }
+}
+/// The enums used by the settings of the [`SessionConfig`].
+pub mod session_config {
+    use super::*;
+    #[derive(
+        ::std::fmt::Debug,
+        ::core::clone::Clone,
+        ::core::marker::Copy,
+        __g::strum::AsRefStr,
+        __g::strum::Display,
+        __g::strum::EnumString,
+        __g::strum::EnumIs,
+        __g::strum::FromRepr,
+        __g::strum::IntoStaticStr
+    )]
+    #[cfg_attr(
+        feature = "with_serde",
+        derive(__g::serde::Serialize, __g::serde::Deserialize)
+    )]
+    #[cfg_attr(feature = "with_query", derive(__g::gel_derive::Queryable))]
+    #[cfg_attr(feature = "with_query", gel(crate_path = __g::gel_protocol))]
+    #[strum(crate = "__g::strum")]
+    pub enum AllowBareDdl {
+        AlwaysAllow,
+        NeverAllow,
+    }
+    impl ::core::convert::From<AllowBareDdl> for __g::gel_protocol::value::Value {
+        fn from(value: AllowBareDdl) -> Self {
+            __g::gel_protocol::value::Value::Enum(value.as_ref().into())
+        }
+    }
+    #[derive(
+        ::std::fmt::Debug,
+        ::core::clone::Clone,
+        ::core::marker::Copy,
+        __g::strum::AsRefStr,
+        __g::strum::Display,
+        __g::strum::EnumString,
+        __g::strum::EnumIs,
+        __g::strum::FromRepr,
+        __g::strum::IntoStaticStr
+    )]
+    #[cfg_attr(
+        feature = "with_serde",
+        derive(__g::serde::Serialize, __g::serde::Deserialize)
+    )]
+    #[cfg_attr(feature = "with_query", derive(__g::gel_derive::Queryable))]
+    #[cfg_attr(feature = "with_query", gel(crate_path = __g::gel_protocol))]
+    #[strum(crate = "__g::strum")]
+    pub enum StoreMigrationSdl {
+        AlwaysStore,
+        NeverStore,
+    }
+    impl ::core::convert::From<StoreMigrationSdl> for __g::gel_protocol::value::Value {
+        fn from(value: StoreMigrationSdl) -> Self {
+            __g::gel_protocol::value::Value::Enum(value.as_ref().into())
+        }
+    }
+    #[derive(
+        ::std::fmt::Debug,
+        ::core::clone::Clone,
+        ::core::marker::Copy,
+        __g::strum::AsRefStr,
+        __g::strum::Display,
+        __g::strum::EnumString,
+        __g::strum::EnumIs,
+        __g::strum::FromRepr,
+        __g::strum::IntoStaticStr
+    )]
+    #[cfg_attr(
+        feature = "with_serde",
+        derive(__g::serde::Serialize, __g::serde::Deserialize)
+    )]
+    #[cfg_attr(feature = "with_query", derive(__g::gel_derive::Queryable))]
+    #[cfg_attr(feature = "with_query", gel(crate_path = __g::gel_protocol))]
+    #[strum(crate = "__g::strum")]
+    pub enum QueryStatsOption {
+        None,
+        All,
+    }
+    impl ::core::convert::From<QueryStatsOption> for __g::gel_protocol::value::Value {
+        fn from(value: QueryStatsOption) -> Self {
+            __g::gel_protocol::value::Value::Enum(value.as_ref().into())
+        }
+    }
+}
+pub mod global_current_user {
+    use ::gelx::exports as __g;
+    /// Execute the query with any executor, e.g. a client or a transaction.
+    #[cfg(feature = "with_query")]
+    pub async fn execute(
+        mut executor: impl __g::Executor,
+    ) -> ::core::result::Result<Option<Output>, __g::gel_errors::Error> {
+        executor.query_single(QUERY, &()).await
+    }
+    /// Execute the desired query.
+    #[cfg(feature = "with_query")]
+    pub async fn query(
+        client: &__g::gel_tokio::Client,
+    ) -> ::core::result::Result<Option<Output>, __g::gel_errors::Error> {
+        execute(&client.with_tag(Some(QUERY_TAG))?).await
+    }
+    /// Execute the query with the provided options, e.g. a timeout, a
+    /// retry policy or the options of the transaction which runs the query.
+    #[cfg(feature = "with_query")]
+    pub async fn query_with(
+        client: &__g::gel_tokio::Client,
+        options: __g::QueryOptions,
+    ) -> ::core::result::Result<Option<Output>, __g::gel_errors::Error> {
+        let client = options.apply_to(&client.with_tag(Some(QUERY_TAG))?);
+        if options.is_transaction() {
+            return client
+                .transaction(|mut conn| async move { execute(&mut *conn).await })
+                .await;
+        }
+        execute(&client).await
+    }
+    /// Compose the query as part of a larger transaction.
+    #[cfg(feature = "with_query")]
+    pub async fn transaction(
+        conn: &mut __g::gel_tokio::Transaction,
+    ) -> ::core::result::Result<Option<Output>, __g::gel_errors::Error> {
+        execute(conn).await
+    }
+    /// Execute the query and return the output as JSON rendered by the
+    /// database. A set of results is returned as a JSON array.
+    #[cfg(feature = "with_query")]
+    pub async fn query_json(
+        client: &__g::gel_tokio::Client,
+    ) -> ::core::result::Result<
+        Option<__g::gel_protocol::model::Json>,
+        __g::gel_errors::Error,
+    > {
+        client.with_tag(Some(QUERY_TAG))?.query_single_json(QUERY, &()).await
+    }
+    pub type Input = ();
+    #[derive(::std::fmt::Debug, ::core::clone::Clone)]
+    #[cfg_attr(
+        feature = "with_serde",
+        derive(__g::serde::Serialize, __g::serde::Deserialize)
+    )]
+    #[cfg_attr(feature = "with_query", derive(__g::gel_derive::Queryable))]
+    #[cfg_attr(feature = "with_query", gel(crate_path = __g::gel_protocol))]
+    pub struct Output {
+        pub slug: String,
+        pub id: __g::uuid::Uuid,
+        pub created_at: __g::DateTimeAlias,
+        pub updated_at: __g::DateTimeAlias,
+        pub bio: Option<String>,
+        pub name: Option<String>,
+    }
+    /// The tag which attributes the statistics of the query in `sys::QueryStats`.
+    pub const QUERY_TAG: &str = "global_current_user";
+    /// The capabilities of the query reported by the database.
+    pub const CAPABILITIES: __g::Capabilities = __g::Capabilities::from_bits_retain(0);
+    /// The original query string provided to the macro. Can be reused in your codebase.
+    pub const QUERY: &str = "select (global default::current_user) { * };";
+    /// The hash of the query which identifies it in the `queries.lock.json` manifest.
+    pub const QUERY_HASH: &str = "55ffa7d576d8269b569139541d4128a2c4ea32bbdecfcc493c267342b8706323";
 }
 pub mod auth_allowed_redirect_urls {
     use ::gelx::exports as __g;
+    /// This query is used to set the allowed redirect URLs for the auth system. Unfortunately,
+    /// `configure` can't be used with parameters.
+    ///
+    /// `non-constant expression in CONFIGURE DATABASE SET`
+    ///
+    /// Execute the query with any executor, e.g. a client or a transaction.
+    #[cfg(feature = "with_query")]
+    pub async fn execute(
+        mut executor: impl __g::Modifying,
+    ) -> ::core::result::Result<(), __g::gel_errors::Error> {
+        executor.execute(QUERY, &()).await
+    }
+    /// This query is used to set the allowed redirect URLs for the auth system. Unfortunately,
+    /// `configure` can't be used with parameters.
+    ///
+    /// `non-constant expression in CONFIGURE DATABASE SET`
+    ///
     /// Execute the desired query.
     #[cfg(feature = "with_query")]
     pub async fn query(
         client: &__g::gel_tokio::Client,
     ) -> ::core::result::Result<(), __g::gel_errors::Error> {
-        client.execute(QUERY, &()).await
+        execute(&client.with_tag(Some(QUERY_TAG))?).await
+    }
+    /// This query is used to set the allowed redirect URLs for the auth system. Unfortunately,
+    /// `configure` can't be used with parameters.
+    ///
+    /// `non-constant expression in CONFIGURE DATABASE SET`
+    ///
+    /// Execute the query with the provided options, e.g. a timeout, a
+    /// retry policy or the options of the transaction which runs the query.
+    #[cfg(feature = "with_query")]
+    pub async fn query_with(
+        client: &__g::gel_tokio::Client,
+        options: __g::QueryOptions,
+    ) -> ::core::result::Result<(), __g::gel_errors::Error> {
+        let client = options.apply_to(&client.with_tag(Some(QUERY_TAG))?);
+        if options.is_transaction() {
+            return client
+                .transaction(|mut conn| async move { execute(&mut *conn).await })
+                .await;
+        }
+        execute(&client).await
     }
+    /// This query is used to set the allowed redirect URLs for the auth system. Unfortunately,
+    /// `configure` can't be used with parameters.
+    ///
+    /// `non-constant expression in CONFIGURE DATABASE SET`
+    ///
     /// Compose the query as part of a larger transaction.
     #[cfg(feature = "with_query")]
     pub async fn transaction(
         conn: &mut __g::gel_tokio::Transaction,
     ) -> ::core::result::Result<(), __g::gel_errors::Error> {
-        conn.execute(QUERY, &()).await
+        execute(conn).await
     }
     pub type Input = ();
     pub type Output = ();
+    /// The tag which attributes the statistics of the query in `sys::QueryStats`.
+    pub const QUERY_TAG: &str = "auth_allowed_redirect_urls";
+    /// The capabilities of the query reported by the database.
+    pub const CAPABILITIES: __g::Capabilities = __g::Capabilities::from_bits_retain(16);
+    /// This query is used to set the allowed redirect URLs for the auth system. Unfortunately,
+    /// `configure` can't be used with parameters.
+    ///
+    /// `non-constant expression in CONFIGURE DATABASE SET`
+    ///
     /// The original query string provided to the macro. Can be reused in your codebase.
     pub const QUERY: &str = "# This query is used to set the allowed redirect URLs for the auth system. Unfortunately,\n# `configure` can't be used with parameters.\n#\n# `non-constant expression in CONFIGURE DATABASE SET`\nconfigure current branch set ext::auth::AuthConfig::allowed_redirect_urls := {\n    'https://example.com',\n    'https://example.com/auth',\n    'https://localhost:3000',\n    'https://localhost:3000/auth'\n};\n";
+    /// The hash of the query which identifies it in the `queries.lock.json` manifest.
+    pub const QUERY_HASH: &str = "a7d45aae28e2759e70b3195fed87c4974a9504299d9db573f1cde01fe108d74e";
 }
 pub mod insert_location {
     use ::gelx::exports as __g;
+    /// Execute the query with any executor, e.g. a client or a transaction.
+    #[cfg(feature = "with_query")]
+    pub async fn execute(
+        mut executor: impl __g::Modifying,
+        props: &Input,
+    ) -> ::core::result::Result<Output, __g::gel_errors::Error> {
+        executor.query_required_single(QUERY, props).await
+    }
     /// Execute the desired query.
     #[cfg(feature = "with_query")]
     pub async fn query(
         client: &__g::gel_tokio::Client,
         props: &Input,
     ) -> ::core::result::Result<Output, __g::gel_errors::Error> {
-        client.query_required_single(QUERY, props).await
+        query_with(client, props, __g::QueryOptions::new()).await
+    }
+    /// Execute the query with the provided options, e.g. a timeout, a
+    /// retry policy or the options of the transaction which runs the query.
+    #[cfg(feature = "with_query")]
+    pub async fn query_with(
+        client: &__g::gel_tokio::Client,
+        props: &Input,
+        options: __g::QueryOptions,
+    ) -> ::core::result::Result<Output, __g::gel_errors::Error> {
+        let options = options.or(__g::QueryOptions::new().retry_attempts(1u32));
+        let client = options.apply_to(&client.with_tag(Some(QUERY_TAG))?);
+        if options.is_transaction() {
+            return client
+                .transaction(|mut conn| async move { execute(&mut *conn, props).await })
+                .await;
+        }
+        execute(&client, props).await
     }
     /// Compose the query as part of a larger transaction.
     #[cfg(feature = "with_query")]
@@ -87,7 +493,16 @@ pub mod insert_location {
         conn: &mut __g::gel_tokio::Transaction,
         props: &Input,
     ) -> ::core::result::Result<Output, __g::gel_errors::Error> {
-        conn.query_required_single(QUERY, props).await
+        execute(conn, props).await
+    }
+    /// Execute the query and return the output as JSON rendered by the
+    /// database. A set of results is returned as a JSON array.
+    #[cfg(feature = "with_query")]
+    pub async fn query_json(
+        client: &__g::gel_tokio::Client,
+        props: &Input,
+    ) -> ::core::result::Result<__g::gel_protocol::model::Json, __g::gel_errors::Error> {
+        client.with_tag(Some(QUERY_TAG))?.query_required_single_json(QUERY, props).await
     }
     #[derive(::std::fmt::Debug, ::core::clone::Clone, __g::typed_builder::TypedBuilder)]
     #[cfg_attr(
@@ -125,18 +540,49 @@ pub mod insert_location {
         pub point: __g::Geometry,
         pub area: __g::Geography,
     }
+    /// The tag which attributes the statistics of the query in `sys::QueryStats`.
+    pub const QUERY_TAG: &str = "insert_location";
+    /// The capabilities of the query reported by the database.
+    pub const CAPABILITIES: __g::Capabilities = __g::Capabilities::from_bits_retain(1);
     /// The original query string provided to the macro. Can be reused in your codebase.
     pub const QUERY: &str = "with NewLocation := (insert Location {\n\tpoint := <ext::postgis::geometry>$point,\n\tarea := <ext::postgis::geography>$area,\n})\nselect NewLocation {\n\tpoint,\n\tarea,\n};";
+    /// The hash of the query which identifies it in the `queries.lock.json` manifest.
+    pub const QUERY_HASH: &str = "4d7a443c303251db2d4542bfa375d679f7a456fba1840a7b315b155388f30f65";
 }
 pub mod insert_position {
     use ::gelx::exports as __g;
+    /// Execute the query with any executor, e.g. a client or a transaction.
+    #[cfg(feature = "with_query")]
+    pub async fn execute(
+        mut executor: impl __g::Modifying,
+        props: &Input,
+    ) -> ::core::result::Result<Output, __g::gel_errors::Error> {
+        executor.query_required_single(QUERY, props).await
+    }
     /// Execute the desired query.
     #[cfg(feature = "with_query")]
     pub async fn query(
         client: &__g::gel_tokio::Client,
         props: &Input,
     ) -> ::core::result::Result<Output, __g::gel_errors::Error> {
-        client.query_required_single(QUERY, props).await
+        query_with(client, props, __g::QueryOptions::new()).await
+    }
+    /// Execute the query with the provided options, e.g. a timeout, a
+    /// retry policy or the options of the transaction which runs the query.
+    #[cfg(feature = "with_query")]
+    pub async fn query_with(
+        client: &__g::gel_tokio::Client,
+        props: &Input,
+        options: __g::QueryOptions,
+    ) -> ::core::result::Result<Output, __g::gel_errors::Error> {
+        let options = options.or(__g::QueryOptions::new().retry_attempts(1u32));
+        let client = options.apply_to(&client.with_tag(Some(QUERY_TAG))?);
+        if options.is_transaction() {
+            return client
+                .transaction(|mut conn| async move { execute(&mut *conn, props).await })
+                .await;
+        }
+        execute(&client, props).await
     }
     /// Compose the query as part of a larger transaction.
     #[cfg(feature = "with_query")]
@@ -144,7 +590,16 @@ pub mod insert_position {
         conn: &mut __g::gel_tokio::Transaction,
         props: &Input,
     ) -> ::core::result::Result<Output, __g::gel_errors::Error> {
-        conn.query_required_single(QUERY, props).await
+        execute(conn, props).await
+    }
+    /// Execute the query and return the output as JSON rendered by the
+    /// database. A set of results is returned as a JSON array.
+    #[cfg(feature = "with_query")]
+    pub async fn query_json(
+        client: &__g::gel_tokio::Client,
+        props: &Input,
+    ) -> ::core::result::Result<__g::gel_protocol::model::Json, __g::gel_errors::Error> {
+        client.with_tag(Some(QUERY_TAG))?.query_required_single_json(QUERY, props).await
     }
     #[derive(::std::fmt::Debug, ::core::clone::Clone, __g::typed_builder::TypedBuilder)]
     #[cfg_attr(
@@ -180,18 +635,57 @@ pub mod insert_position {
         pub id: __g::uuid::Uuid,
         pub position: super::default::Position,
     }
+    impl __g::validate::Validate for Input {
+        fn validate(
+            &self,
+        ) -> ::core::result::Result<(), __g::validate::ValidationError> {
+            __g::validate::Validate::validate(&self.position)?;
+            Ok(())
+        }
+    }
+    /// The tag which attributes the statistics of the query in `sys::QueryStats`.
+    pub const QUERY_TAG: &str = "insert_position";
+    /// The capabilities of the query reported by the database.
+    pub const CAPABILITIES: __g::Capabilities = __g::Capabilities::from_bits_retain(1);
     /// The original query string provided to the macro. Can be reused in your codebase.
     pub const QUERY: &str = "select (insert Simple {\n  position := <default::Position>$position,\n}) {**};\n";
+    /// The hash of the query which identifies it in the `queries.lock.json` manifest.
+    pub const QUERY_HASH: &str = "f9acb24be1c16695cf68bd3ab2372e47499327006b124bf1f822f515ae1ff24b";
 }
 pub mod insert_user {
     use ::gelx::exports as __g;
+    /// Execute the query with any executor, e.g. a client or a transaction.
+    #[cfg(feature = "with_query")]
+    pub async fn execute(
+        mut executor: impl __g::Modifying,
+        props: &Input,
+    ) -> ::core::result::Result<Output, __g::gel_errors::Error> {
+        executor.query_required_single(QUERY, props).await
+    }
     /// Execute the desired query.
     #[cfg(feature = "with_query")]
     pub async fn query(
         client: &__g::gel_tokio::Client,
         props: &Input,
     ) -> ::core::result::Result<Output, __g::gel_errors::Error> {
-        client.query_required_single(QUERY, props).await
+        query_with(client, props, __g::QueryOptions::new()).await
+    }
+    /// Execute the query with the provided options, e.g. a timeout, a
+    /// retry policy or the options of the transaction which runs the query.
+    #[cfg(feature = "with_query")]
+    pub async fn query_with(
+        client: &__g::gel_tokio::Client,
+        props: &Input,
+        options: __g::QueryOptions,
+    ) -> ::core::result::Result<Output, __g::gel_errors::Error> {
+        let options = options.or(__g::QueryOptions::new().retry_attempts(1u32));
+        let client = options.apply_to(&client.with_tag(Some(QUERY_TAG))?);
+        if options.is_transaction() {
+            return client
+                .transaction(|mut conn| async move { execute(&mut *conn, props).await })
+                .await;
+        }
+        execute(&client, props).await
     }
     /// Compose the query as part of a larger transaction.
     #[cfg(feature = "with_query")]
@@ -199,8 +693,58 @@ pub mod insert_user {
         conn: &mut __g::gel_tokio::Transaction,
         props: &Input,
     ) -> ::core::result::Result<Output, __g::gel_errors::Error> {
-        conn.query_required_single(QUERY, props).await
+        execute(conn, props).await
+    }
+    /// Execute the query and return the output as JSON rendered by the
+    /// database. A set of results is returned as a JSON array.
+    #[cfg(feature = "with_query")]
+    pub async fn query_json(
+        client: &__g::gel_tokio::Client,
+        props: &Input,
+    ) -> ::core::result::Result<__g::gel_protocol::model::Json, __g::gel_errors::Error> {
+        client.with_tag(Some(QUERY_TAG))?.query_required_single_json(QUERY, props).await
     }
+    /// Execute the query once for every input. The inputs are sent in chunks of
+    /// `BULK_CHUNK_SIZE` with a single round trip for each chunk.
+    #[cfg(feature = "with_query")]
+    #[cfg(feature = "with_serde")]
+    pub async fn execute_bulk(
+        mut executor: impl __g::Modifying,
+        props: &[Input],
+    ) -> ::core::result::Result<Vec<Output>, __g::gel_errors::Error> {
+        let mut outputs = Vec::with_capacity(props.len());
+        for chunk in props.chunks(BULK_CHUNK_SIZE) {
+            let mut items = __g::bulk::BulkItems::with_capacity(chunk.len());
+            for props in chunk {
+                items
+                    .push([
+                        ("name", __g::bulk::to_value(&props.name)?),
+                        ("bio", __g::bulk::to_value(&props.bio)?),
+                        ("slug", __g::bulk::to_value(&props.slug)?),
+                    ]);
+            }
+            outputs.extend(executor.query::<Output, _>(QUERY_BULK, &items).await?);
+        }
+        Ok(outputs)
+    }
+    /// Execute the query once for every input. All the chunks run in a single
+    /// transaction so either every input or none of them is applied.
+    #[cfg(feature = "with_query")]
+    #[cfg(feature = "with_serde")]
+    pub async fn query_bulk(
+        client: &__g::gel_tokio::Client,
+        props: &[Input],
+    ) -> ::core::result::Result<Vec<Output>, __g::gel_errors::Error> {
+        client
+            .with_tag(Some(QUERY_TAG))?
+            .transaction(|mut conn| async move { execute_bulk(&mut *conn, props).await })
+            .await
+    }
+    /// The maximum number of inputs sent with each bulk query.
+    pub const BULK_CHUNK_SIZE: usize = 1000usize;
+    /// The query which runs the original query for every item of the `$items`
+    /// JSON array.
+    pub const QUERY_BULK: &str = "for item in json_array_unpack(<json>$items) union (\nselect (insert User {\n  name := <str>item['name'],\n  bio := <str>item['bio'],\n  slug := <str>item['slug'],\n}) {\n  id,\n  name,\n  bio,\n  slug,\n}\n);";
     #[derive(::std::fmt::Debug, ::core::clone::Clone, __g::typed_builder::TypedBuilder)]
     #[cfg_attr(
         feature = "with_serde",
@@ -242,18 +786,61 @@ pub mod insert_user {
         pub bio: Option<String>,
         pub slug: String,
     }
+    impl __g::validate::Validate for Input {
+        fn validate(
+            &self,
+        ) -> ::core::result::Result<(), __g::validate::ValidationError> {
+            __g::validate::regexp(
+                "default::Actor.slug",
+                &self.slug,
+                "^(?=.{3,39}$)(?![_.-])(?!.*[_.-]{2})[a-zA-Z0-9._-]+(?<![_.-])$",
+            )?;
+            Ok(())
+        }
+    }
+    /// The tag which attributes the statistics of the query in `sys::QueryStats`.
+    pub const QUERY_TAG: &str = "insert_user";
+    /// The capabilities of the query reported by the database.
+    pub const CAPABILITIES: __g::Capabilities = __g::Capabilities::from_bits_retain(1);
     /// The original query string provided to the macro. Can be reused in your codebase.
     pub const QUERY: &str = "select (insert User {\n  name := <str>$name,\n  bio := <str>$bio,\n  slug := <str>$slug,\n}) {\n  id,\n  name,\n  bio,\n  slug,\n};\n";
+    /// The hash of the query which identifies it in the `queries.lock.json` manifest.
+    pub const QUERY_HASH: &str = "6b974ba78cd49822056667be41167114fcfb45db9b0ab3b6eee1ca9812ab16c0";
 }
 pub mod remove_user {
     use ::gelx::exports as __g;
+    /// Execute the query with any executor, e.g. a client or a transaction.
+    #[cfg(feature = "with_query")]
+    pub async fn execute(
+        mut executor: impl __g::Modifying,
+        props: &Input,
+    ) -> ::core::result::Result<Option<Output>, __g::gel_errors::Error> {
+        executor.query_single(QUERY, props).await
+    }
     /// Execute the desired query.
     #[cfg(feature = "with_query")]
     pub async fn query(
         client: &__g::gel_tokio::Client,
         props: &Input,
     ) -> ::core::result::Result<Option<Output>, __g::gel_errors::Error> {
-        client.query_single(QUERY, props).await
+        query_with(client, props, __g::QueryOptions::new()).await
+    }
+    /// Execute the query with the provided options, e.g. a timeout, a
+    /// retry policy or the options of the transaction which runs the query.
+    #[cfg(feature = "with_query")]
+    pub async fn query_with(
+        client: &__g::gel_tokio::Client,
+        props: &Input,
+        options: __g::QueryOptions,
+    ) -> ::core::result::Result<Option<Output>, __g::gel_errors::Error> {
+        let options = options.or(__g::QueryOptions::new().retry_attempts(1u32));
+        let client = options.apply_to(&client.with_tag(Some(QUERY_TAG))?);
+        if options.is_transaction() {
+            return client
+                .transaction(|mut conn| async move { execute(&mut *conn, props).await })
+                .await;
+        }
+        execute(&client, props).await
     }
     /// Compose the query as part of a larger transaction.
     #[cfg(feature = "with_query")]
@@ -261,7 +848,19 @@ pub mod remove_user {
         conn: &mut __g::gel_tokio::Transaction,
         props: &Input,
     ) -> ::core::result::Result<Option<Output>, __g::gel_errors::Error> {
-        conn.query_single(QUERY, props).await
+        execute(conn, props).await
+    }
+    /// Execute the query and return the output as JSON rendered by the
+    /// database. A set of results is returned as a JSON array.
+    #[cfg(feature = "with_query")]
+    pub async fn query_json(
+        client: &__g::gel_tokio::Client,
+        props: &Input,
+    ) -> ::core::result::Result<
+        Option<__g::gel_protocol::model::Json>,
+        __g::gel_errors::Error,
+    > {
+        client.with_tag(Some(QUERY_TAG))?.query_single_json(QUERY, props).await
     }
     #[derive(::std::fmt::Debug, ::core::clone::Clone, __g::typed_builder::TypedBuilder)]
     #[cfg_attr(
@@ -296,18 +895,48 @@ pub mod remove_user {
     pub struct Output {
         pub id: __g::uuid::Uuid,
     }
+    /// The tag which attributes the statistics of the query in `sys::QueryStats`.
+    pub const QUERY_TAG: &str = "remove_user";
+    /// The capabilities of the query reported by the database.
+    pub const CAPABILITIES: __g::Capabilities = __g::Capabilities::from_bits_retain(1);
     /// The original query string provided to the macro. Can be reused in your codebase.
     pub const QUERY: &str = "delete User filter .id = <uuid>$id;\n";
+    /// The hash of the query which identifies it in the `queries.lock.json` manifest.
+    pub const QUERY_HASH: &str = "0d2e12c893f4a389ecbbd8a55fde7d015173e7eba484afd7dc48a2c57fb2c83e";
 }
 pub mod select_accounts {
     use ::gelx::exports as __g;
+    /// Execute the query with any executor, e.g. a client or a transaction.
+    #[cfg(feature = "with_query")]
+    pub async fn execute(
+        mut executor: impl __g::Executor,
+        props: &Input,
+    ) -> ::core::result::Result<Vec<Output>, __g::gel_errors::Error> {
+        executor.query(QUERY, props).await
+    }
     /// Execute the desired query.
     #[cfg(feature = "with_query")]
     pub async fn query(
         client: &__g::gel_tokio::Client,
         props: &Input,
     ) -> ::core::result::Result<Vec<Output>, __g::gel_errors::Error> {
-        client.query(QUERY, props).await
+        execute(&client.with_tag(Some(QUERY_TAG))?, props).await
+    }
+    /// Execute the query with the provided options, e.g. a timeout, a
+    /// retry policy or the options of the transaction which runs the query.
+    #[cfg(feature = "with_query")]
+    pub async fn query_with(
+        client: &__g::gel_tokio::Client,
+        props: &Input,
+        options: __g::QueryOptions,
+    ) -> ::core::result::Result<Vec<Output>, __g::gel_errors::Error> {
+        let client = options.apply_to(&client.with_tag(Some(QUERY_TAG))?);
+        if options.is_transaction() {
+            return client
+                .transaction(|mut conn| async move { execute(&mut *conn, props).await })
+                .await;
+        }
+        execute(&client, props).await
     }
     /// Compose the query as part of a larger transaction.
     #[cfg(feature = "with_query")]
@@ -315,7 +944,42 @@ pub mod select_accounts {
         conn: &mut __g::gel_tokio::Transaction,
         props: &Input,
     ) -> ::core::result::Result<Vec<Output>, __g::gel_errors::Error> {
-        conn.query(QUERY, props).await
+        execute(conn, props).await
+    }
+    /// Stream the results of the query without buffering them in memory. The
+    /// results are fetched in pages of `page_size` rows ordered by `id`.
+    #[cfg(feature = "with_query")]
+    pub fn query_stream<'a>(
+        client: &'a __g::gel_tokio::Client,
+        props: &'a Input,
+        page_size: i64,
+    ) -> impl __g::futures::Stream<
+        Item = ::core::result::Result<Output, __g::gel_errors::Error>,
+    > + 'a {
+        __g::stream::keyset(
+            page_size,
+            |output: &Output| output.id,
+            move |cursor, limit| {
+                async move {
+                    let args = __g::gel_protocol::named_args! {
+                        "provider" => props.provider.clone(), "gelx_cursor" => cursor,
+                        "gelx_limit" => limit,
+                    };
+                    client.with_tag(Some(QUERY_TAG))?.query(QUERY_STREAM, &args).await
+                }
+            },
+        )
+    }
+    /// The query used to fetch each page of results for the stream.
+    pub const QUERY_STREAM: &str = "with\n\tgelx_cursor := <optional uuid>$gelx_cursor,\n\tgelx_rows := (\nselect Account {**} filter .provider = <AccountProvider>$provider\n\t)\nselect gelx_rows\nfilter not exists gelx_cursor or .id > gelx_cursor\norder by .id\nlimit <int64>$gelx_limit;";
+    /// Execute the query and return the output as JSON rendered by the
+    /// database. A set of results is returned as a JSON array.
+    #[cfg(feature = "with_query")]
+    pub async fn query_json(
+        client: &__g::gel_tokio::Client,
+        props: &Input,
+    ) -> ::core::result::Result<__g::gel_protocol::model::Json, __g::gel_errors::Error> {
+        client.with_tag(Some(QUERY_TAG))?.query_json(QUERY, props).await
     }
     #[derive(::std::fmt::Debug, ::core::clone::Clone, __g::typed_builder::TypedBuilder)]
     #[cfg_attr(
@@ -376,18 +1040,48 @@ pub mod select_accounts {
         pub username: Option<String>,
         pub user: OutputUser,
     }
+    /// The tag which attributes the statistics of the query in `sys::QueryStats`.
+    pub const QUERY_TAG: &str = "select_accounts";
+    /// The capabilities of the query reported by the database.
+    pub const CAPABILITIES: __g::Capabilities = __g::Capabilities::from_bits_retain(0);
     /// The original query string provided to the macro. Can be reused in your codebase.
     pub const QUERY: &str = "select Account {**} filter .provider = <AccountProvider>$provider;";
+    /// The hash of the query which identifies it in the `queries.lock.json` manifest.
+    pub const QUERY_HASH: &str = "a191752e2b1f2fc1d691b48ba3fe35480cf91852c47235ff6fb9d54e9e8ff992";
 }
 pub mod select_test_user {
     use ::gelx::exports as __g;
+    /// Execute the query with any executor, e.g. a client or a transaction.
+    #[cfg(feature = "with_query")]
+    pub async fn execute(
+        mut executor: impl __g::Executor,
+        props: &Input,
+    ) -> ::core::result::Result<Option<Output>, __g::gel_errors::Error> {
+        executor.query_single(QUERY, props).await
+    }
     /// Execute the desired query.
     #[cfg(feature = "with_query")]
     pub async fn query(
         client: &__g::gel_tokio::Client,
         props: &Input,
     ) -> ::core::result::Result<Option<Output>, __g::gel_errors::Error> {
-        client.query_single(QUERY, props).await
+        execute(&client.with_tag(Some(QUERY_TAG))?, props).await
+    }
+    /// Execute the query with the provided options, e.g. a timeout, a
+    /// retry policy or the options of the transaction which runs the query.
+    #[cfg(feature = "with_query")]
+    pub async fn query_with(
+        client: &__g::gel_tokio::Client,
+        props: &Input,
+        options: __g::QueryOptions,
+    ) -> ::core::result::Result<Option<Output>, __g::gel_errors::Error> {
+        let client = options.apply_to(&client.with_tag(Some(QUERY_TAG))?);
+        if options.is_transaction() {
+            return client
+                .transaction(|mut conn| async move { execute(&mut *conn, props).await })
+                .await;
+        }
+        execute(&client, props).await
     }
     /// Compose the query as part of a larger transaction.
     #[cfg(feature = "with_query")]
@@ -395,7 +1089,19 @@ pub mod select_test_user {
         conn: &mut __g::gel_tokio::Transaction,
         props: &Input,
     ) -> ::core::result::Result<Option<Output>, __g::gel_errors::Error> {
-        conn.query_single(QUERY, props).await
+        execute(conn, props).await
+    }
+    /// Execute the query and return the output as JSON rendered by the
+    /// database. A set of results is returned as a JSON array.
+    #[cfg(feature = "with_query")]
+    pub async fn query_json(
+        client: &__g::gel_tokio::Client,
+        props: &Input,
+    ) -> ::core::result::Result<
+        Option<__g::gel_protocol::model::Json>,
+        __g::gel_errors::Error,
+    > {
+        client.with_tag(Some(QUERY_TAG))?.query_single_json(QUERY, props).await
     }
     #[derive(::std::fmt::Debug, ::core::clone::Clone, __g::typed_builder::TypedBuilder)]
     #[cfg_attr(
@@ -431,18 +1137,48 @@ pub mod select_test_user {
         pub id: __g::uuid::Uuid,
         pub public_id: super::default::UserId,
     }
+    /// The tag which attributes the statistics of the query in `sys::QueryStats`.
+    pub const QUERY_TAG: &str = "select_test_user";
+    /// The capabilities of the query reported by the database.
+    pub const CAPABILITIES: __g::Capabilities = __g::Capabilities::from_bits_retain(0);
     /// The original query string provided to the macro. Can be reused in your codebase.
     pub const QUERY: &str = "select assert_single((\n\tselect TestUser { id, public_id } filter .active and .namelc = str_lower(<str>$username)\n))\n";
+    /// The hash of the query which identifies it in the `queries.lock.json` manifest.
+    pub const QUERY_HASH: &str = "b1553f279513dbd54687b2be714efb3b36c596a465bda0eaca07c020368e970a";
 }
 pub mod select_user {
     use ::gelx::exports as __g;
+    /// Execute the query with any executor, e.g. a client or a transaction.
+    #[cfg(feature = "with_query")]
+    pub async fn execute(
+        mut executor: impl __g::Executor,
+        props: &Input,
+    ) -> ::core::result::Result<Option<Output>, __g::gel_errors::Error> {
+        executor.query_single(QUERY, props).await
+    }
     /// Execute the desired query.
     #[cfg(feature = "with_query")]
     pub async fn query(
         client: &__g::gel_tokio::Client,
         props: &Input,
     ) -> ::core::result::Result<Option<Output>, __g::gel_errors::Error> {
-        client.query_single(QUERY, props).await
+        execute(&client.with_tag(Some(QUERY_TAG))?, props).await
+    }
+    /// Execute the query with the provided options, e.g. a timeout, a
+    /// retry policy or the options of the transaction which runs the query.
+    #[cfg(feature = "with_query")]
+    pub async fn query_with(
+        client: &__g::gel_tokio::Client,
+        props: &Input,
+        options: __g::QueryOptions,
+    ) -> ::core::result::Result<Option<Output>, __g::gel_errors::Error> {
+        let client = options.apply_to(&client.with_tag(Some(QUERY_TAG))?);
+        if options.is_transaction() {
+            return client
+                .transaction(|mut conn| async move { execute(&mut *conn, props).await })
+                .await;
+        }
+        execute(&client, props).await
     }
     /// Compose the query as part of a larger transaction.
     #[cfg(feature = "with_query")]
@@ -450,7 +1186,19 @@ pub mod select_user {
         conn: &mut __g::gel_tokio::Transaction,
         props: &Input,
     ) -> ::core::result::Result<Option<Output>, __g::gel_errors::Error> {
-        conn.query_single(QUERY, props).await
+        execute(conn, props).await
+    }
+    /// Execute the query and return the output as JSON rendered by the
+    /// database. A set of results is returned as a JSON array.
+    #[cfg(feature = "with_query")]
+    pub async fn query_json(
+        client: &__g::gel_tokio::Client,
+        props: &Input,
+    ) -> ::core::result::Result<
+        Option<__g::gel_protocol::model::Json>,
+        __g::gel_errors::Error,
+    > {
+        client.with_tag(Some(QUERY_TAG))?.query_single_json(QUERY, props).await
     }
     #[derive(::std::fmt::Debug, ::core::clone::Clone, __g::typed_builder::TypedBuilder)]
     #[cfg_attr(
@@ -488,6 +1236,12 @@ pub mod select_user {
         pub bio: Option<String>,
         pub slug: String,
     }
+    /// The tag which attributes the statistics of the query in `sys::QueryStats`.
+    pub const QUERY_TAG: &str = "select_user";
+    /// The capabilities of the query reported by the database.
+    pub const CAPABILITIES: __g::Capabilities = __g::Capabilities::from_bits_retain(0);
     /// The original query string provided to the macro. Can be reused in your codebase.
     pub const QUERY: &str = "select User {\n\tid,\n  name,\n  bio,\n  slug,\n} filter .slug = <str>$slug;";
+    /// The hash of the query which identifies it in the `queries.lock.json` manifest.
+    pub const QUERY_HASH: &str = "7c2bc546cede3eae5a470226c9ab96415d1063d68b76644226af9d5c011032a9";
 }